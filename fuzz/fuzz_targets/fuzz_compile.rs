@@ -30,7 +30,7 @@
     // Load the input file into our source manager
     source_manager.add_file(INPUT_FILE, data);
 
-    let Some(source_file) = source_manager.load_file(INPUT_FILE) else {
+    let Ok(source_file) = source_manager.load_file(INPUT_FILE) else {
         return Corpus::Reject;
     };
 
@@ -40,11 +40,11 @@
 
     // Parse
     let mut parser = Parser::new(diagnostic_engine.clone(), tokens);
-    let translation_unit = parser.parse();
+    let translation_unit = parser.parse().translation_unit;
 
     // Codegen
     let codegen = Codegen::new(INPUT_FILE);
-    codegen.codegen(&translation_unit);
+    let _ = codegen.codegen(&translation_unit);
 
     Corpus::Keep
 });
@@ -3,21 +3,46 @@
 // TODO: Should the translation unit have a file name field?
 
 #[derive(Debug, Clone, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TranslationUnit<'a> {
+    pub declarations: Vec<FunctionDeclaration<'a>>,
     pub function: Vec<FunctionDefinition<'a>>,
 }
 
 impl TranslationUnit<'_> {
     pub fn new() -> Self {
         Self {
+            declarations: Vec::new(),
             function: Vec::new(),
         }
     }
 
+    /// Dumps the scopes of every function definition and the variables declared in each of them.
+    /// Every block opens a scope nested in the enclosing one.
+    pub fn dump_scopes(&self) -> String {
+        let mut result = String::new();
+
+        for function in &self.function {
+            result.push_str(&format!(
+                "Function \"{}\" {}\n",
+                function.name,
+                ast_source_range_to_string(&function.range)
+            ));
+            function.body.dump_scopes(1, &mut result);
+        }
+
+        result
+    }
+
     pub fn dump(&self) -> String {
         let mut result = String::new();
         result.push_str("TranslationUnit\n");
 
+        // Dump all function declarations
+        for declaration in &self.declarations {
+            result.push_str(&declaration.dump(1));
+        }
+
         // Dump all function definitions
         for function in &self.function {
             result.push_str(&function.dump(1));
@@ -27,37 +52,147 @@ pub fn dump(&self) -> String {
     }
 }
 
+/// The types a value can have
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Type {
+    #[default]
+    Int,
+    /// C99's `_Bool`, which only holds 0 or 1. Any other value converted to it becomes 1.
+    Bool,
+}
+
+impl Type {
+    /// The spelling of the type in C source code
+    pub fn name(&self) -> &'static str {
+        match self {
+            Type::Int => "int",
+            Type::Bool => "_Bool",
+        }
+    }
+}
+
+/// The type qualifiers of a declaration like `volatile int`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Qualifiers {
+    /// Every access has side effects, so it may neither be removed nor merged with another access
+    pub is_volatile: bool,
+    /// The pointed to object is only accessed through this pointer, only valid for pointer types
+    pub is_restrict: bool,
+}
+
+/// The storage-class specifier of a local declaration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StorageClass {
+    /// `auto` or no specifier at all, the variable lives until the end of its block
+    #[default]
+    Auto,
+    /// `register`, a hint to keep the variable in a register which the code generator ignores
+    Register,
+}
+
+/// A function declaration without a body like `int f(void);` or `extern int puts(void);`, which is
+/// resolved at link time unless the function is defined later
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionDeclaration<'a> {
+    pub name: String,
+    pub return_type: Type,
+    pub is_extern: bool,
+    pub has_prototype: bool,
+    /// From the start of the declaration up to the semicolon
+    pub range: SourceRange<'a>,
+}
+
+impl FunctionDeclaration<'_> {
+    pub fn dump(&self, depth: usize) -> String {
+        format!(
+            "{}FunctionDeclaration \"{}\"{}{}\n",
+            "  ".repeat(depth),
+            self.name,
+            return_type_suffix(self.return_type),
+            if self.is_extern { " extern" } else { "" }
+        )
+    }
+}
+
 #[derive(Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionDefinition<'a> {
     pub name: String,
+    pub return_type: Type,
     pub body: Statement<'a>,
-    // TODO: Source Ranges for the function definition
+    /// False for an empty parameter list like `int main()`, which leaves the parameters unspecified
+    pub has_prototype: bool,
+    /// Whether the definition starts with `extern`, which has no effect since it's the default
+    pub is_extern: bool,
+    /// From the start of the return type up to the closing brace of the body
+    pub range: SourceRange<'a>,
 }
 
 impl<'a> FunctionDefinition<'a> {
     pub fn new<S: Into<String>>(name: S, body: Statement<'a>) -> Self {
         Self {
             name: name.into(),
+            return_type: Type::Int,
+            range: body.range,
             body,
+            has_prototype: true,
+            is_extern: false,
         }
     }
 
     pub fn dump(&self, depth: usize) -> String {
         format!(
-            "{}FunctionDefinition \"{}\"\n{}",
+            "{}FunctionDefinition \"{}\"{}{} {}\n{}",
             "  ".repeat(depth),
             self.name,
+            return_type_suffix(self.return_type),
+            if self.is_extern { " extern" } else { "" },
+            ast_source_range_to_string(&self.range),
             self.body.dump(depth + 1)
         )
     }
 }
 
+/// The return type shown when dumping a function, omitted for the common 'int'
+fn return_type_suffix(return_type: Type) -> String {
+    match return_type {
+        Type::Int => String::new(),
+        _ => format!(" {}", return_type.name()),
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatementKind<'a> {
     Return(Expression<'a>),
+    /// A block of statements enclosed in braces like a function body
+    Compound(Vec<Statement<'a>>),
+    /// A local variable declaration like `int x = 3;`, the variable is uninitialized without an
+    /// initializer
+    Declaration {
+        name: String,
+        value_type: Type,
+        qualifiers: Qualifiers,
+        storage_class: StorageClass,
+        initializer: Option<Expression<'a>>,
+    },
+    /// An expression evaluated for its side effects like `x = 3;`
+    Expression(Expression<'a>),
+    /// A lone semicolon, which does nothing
+    Empty,
+    /// A loop like `while (x) x = x - 1;` running the body as long as the condition is not 0
+    While {
+        condition: Expression<'a>,
+        body: Box<Statement<'a>>,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Statement<'a> {
     pub kind: StatementKind<'a>,
     pub range: SourceRange<'a>,
@@ -72,43 +207,346 @@ pub fn new_return(expression: Expression<'a>, range: SourceRange<'a>) -> Self {
         Self::new(StatementKind::Return(expression), range)
     }
 
+    pub fn new_compound(statements: Vec<Statement<'a>>, range: SourceRange<'a>) -> Self {
+        Self::new(StatementKind::Compound(statements), range)
+    }
+
+    pub fn new_expression(expression: Expression<'a>, range: SourceRange<'a>) -> Self {
+        Self::new(StatementKind::Expression(expression), range)
+    }
+
+    /// The name of the kind of statement as shown by [`Statement::dump`], e.g. `ReturnStatement`
+    #[must_use]
+    pub fn kind_name(&self) -> &'static str {
+        match self.kind {
+            StatementKind::Return(_) => "ReturnStatement",
+            StatementKind::Compound(_) => "CompoundStatement",
+            StatementKind::Declaration { .. } => "DeclarationStatement",
+            StatementKind::Expression(_) => "ExpressionStatement",
+            StatementKind::Empty => "EmptyStatement",
+            StatementKind::While { .. } => "WhileStatement",
+        }
+    }
+
+    /// Compares the statements while ignoring their source ranges
+    pub fn structural_eq(&self, other: &Statement<'_>) -> bool {
+        match (&self.kind, &other.kind) {
+            (StatementKind::Return(expression), StatementKind::Return(other_expression)) => {
+                expression.structural_eq(other_expression)
+            }
+            (StatementKind::Compound(statements), StatementKind::Compound(other_statements)) => {
+                statements.len() == other_statements.len()
+                    && statements.iter().zip(other_statements).all(
+                        |(statement, other_statement)| statement.structural_eq(other_statement),
+                    )
+            }
+            (
+                StatementKind::Declaration {
+                    name,
+                    value_type,
+                    qualifiers,
+                    storage_class,
+                    initializer,
+                },
+                StatementKind::Declaration {
+                    name: other_name,
+                    value_type: other_value_type,
+                    qualifiers: other_qualifiers,
+                    storage_class: other_storage_class,
+                    initializer: other_initializer,
+                },
+            ) => {
+                name == other_name
+                    && value_type == other_value_type
+                    && qualifiers == other_qualifiers
+                    && storage_class == other_storage_class
+                    && match (initializer, other_initializer) {
+                        (Some(initializer), Some(other_initializer)) => {
+                            initializer.structural_eq(other_initializer)
+                        }
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+            (
+                StatementKind::Expression(expression),
+                StatementKind::Expression(other_expression),
+            ) => expression.structural_eq(other_expression),
+            (StatementKind::Empty, StatementKind::Empty) => true,
+            (
+                StatementKind::While { condition, body },
+                StatementKind::While {
+                    condition: other_condition,
+                    body: other_body,
+                },
+            ) => condition.structural_eq(other_condition) && body.structural_eq(other_body),
+            _ => false,
+        }
+    }
+
+    /// Appends the scopes opened by the statement and its children to `result`, see
+    /// [`TranslationUnit::dump_scopes`]
+    fn dump_scopes(&self, depth: usize, result: &mut String) {
+        match &self.kind {
+            StatementKind::Compound(statements) => {
+                result.push_str(&format!(
+                    "{}Scope {}\n",
+                    "  ".repeat(depth),
+                    ast_source_range_to_string(&self.range)
+                ));
+
+                for statement in statements {
+                    if let StatementKind::Declaration {
+                        name, value_type, ..
+                    } = &statement.kind
+                    {
+                        result.push_str(&format!(
+                            "{}Variable \"{name}\" {} {}\n",
+                            "  ".repeat(depth + 1),
+                            value_type.name(),
+                            ast_source_range_to_string(&statement.range)
+                        ));
+                    } else {
+                        statement.dump_scopes(depth + 1, result);
+                    }
+                }
+            }
+            StatementKind::While { body, .. } => body.dump_scopes(depth, result),
+            StatementKind::Return(_)
+            | StatementKind::Declaration { .. }
+            | StatementKind::Expression(_)
+            | StatementKind::Empty => {}
+        }
+    }
+
+    /// Dumps the statement and its children, every line including the last one ends with a newline
     pub fn dump(&self, depth: usize) -> String {
         match &self.kind {
             StatementKind::Return(expression) => {
                 format!(
-                    "{}ReturnStatement {}\n{}",
+                    "{}ReturnStatement {}\n{}\n",
+                    "  ".repeat(depth),
+                    ast_source_range_to_string(&self.range),
+                    expression.dump(depth + 1)
+                )
+            }
+            StatementKind::Compound(statements) => {
+                let mut result = format!(
+                    "{}CompoundStatement {}\n",
+                    "  ".repeat(depth),
+                    ast_source_range_to_string(&self.range)
+                );
+                for statement in statements {
+                    result.push_str(&statement.dump(depth + 1));
+                }
+
+                result
+            }
+            StatementKind::Declaration {
+                name,
+                value_type,
+                qualifiers,
+                storage_class,
+                initializer,
+            } => {
+                let mut result = format!(
+                    "{}DeclarationStatement \"{name}\" {}{}{} {}\n",
+                    "  ".repeat(depth),
+                    if *storage_class == StorageClass::Register {
+                        "register "
+                    } else {
+                        ""
+                    },
+                    value_type.name(),
+                    if qualifiers.is_volatile {
+                        " volatile"
+                    } else {
+                        ""
+                    },
+                    ast_source_range_to_string(&self.range)
+                );
+                if let Some(initializer) = initializer {
+                    result.push_str(&initializer.dump(depth + 1));
+                    result.push('\n');
+                }
+
+                result
+            }
+            StatementKind::Expression(expression) => {
+                format!(
+                    "{}ExpressionStatement {}\n{}\n",
                     "  ".repeat(depth),
                     ast_source_range_to_string(&self.range),
                     expression.dump(depth + 1)
                 )
             }
+            StatementKind::Empty => {
+                format!(
+                    "{}EmptyStatement {}\n",
+                    "  ".repeat(depth),
+                    ast_source_range_to_string(&self.range)
+                )
+            }
+            StatementKind::While { condition, body } => {
+                format!(
+                    "{}WhileStatement {}\n{}\n{}",
+                    "  ".repeat(depth),
+                    ast_source_range_to_string(&self.range),
+                    condition.dump(depth + 1),
+                    body.dump(depth + 1)
+                )
+            }
         }
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOperator {
     Complement,
     Negate,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    LogicalAnd,
+    LogicalOr,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExpressionKind<'a> {
     IntegerLiteral(u32),
+    /// The value of a string literal, adjacent literals like `"a" "b"` are already concatenated
+    StringLiteral(Vec<u8>),
     UnaryOperation {
         operator: UnaryOperator,
         expression: Box<Expression<'a>>,
     },
+    BinaryOperation {
+        operator: BinaryOperator,
+        left: Box<Expression<'a>>,
+        right: Box<Expression<'a>>,
+    },
     Parenthesis(Box<Expression<'a>>),
+    /// A use of the local variable with the name
+    Variable(String),
+    /// An assignment like `x = 3` of the value to the variable with the name, its result is the
+    /// assigned value
+    Assignment {
+        name: String,
+        value: Box<Expression<'a>>,
+    },
+    /// A call like `f(1, 2)` of the function with the name
+    Call {
+        name: String,
+        arguments: Vec<Expression<'a>>,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Expression<'a> {
     pub kind: ExpressionKind<'a>,
     pub range: SourceRange<'a>,
 }
 
 impl Expression<'_> {
+    /// The name of the kind of expression as shown by [`Expression::dump`], e.g. `IntegerLiteral`
+    #[must_use]
+    pub fn kind_name(&self) -> &'static str {
+        match self.kind {
+            ExpressionKind::IntegerLiteral(_) => "IntegerLiteral",
+            ExpressionKind::StringLiteral(_) => "StringLiteral",
+            ExpressionKind::UnaryOperation { .. } => "UnaryOperation",
+            ExpressionKind::BinaryOperation { .. } => "BinaryOperation",
+            ExpressionKind::Parenthesis(_) => "Parenthesis",
+            ExpressionKind::Variable(_) => "Variable",
+            ExpressionKind::Assignment { .. } => "Assignment",
+            ExpressionKind::Call { .. } => "Call",
+        }
+    }
+
+    /// Compares the expressions while ignoring their source ranges
+    pub fn structural_eq(&self, other: &Expression<'_>) -> bool {
+        match (&self.kind, &other.kind) {
+            (
+                ExpressionKind::IntegerLiteral(value),
+                ExpressionKind::IntegerLiteral(other_value),
+            ) => value == other_value,
+            (
+                ExpressionKind::UnaryOperation {
+                    operator,
+                    expression,
+                },
+                ExpressionKind::UnaryOperation {
+                    operator: other_operator,
+                    expression: other_expression,
+                },
+            ) => operator == other_operator && expression.structural_eq(other_expression),
+            (
+                ExpressionKind::BinaryOperation {
+                    operator,
+                    left,
+                    right,
+                },
+                ExpressionKind::BinaryOperation {
+                    operator: other_operator,
+                    left: other_left,
+                    right: other_right,
+                },
+            ) => {
+                operator == other_operator
+                    && left.structural_eq(other_left)
+                    && right.structural_eq(other_right)
+            }
+            (
+                ExpressionKind::Parenthesis(expression),
+                ExpressionKind::Parenthesis(other_expression),
+            ) => expression.structural_eq(other_expression),
+            (ExpressionKind::StringLiteral(value), ExpressionKind::StringLiteral(other_value)) => {
+                value == other_value
+            }
+            (ExpressionKind::Variable(name), ExpressionKind::Variable(other_name)) => {
+                name == other_name
+            }
+            (
+                ExpressionKind::Assignment { name, value },
+                ExpressionKind::Assignment {
+                    name: other_name,
+                    value: other_value,
+                },
+            ) => name == other_name && value.structural_eq(other_value),
+            (
+                ExpressionKind::Call { name, arguments },
+                ExpressionKind::Call {
+                    name: other_name,
+                    arguments: other_arguments,
+                },
+            ) => {
+                name == other_name
+                    && arguments.len() == other_arguments.len()
+                    && arguments
+                        .iter()
+                        .zip(other_arguments)
+                        .all(|(argument, other_argument)| argument.structural_eq(other_argument))
+            }
+            _ => false,
+        }
+    }
+
     pub fn dump(&self, depth: usize) -> String {
         match &self.kind {
             ExpressionKind::IntegerLiteral(value) => {
@@ -119,6 +557,14 @@ pub fn dump(&self, depth: usize) -> String {
                     ast_source_range_to_string(&self.range)
                 )
             }
+            ExpressionKind::StringLiteral(value) => {
+                format!(
+                    "{}StringLiteral \"{}\" {}",
+                    "  ".repeat(depth),
+                    value.escape_ascii(),
+                    ast_source_range_to_string(&self.range)
+                )
+            }
             ExpressionKind::UnaryOperation {
                 operator,
                 expression,
@@ -131,6 +577,20 @@ pub fn dump(&self, depth: usize) -> String {
                     expression.dump(depth + 1)
                 )
             }
+            ExpressionKind::BinaryOperation {
+                operator,
+                left,
+                right,
+            } => {
+                format!(
+                    "{}BinaryOperation {:?} {}\n{}\n{}",
+                    "  ".repeat(depth),
+                    operator,
+                    ast_source_range_to_string(&self.range),
+                    left.dump(depth + 1),
+                    right.dump(depth + 1)
+                )
+            }
             ExpressionKind::Parenthesis(expression) => {
                 format!(
                     "{}Parenthesis {}\n{}",
@@ -139,6 +599,34 @@ pub fn dump(&self, depth: usize) -> String {
                     expression.dump(depth + 1)
                 )
             }
+            ExpressionKind::Variable(name) => {
+                format!(
+                    "{}Variable \"{name}\" {}",
+                    "  ".repeat(depth),
+                    ast_source_range_to_string(&self.range)
+                )
+            }
+            ExpressionKind::Assignment { name, value } => {
+                format!(
+                    "{}Assignment \"{name}\" {}\n{}",
+                    "  ".repeat(depth),
+                    ast_source_range_to_string(&self.range),
+                    value.dump(depth + 1)
+                )
+            }
+            ExpressionKind::Call { name, arguments } => {
+                let mut result = format!(
+                    "{}Call \"{name}\" {}",
+                    "  ".repeat(depth),
+                    ast_source_range_to_string(&self.range)
+                );
+                for argument in arguments {
+                    result.push('\n');
+                    result.push_str(&argument.dump(depth + 1));
+                }
+
+                result
+            }
         }
     }
 }
@@ -153,3 +641,165 @@ fn ast_source_range_to_string(range: &SourceRange<'_>) -> String {
         range.begin.line, range.begin.column, range.end.line, range.end.column
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        diagnostic_consumer::IgnoreDiagnosticConsumer,
+        diagnostic_engine::DiagnosticEngine,
+        lexer::Lexer,
+        parser::{Parser, parse_statement_from_source},
+        source_file::SourceFile,
+    };
+
+    use std::{cell::RefCell, rc::Rc};
+
+    fn parse_statement(source_file: &SourceFile) -> Statement<'_> {
+        let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            IgnoreDiagnosticConsumer,
+        ))));
+
+        parse_statement_from_source(diagnostic_engine, source_file).unwrap()
+    }
+
+    #[test]
+    fn test_statement_kind_names() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "{ return 0; { } int x = 1; x = 2; ; while (x) ; }",
+        );
+        let statement = parse_statement(&source_file);
+        let StatementKind::Compound(statements) = &statement.kind else {
+            panic!("expected a compound statement");
+        };
+
+        assert_eq!(statement.kind_name(), "CompoundStatement");
+        assert_eq!(
+            statements
+                .iter()
+                .map(Statement::kind_name)
+                .collect::<Vec<_>>(),
+            [
+                "ReturnStatement",
+                "CompoundStatement",
+                "DeclarationStatement",
+                "ExpressionStatement",
+                "EmptyStatement",
+                "WhileStatement",
+            ]
+        );
+        for statement in statements {
+            assert!(statement.dump(0).starts_with(statement.kind_name()));
+        }
+    }
+
+    #[test]
+    fn test_dump_scopes() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) {\n  int x = 1;\n  {\n    _Bool y;\n    while (x) { int z; }\n  }\n  return x;\n}\n",
+        );
+        let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            IgnoreDiagnosticConsumer,
+        ))));
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+        let parse_result = Parser::new(diagnostic_engine, tokens).parse();
+
+        assert!(!parse_result.had_errors);
+        assert_eq!(
+            parse_result.translation_unit.dump_scopes(),
+            concat!(
+                "Function \"main\" 1:1-8:1\n",
+                "  Scope 1:16-8:1\n",
+                "    Variable \"x\" int 2:3-2:12\n",
+                "    Scope 3:3-6:3\n",
+                "      Variable \"y\" _Bool 4:5-4:12\n",
+                "      Scope 5:15-5:24\n",
+                "        Variable \"z\" int 5:17-5:22\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_expression_kind_names() {
+        let source_file =
+            SourceFile::new("test.c", "{ int x; return 1; -1; 1 + 2; (1); x; x = 1; }");
+        let statement = parse_statement(&source_file);
+        let StatementKind::Compound(statements) = &statement.kind else {
+            panic!("expected a compound statement");
+        };
+        let expressions = statements
+            .iter()
+            .filter_map(|statement| match &statement.kind {
+                StatementKind::Return(expression) | StatementKind::Expression(expression) => {
+                    Some(expression)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            expressions
+                .iter()
+                .map(|expression| expression.kind_name())
+                .collect::<Vec<_>>(),
+            [
+                "IntegerLiteral",
+                "UnaryOperation",
+                "BinaryOperation",
+                "Parenthesis",
+                "Variable",
+                "Assignment",
+            ]
+        );
+        for expression in expressions {
+            assert!(expression.dump(0).starts_with(expression.kind_name()));
+        }
+    }
+
+    #[test]
+    fn test_structural_eq_ignores_source_ranges() {
+        let first_file = SourceFile::new("first.c", "return -(~1);");
+        let second_file = SourceFile::new("second.c", "\n\n   return   - ( ~ 1 ) ;");
+
+        let first = parse_statement(&first_file);
+        let second = parse_statement(&second_file);
+
+        assert_ne!(first, second);
+        assert!(first.structural_eq(&second));
+        assert!(second.structural_eq(&first));
+    }
+
+    #[test]
+    fn test_structural_eq_different_values() {
+        let first_file = SourceFile::new("test.c", "return 1;");
+        let second_file = SourceFile::new("test.c", "return 2;");
+
+        assert!(!parse_statement(&first_file).structural_eq(&parse_statement(&second_file)));
+    }
+
+    #[test]
+    fn test_structural_eq_different_operators() {
+        let first_file = SourceFile::new("test.c", "return -1;");
+        let second_file = SourceFile::new("test.c", "return ~1;");
+
+        assert!(!parse_statement(&first_file).structural_eq(&parse_statement(&second_file)));
+    }
+
+    #[test]
+    fn test_structural_eq_different_binary_operators() {
+        let first_file = SourceFile::new("test.c", "return 1 + 2;");
+        let second_file = SourceFile::new("test.c", "return 1 - 2;");
+
+        assert!(!parse_statement(&first_file).structural_eq(&parse_statement(&second_file)));
+    }
+
+    #[test]
+    fn test_structural_eq_parenthesis_is_significant() {
+        let first_file = SourceFile::new("test.c", "return 1;");
+        let second_file = SourceFile::new("test.c", "return (1);");
+
+        assert!(!parse_statement(&first_file).structural_eq(&parse_statement(&second_file)));
+    }
+}
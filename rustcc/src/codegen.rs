@@ -1,32 +1,88 @@
-use std::{ffi::CString, ptr};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    ffi::{CStr, CString},
+    fmt::Write,
+    path::Path,
+    ptr,
+    rc::Rc,
+};
 
 use libc::c_uint;
 use llvm_sys::{
-    analysis::{LLVMVerifierFailureAction, LLVMVerifyFunction},
+    LLVMIntPredicate,
+    analysis::{LLVMVerifierFailureAction, LLVMVerifyModule},
     core::{
-        LLVMAddFunction, LLVMAppendBasicBlockInContext, LLVMBuildNeg, LLVMBuildNot, LLVMBuildRet,
-        LLVMConstInt, LLVMContextCreate, LLVMContextDispose, LLVMCreateBuilder,
-        LLVMCreateBuilderInContext, LLVMDisposeBuilder, LLVMDisposeModule, LLVMDumpModule,
-        LLVMFunctionType, LLVMInt1TypeInContext, LLVMInt8TypeInContext, LLVMInt16TypeInContext,
+        LLVMAddFunction, LLVMAddIncoming, LLVMAppendBasicBlockInContext, LLVMBuildAdd,
+        LLVMBuildAlloca, LLVMBuildBr, LLVMBuildCall2, LLVMBuildCondBr, LLVMBuildICmp,
+        LLVMBuildLoad2, LLVMBuildMul, LLVMBuildNeg, LLVMBuildNot, LLVMBuildPhi, LLVMBuildRet,
+        LLVMBuildSDiv, LLVMBuildSRem, LLVMBuildStore, LLVMBuildSub, LLVMBuildZExt, LLVMConstInt,
+        LLVMContextCreate, LLVMContextDispose, LLVMCountBasicBlocks, LLVMCreateBuilder,
+        LLVMCreateBuilderInContext, LLVMDisposeBuilder, LLVMDisposeMessage, LLVMDisposeModule,
+        LLVMDumpModule, LLVMFunctionType, LLVMGetBasicBlockTerminator, LLVMGetEntryBasicBlock,
+        LLVMGetFirstFunction, LLVMGetFirstInstruction, LLVMGetInsertBlock, LLVMGetNamedFunction,
+        LLVMGetNextFunction, LLVMGetNextInstruction, LLVMGetValueName2, LLVMGlobalGetValueType,
+        LLVMInt1TypeInContext, LLVMInt8TypeInContext, LLVMInt16TypeInContext,
         LLVMInt32TypeInContext, LLVMInt64TypeInContext, LLVMInt128TypeInContext,
-        LLVMIntTypeInContext, LLVMModuleCreateWithName, LLVMModuleCreateWithNameInContext,
-        LLVMPositionBuilderAtEnd, LLVMSetSourceFileName,
+        LLVMIntTypeInContext, LLVMIsAAllocaInst, LLVMIsAInstruction, LLVMModuleCreateWithName,
+        LLVMModuleCreateWithNameInContext, LLVMPositionBuilderAtEnd, LLVMPositionBuilderBefore,
+        LLVMPrintModuleToFile, LLVMPrintModuleToString, LLVMPrintValueToString,
+        LLVMSetSourceFileName, LLVMSetTarget, LLVMSetVolatile,
     },
     prelude::{
         LLVMBasicBlockRef, LLVMBuilderRef, LLVMContextRef, LLVMModuleRef, LLVMTypeRef, LLVMValueRef,
     },
+    target::{
+        LLVM_InitializeAllAsmPrinters, LLVM_InitializeAllTargetInfos, LLVM_InitializeAllTargetMCs,
+        LLVM_InitializeAllTargets, LLVM_InitializeNativeAsmPrinter, LLVM_InitializeNativeTarget,
+    },
+    target_machine::{
+        LLVMCodeGenFileType, LLVMCodeGenOptLevel, LLVMCodeModel, LLVMCreateTargetMachine,
+        LLVMDisposeTargetMachine, LLVMGetDefaultTargetTriple, LLVMGetHostCPUFeatures,
+        LLVMGetHostCPUName, LLVMGetTargetFromTriple, LLVMRelocMode, LLVMTargetMachineEmitToFile,
+        LLVMTargetRef,
+    },
 };
 
-use crate::ast::{
-    Expression, ExpressionKind, FunctionDefinition, Statement, StatementKind, TranslationUnit,
-    UnaryOperator,
+use crate::{
+    ast::{
+        BinaryOperator, Expression, ExpressionKind, FunctionDeclaration, FunctionDefinition,
+        Qualifiers, Statement, StatementKind, TranslationUnit, Type, UnaryOperator,
+    },
+    diagnostic::{Diagnostic, DiagnosticId},
+    diagnostic_engine::DiagnosticEngine,
+    error::{Error, Result},
+    json::escape_json,
+    source_range::SourceRange,
 };
 
+/// Generates LLVM IR for a translation unit.
+///
+/// Functions are emitted in the order they appear in the translation unit, so the generated IR is
+/// deterministic. Any lookup tables added to the code generator must preserve this by iterating in
+/// insertion or sorted order.
 #[derive(Debug)]
 pub struct Codegen {
     builder: LLVMBuilder,
     module: LLVMModule,
     context: LLVMContext,
+    file_path: String,
+    /// The target triple set with [`Codegen::with_target`], the host is targeted if `None`
+    target_triple: Option<CString>,
+    /// The generated instructions for each source line
+    source_map: RefCell<BTreeMap<u32, Vec<String>>>,
+    /// Errors are reported as diagnostics if set, see [`Codegen::with_diagnostic_engine`]
+    diagnostic_engine: Option<Rc<RefCell<DiagnosticEngine>>>,
+    /// The local variables declared in each enclosing block, the innermost block is last
+    variables: RefCell<Vec<BTreeMap<String, Variable>>>,
+}
+
+/// A local variable, which lives in a stack slot allocated in the entry block of its function
+#[derive(Debug, Clone, Copy)]
+struct Variable {
+    pointer: LLVMValueRef,
+    value_type: Type,
+    qualifiers: Qualifiers,
 }
 
 impl Codegen {
@@ -43,29 +99,305 @@ pub fn new(file_path: &str) -> Self {
             builder,
             module,
             context,
+            file_path: file_path.to_string(),
+            target_triple: None,
+            source_map: RefCell::new(BTreeMap::new()),
+            diagnostic_engine: None,
+            variables: RefCell::new(Vec::new()),
         }
     }
 
+    /// Creates a code generator which reports its errors to the diagnostic engine, like the lexer
+    /// and parser do. [`Codegen::codegen`] then fails with [`Error::CodegenReported`] instead of
+    /// carrying the message in the error.
+    pub fn with_diagnostic_engine(
+        file_path: &str,
+        diagnostic_engine: Rc<RefCell<DiagnosticEngine>>,
+    ) -> Self {
+        let mut codegen = Self::new(file_path);
+        codegen.diagnostic_engine = Some(diagnostic_engine);
+
+        codegen
+    }
+
+    /// Creates a code generator for the target `triple` instead of the host, an error is returned
+    /// if LLVM doesn't know the target
+    pub fn with_target(file_path: &str, triple: &str) -> Result<Self> {
+        Self::new(file_path).targeting(triple)
+    }
+
+    /// Switches the code generator to the target `triple`, see [`Codegen::with_target`]
+    pub fn targeting(mut self, triple: &str) -> Result<Self> {
+        let unknown_target = |message: String| Error::UnknownTarget {
+            triple: triple.to_string(),
+            message,
+        };
+        let triple_cstring = CString::new(triple)
+            .map_err(|_| unknown_target("the triple contains a nul byte".to_string()))?;
+
+        lookup_target(&triple_cstring).map_err(unknown_target)?;
+
+        self.module.set_target(&triple_cstring);
+        self.target_triple = Some(triple_cstring);
+
+        Ok(self)
+    }
+
     pub fn dump(&self) {
         unsafe { LLVMDumpModule(self.module.0) };
     }
 
+    /// Returns the number of functions in the module
+    #[must_use]
+    pub fn function_count(&self) -> usize {
+        self.module.functions().len()
+    }
+
+    /// Returns the names of all functions in the module in the order they were emitted
+    #[must_use]
+    pub fn function_names(&self) -> Vec<String> {
+        self.module
+            .functions()
+            .into_iter()
+            .map(|function| {
+                let mut length = 0;
+                unsafe {
+                    let name = LLVMGetValueName2(function, &mut length);
+                    let bytes = std::slice::from_raw_parts(name.cast::<u8>(), length);
+                    String::from_utf8_lossy(bytes).into_owned()
+                }
+            })
+            .collect()
+    }
+
+    /// Returns a JSON object mapping each source line to the LLVM instructions generated for it.
+    ///
+    /// Constant expressions are folded by LLVM and therefore don't show up as instructions.
+    #[must_use]
+    pub fn source_map_json(&self) -> String {
+        let mut json = format!(
+            "{{\"source_file\":\"{}\",\"lines\":[",
+            escape_json(&self.file_path)
+        );
+
+        for (index, (line, instructions)) in self.source_map.borrow().iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+
+            let instructions = instructions
+                .iter()
+                .map(|instruction| format!("\"{}\"", escape_json(instruction)))
+                .collect::<Vec<_>>()
+                .join(",");
+            let _ = write!(
+                json,
+                "{{\"line\":{line},\"instructions\":[{instructions}]}}"
+            );
+        }
+
+        json.push_str("]}");
+        json
+    }
+
+    /// Remembers the source line a value was generated for, if the value is an instruction
+    fn record_source_line(&self, value: LLVMValueRef, range: &SourceRange) {
+        if value.is_null() || unsafe { LLVMIsAInstruction(value) }.is_null() {
+            return;
+        }
+
+        let instruction = unsafe {
+            let message = LLVMPrintValueToString(value);
+            let string = CStr::from_ptr(message).to_string_lossy().trim().to_string();
+            LLVMDisposeMessage(message);
+
+            string
+        };
+
+        self.source_map
+            .borrow_mut()
+            .entry(range.begin.line)
+            .or_default()
+            .push(instruction);
+    }
+
+    /// Returns the textual LLVM intermediate representation of the module
+    #[must_use]
+    pub fn ir_string(&self) -> String {
+        self.module.print_to_string()
+    }
+
+    /// Returns a hash of the textual LLVM intermediate representation of the module. The hash only
+    /// depends on the generated IR, so it is the same across runs, builds and platforms.
+    #[must_use]
+    pub fn module_hash(&self) -> u64 {
+        fnv1a_hash(self.ir_string().as_bytes())
+    }
+
+    /// Writes the textual LLVM intermediate representation of the module to `path`
+    pub fn write_ir_to_file(&self, path: &Path) -> Result<()> {
+        let file_name = path_to_cstring(path)?;
+        let mut message = ptr::null_mut();
+
+        let failed =
+            unsafe { LLVMPrintModuleToFile(self.module.0, file_name.as_ptr(), &mut message) } != 0;
+
+        if failed {
+            return Err(Error::Output {
+                path: path.display().to_string(),
+                message: take_llvm_message(message),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Writes the module as an object file for the target to `path`
+    pub fn write_object_file(&self, path: &Path) -> Result<()> {
+        self.emit_to_file(path, LLVMCodeGenFileType::LLVMObjectFile)
+    }
+
+    /// Writes the module as assembly text for the target to `path`
+    pub fn emit_assembly(&self, path: &Path) -> Result<()> {
+        self.emit_to_file(path, LLVMCodeGenFileType::LLVMAssemblyFile)
+    }
+
+    /// Generates machine code for the target and writes it to `path` as `file_type`
+    fn emit_to_file(&self, path: &Path, file_type: LLVMCodeGenFileType) -> Result<()> {
+        let file_name = path_to_cstring(path)?;
+        let output_error = |message: String| Error::Output {
+            path: path.display().to_string(),
+            message,
+        };
+
+        unsafe {
+            let target_machine = match &self.target_triple {
+                Some(triple) => {
+                    let target = lookup_target(triple).map_err(output_error)?;
+
+                    // Without knowing the machine a cross compilation targets, use the baseline CPU
+                    LLVMCreateTargetMachine(
+                        target,
+                        triple.as_ptr(),
+                        c"generic".as_ptr(),
+                        c"".as_ptr(),
+                        LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault,
+                        LLVMRelocMode::LLVMRelocPIC,
+                        LLVMCodeModel::LLVMCodeModelDefault,
+                    )
+                }
+                None => {
+                    if LLVM_InitializeNativeTarget() != 0 || LLVM_InitializeNativeAsmPrinter() != 0
+                    {
+                        return Err(output_error(
+                            "the native target is not available".to_string(),
+                        ));
+                    }
+
+                    let triple = LLVMGetDefaultTargetTriple();
+                    let target = match lookup_target(CStr::from_ptr(triple)) {
+                        Ok(target) => target,
+                        Err(message) => {
+                            LLVMDisposeMessage(triple);
+
+                            return Err(output_error(message));
+                        }
+                    };
+
+                    let cpu = LLVMGetHostCPUName();
+                    let features = LLVMGetHostCPUFeatures();
+                    let target_machine = LLVMCreateTargetMachine(
+                        target,
+                        triple,
+                        cpu,
+                        features,
+                        LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault,
+                        LLVMRelocMode::LLVMRelocPIC,
+                        LLVMCodeModel::LLVMCodeModelDefault,
+                    );
+                    LLVMDisposeMessage(features);
+                    LLVMDisposeMessage(cpu);
+                    LLVMDisposeMessage(triple);
+
+                    target_machine
+                }
+            };
+
+            // The target machine only reads the file name, the mutable pointer is an artifact of the C API
+            let mut message = ptr::null_mut();
+            let failed = LLVMTargetMachineEmitToFile(
+                target_machine,
+                self.module.0,
+                file_name.as_ptr().cast_mut(),
+                file_type,
+                &mut message,
+            ) != 0;
+            LLVMDisposeTargetMachine(target_machine);
+
+            if failed {
+                return Err(output_error(take_llvm_message(message)));
+            }
+        }
+
+        Ok(())
+    }
+
     #[must_use]
     fn int32_type(&self) -> LLVMTypeRef {
         self.context.int32_type()
     }
 
+    /// The LLVM type of a value of `value_type`, a `_Bool` is an `i1` holding 0 or 1
+    #[must_use]
+    fn llvm_type(&self, value_type: Type) -> LLVMTypeRef {
+        match value_type {
+            Type::Int => self.int32_type(),
+            Type::Bool => self.context.int1_type(),
+        }
+    }
+
     #[must_use]
     fn function_type(&self, return_type: LLVMTypeRef) -> LLVMTypeRef {
         unsafe { LLVMFunctionType(return_type, ptr::null_mut(), 0, 0) }
     }
 
-    fn function(&self, name: &str, function_type: LLVMTypeRef) -> LLVMValueRef {
-        let Ok(function_name) = CString::new(name) else {
-            return ptr::null_mut();
-        };
+    /// Returns the function with the given name, adding it to the module if it wasn't declared yet.
+    /// A function which was declared before with a different type is an error.
+    fn function(
+        &self,
+        name: &str,
+        function_type: LLVMTypeRef,
+        source_range: SourceRange,
+    ) -> Result<LLVMValueRef> {
+        let function = CString::new(name)
+            .map(|function_name| {
+                let function = self.module.named_function(&function_name);
+                if function.is_null() {
+                    self.module.add_function(function_name, function_type)
+                } else {
+                    function
+                }
+            })
+            .unwrap_or(ptr::null_mut());
+
+        if function.is_null() {
+            return Err(self.error(
+                DiagnosticId::CodegenFailed,
+                source_range,
+                format!("failed to create function '{name}'"),
+            ));
+        }
 
-        self.module.add_function(function_name, function_type)
+        // LLVM types are unique within a context, so equal types are the same pointer
+        if unsafe { LLVMGlobalGetValueType(function) } != function_type {
+            return Err(self.error(
+                DiagnosticId::ConflictingTypes,
+                source_range,
+                format!("conflicting types for '{name}'"),
+            ));
+        }
+
+        Ok(function)
     }
 
     fn function_basic_block(&self, name: &str, function: LLVMValueRef) -> LLVMBasicBlockRef {
@@ -98,23 +430,98 @@ fn not(&self, value: LLVMValueRef) -> LLVMValueRef {
         self.builder.not(value)
     }
 
-    pub fn codegen(&self, translation_unit: &TranslationUnit) -> Option<()> {
+    pub fn codegen(&self, translation_unit: &TranslationUnit) -> Result<()> {
+        // Declare all functions which are resolved at link time
+        for declaration in &translation_unit.declarations {
+            self.codegen_function_declaration(declaration)?;
+        }
+
         // Code gen all functions
         for function in &translation_unit.function {
-            self.codegen_function(function);
+            self.codegen_function(function)?;
+        }
+
+        // An invalid module is a bug in the code generator, it can't be pinned to a source range
+        match self.verify() {
+            Err(Error::LlvmVerification(message)) if self.diagnostic_engine.is_some() => Err(self
+                .error(
+                    DiagnosticId::CodegenFailed,
+                    SourceRange::invalid(),
+                    format!("LLVM module is invalid: {message}"),
+                )),
+            result => result,
+        }
+    }
+
+    /// Reports an error as a diagnostic if there is a diagnostic engine, otherwise the message is
+    /// returned in an [`Error::Codegen`]
+    fn error(&self, id: DiagnosticId, source_range: SourceRange, message: String) -> Error {
+        let Some(diagnostic_engine) = &self.diagnostic_engine else {
+            return Error::Codegen(message);
+        };
+
+        diagnostic_engine
+            .borrow_mut()
+            .report(&mut Diagnostic::new(id, source_range, message));
+
+        Error::CodegenReported
+    }
+
+    /// Runs the LLVM verifier on the whole module
+    pub fn verify(&self) -> Result<()> {
+        let mut message = ptr::null_mut();
+
+        let failed = unsafe {
+            LLVMVerifyModule(
+                self.module.0,
+                LLVMVerifierFailureAction::LLVMReturnStatusAction,
+                &mut message,
+            )
+        } != 0;
+
+        let message_string = if message.is_null() {
+            String::new()
+        } else {
+            unsafe {
+                let string = CStr::from_ptr(message).to_string_lossy().into_owned();
+                LLVMDisposeMessage(message);
+
+                string
+            }
+        };
+
+        if failed {
+            return Err(Error::LlvmVerification(
+                message_string.trim_end().to_string(),
+            ));
         }
 
-        Some(())
+        Ok(())
     }
 
-    fn codegen_function(&self, function: &FunctionDefinition) -> Option<()> {
+    fn codegen_function_declaration(&self, declaration: &FunctionDeclaration) -> Result<()> {
+        // Functions have external linkage by default, so adding it without a body is enough
+        let function_type = self.function_type(self.llvm_type(declaration.return_type));
+
+        self.function(&declaration.name, function_type, declaration.range)?;
+
+        Ok(())
+    }
+
+    fn codegen_function(&self, function: &FunctionDefinition) -> Result<()> {
         // Create the function type
-        let function_type = self.function_type(self.int32_type());
+        let function_type = self.function_type(self.llvm_type(function.return_type));
 
         // Create the function
-        let llvm_function = self.function(&function.name, function_type);
-        if llvm_function.is_null() {
-            return None;
+        let llvm_function = self.function(&function.name, function_type, function.range)?;
+
+        // A function which already has a body was defined before
+        if unsafe { LLVMCountBasicBlocks(llvm_function) } != 0 {
+            return Err(self.error(
+                DiagnosticId::FunctionRedefinition,
+                function.range,
+                format!("redefinition of function '{}'", function.name),
+            ));
         }
 
         // Create a basic block in the function and set our builder to generate
@@ -122,37 +529,278 @@ fn codegen_function(&self, function: &FunctionDefinition) -> Option<()> {
         self.function_basic_block("entry", llvm_function);
 
         // Codegen the function body
-        self.codegen_statement(&function.body);
+        self.codegen_statement(&function.body, function.return_type, llvm_function);
 
-        // Verify generated function
-        unsafe {
-            LLVMVerifyFunction(
-                llvm_function,
-                LLVMVerifierFailureAction::LLVMPrintMessageAction,
-            )
-        };
+        // Falling off the end of a function returns 0, which is what C requires for main
+        if !self.current_block_is_terminated() {
+            self.builder
+                .ret(self.convert(self.const_int(0), function.return_type));
+        }
 
-        Some(())
+        Ok(())
     }
 
-    fn codegen_statement(&self, statement: &Statement) {
+    fn codegen_statement(&self, statement: &Statement, return_type: Type, function: LLVMValueRef) {
         match &statement.kind {
             StatementKind::Return(expression) => {
-                let value = self.codegen_expression(expression);
+                let value =
+                    self.convert(self.codegen_expression(expression, function), return_type);
+
+                let instruction = self.builder.ret(value);
+                self.record_source_line(instruction, &statement.range);
+            }
+            StatementKind::Compound(statements) => {
+                self.variables.borrow_mut().push(BTreeMap::new());
+
+                for statement in statements {
+                    // Statements after a return are unreachable and would follow the terminator
+                    if self.current_block_is_terminated() {
+                        break;
+                    }
+
+                    self.codegen_statement(statement, return_type, function);
+                }
+
+                self.variables.borrow_mut().pop();
+            }
+            StatementKind::Declaration {
+                name,
+                value_type,
+                qualifiers,
+                initializer,
+                ..
+            } => {
+                let variable = Variable {
+                    pointer: self.entry_block_alloca(function, self.llvm_type(*value_type), name),
+                    value_type: *value_type,
+                    qualifiers: *qualifiers,
+                };
+                if let Some(scope) = self.variables.borrow_mut().last_mut() {
+                    scope.insert(name.clone(), variable);
+                }
+
+                if let Some(initializer) = initializer {
+                    let value = self.codegen_expression(initializer, function);
+                    self.store_variable(&variable, value, &statement.range);
+                }
+            }
+            StatementKind::Expression(expression) => {
+                // The value of an expression statement is discarded
+                let _ = self.codegen_expression(expression, function);
+            }
+            StatementKind::Empty => {}
+            StatementKind::While { condition, body } => {
+                self.codegen_while(condition, body, return_type, function);
+            }
+        }
+    }
+
+    /// Generates a `while` loop, the condition is checked in its own block which the end of the
+    /// body branches back to
+    fn codegen_while(
+        &self,
+        condition: &Expression,
+        body: &Statement,
+        return_type: Type,
+        function: LLVMValueRef,
+    ) {
+        let condition_block = self
+            .context
+            .create_basic_block_for_function(function, CString::new("while.cond").unwrap());
+        let body_block = self
+            .context
+            .create_basic_block_for_function(function, CString::new("while.body").unwrap());
+        let exit_block = self
+            .context
+            .create_basic_block_for_function(function, CString::new("while.exit").unwrap());
+
+        self.builder.br(condition_block);
+
+        self.builder.position_at_end(condition_block);
+        let value = self.codegen_expression(condition, function);
+        let condition_value =
+            self.builder
+                .icmp(LLVMIntPredicate::LLVMIntNE, value, self.const_int(0));
+        self.builder
+            .cond_br(condition_value, body_block, exit_block);
 
-                self.builder.ret(value);
+        self.builder.position_at_end(body_block);
+        self.codegen_statement(body, return_type, function);
+        // The body may end in a return, which already terminates its last block
+        if !self.current_block_is_terminated() {
+            self.builder.br(condition_block);
+        }
+
+        self.builder.position_at_end(exit_block);
+    }
+
+    /// Allocates a stack slot in the entry block of `function`. Keeping all allocas at the start of
+    /// the entry block lets LLVM promote them to registers.
+    fn entry_block_alloca(
+        &self,
+        function: LLVMValueRef,
+        value_type: LLVMTypeRef,
+        name: &str,
+    ) -> LLVMValueRef {
+        let builder = LLVMBuilder::new_in_context(&self.context);
+
+        unsafe {
+            let entry_block = LLVMGetEntryBasicBlock(function);
+
+            // Insert after the allocas of the previously declared variables
+            let mut instruction = LLVMGetFirstInstruction(entry_block);
+            while !instruction.is_null() && !LLVMIsAAllocaInst(instruction).is_null() {
+                instruction = LLVMGetNextInstruction(instruction);
+            }
+
+            if instruction.is_null() {
+                builder.position_at_end(entry_block);
+            } else {
+                builder.position_before(instruction);
             }
         }
+
+        builder.alloca(value_type, name)
+    }
+
+    /// Looks up a variable in the enclosing blocks, the innermost declaration wins
+    fn variable(&self, name: &str) -> Option<Variable> {
+        self.variables
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+    }
+
+    /// Stores an `int` value into the variable, converting it to the type of the variable
+    fn store_variable(&self, variable: &Variable, value: LLVMValueRef, range: &SourceRange) {
+        let value = self.convert(value, variable.value_type);
+
+        let instruction = self.builder.store(value, variable.pointer);
+        if variable.qualifiers.is_volatile {
+            unsafe { LLVMSetVolatile(instruction, 1) };
+        }
+        self.record_source_line(instruction, range);
+    }
+
+    /// Loads the value of the variable as an `int`
+    fn load_variable(&self, variable: &Variable, range: &SourceRange) -> LLVMValueRef {
+        let value = self
+            .builder
+            .load(self.llvm_type(variable.value_type), variable.pointer);
+        if variable.qualifiers.is_volatile {
+            unsafe { LLVMSetVolatile(value, 1) };
+        }
+        self.record_source_line(value, range);
+
+        match variable.value_type {
+            Type::Int => value,
+            Type::Bool => self.builder.zext(value, self.int32_type()),
+        }
+    }
+
+    /// Converts an `int` value to `value_type`, a `_Bool` is 0 if the value compares equal to 0 and 1
+    /// otherwise
+    #[must_use]
+    fn convert(&self, value: LLVMValueRef, value_type: Type) -> LLVMValueRef {
+        match value_type {
+            Type::Int => value,
+            Type::Bool => self
+                .builder
+                .icmp(LLVMIntPredicate::LLVMIntNE, value, self.const_int(0)),
+        }
     }
 
-    fn codegen_expression(&self, expression: &Expression) -> LLVMValueRef {
+    /// Whether the basic block the builder inserts into already ends with a terminator like `ret`
+    fn current_block_is_terminated(&self) -> bool {
+        let basic_block = self.builder.insert_block();
+
+        !basic_block.is_null() && unsafe { !LLVMGetBasicBlockTerminator(basic_block).is_null() }
+    }
+
+    fn codegen_expression(&self, expression: &Expression, function: LLVMValueRef) -> LLVMValueRef {
         match &expression.kind {
             ExpressionKind::IntegerLiteral(value) => self.const_int(*value),
+            // The parser already reported string literals as unsupported
+            ExpressionKind::StringLiteral(_) => self.const_int(0),
             ExpressionKind::UnaryOperation {
                 operator,
-                expression,
-            } => self.codegen_unary_operation(operator, expression.as_ref()),
-            ExpressionKind::Parenthesis(expression) => self.codegen_expression(expression),
+                expression: operand,
+            } => {
+                let value = self.codegen_unary_operation(operator, operand.as_ref(), function);
+                self.record_source_line(value, &expression.range);
+
+                value
+            }
+            ExpressionKind::BinaryOperation {
+                operator,
+                left,
+                right,
+            } => {
+                let value = self.codegen_binary_operation(operator, left, right, function);
+                self.record_source_line(value, &expression.range);
+
+                value
+            }
+            ExpressionKind::Parenthesis(expression) => {
+                self.codegen_expression(expression, function)
+            }
+            // The parser already reported undeclared variables, so the lookups can't fail
+            ExpressionKind::Variable(name) => match self.variable(name) {
+                Some(variable) => self.load_variable(&variable, &expression.range),
+                None => self.const_int(0),
+            },
+            ExpressionKind::Assignment { name, value } => {
+                let value = self.codegen_expression(value, function);
+                let Some(variable) = self.variable(name) else {
+                    return value;
+                };
+                self.store_variable(&variable, value, &expression.range);
+
+                // The value of an assignment is the value stored in the variable
+                match variable.value_type {
+                    Type::Int => value,
+                    Type::Bool => self
+                        .builder
+                        .zext(self.convert(value, Type::Bool), self.int32_type()),
+                }
+            }
+            ExpressionKind::Call { name, arguments } => {
+                let value = self.codegen_call(name, arguments, function);
+                self.record_source_line(value, &expression.range);
+
+                value
+            }
+        }
+    }
+
+    /// Calls the function with the name, which the parser made sure is declared before. The result
+    /// is an `int`, so a `_Bool` result is zero-extended.
+    fn codegen_call(
+        &self,
+        name: &str,
+        arguments: &[Expression],
+        function: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let Ok(function_name) = CString::new(name) else {
+            return self.const_int(0);
+        };
+        let callee = self.module.named_function(&function_name);
+        if callee.is_null() {
+            return self.const_int(0);
+        }
+
+        let mut arguments = arguments
+            .iter()
+            .map(|argument| self.codegen_expression(argument, function))
+            .collect::<Vec<_>>();
+        let function_type = unsafe { LLVMGlobalGetValueType(callee) };
+        let value = self.builder.call(function_type, callee, &mut arguments);
+
+        if function_type == self.function_type(self.llvm_type(Type::Bool)) {
+            self.builder.zext(value, self.int32_type())
+        } else {
+            value
         }
     }
 
@@ -160,14 +808,164 @@ fn codegen_unary_operation(
         &self,
         operator: &UnaryOperator,
         expression: &Expression,
+        function: LLVMValueRef,
     ) -> LLVMValueRef {
-        let value = self.codegen_expression(expression);
+        let value = self.codegen_expression(expression, function);
 
         match operator {
             UnaryOperator::Negate => self.negate(value),
             UnaryOperator::Complement => self.not(value),
         }
     }
+
+    fn codegen_binary_operation(
+        &self,
+        operator: &BinaryOperator,
+        left: &Expression,
+        right: &Expression,
+        function: LLVMValueRef,
+    ) -> LLVMValueRef {
+        // The right operand of a logical operator may not be evaluated at all
+        if matches!(
+            operator,
+            BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr
+        ) {
+            return self.codegen_logical_operation(operator, left, right, function);
+        }
+
+        let left = self.codegen_expression(left, function);
+        let right = self.codegen_expression(right, function);
+
+        match operator {
+            BinaryOperator::Add => self.builder.add(left, right),
+            BinaryOperator::Subtract => self.builder.sub(left, right),
+            BinaryOperator::Multiply => self.builder.mul(left, right),
+            BinaryOperator::Divide => self.builder.sdiv(left, right),
+            BinaryOperator::Remainder => self.builder.srem(left, right),
+            BinaryOperator::Equal => self.compare(LLVMIntPredicate::LLVMIntEQ, left, right),
+            BinaryOperator::NotEqual => self.compare(LLVMIntPredicate::LLVMIntNE, left, right),
+            BinaryOperator::Less => self.compare(LLVMIntPredicate::LLVMIntSLT, left, right),
+            BinaryOperator::Greater => self.compare(LLVMIntPredicate::LLVMIntSGT, left, right),
+            BinaryOperator::LessEqual => self.compare(LLVMIntPredicate::LLVMIntSLE, left, right),
+            BinaryOperator::GreaterEqual => self.compare(LLVMIntPredicate::LLVMIntSGE, left, right),
+            BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => unreachable!(),
+        }
+    }
+
+    /// Generates `&&` and `||`, which only evaluate the right operand if the left one doesn't
+    /// decide the result already. The operands are compared against zero and the result is 0 or 1.
+    fn codegen_logical_operation(
+        &self,
+        operator: &BinaryOperator,
+        left: &Expression,
+        right: &Expression,
+        function: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let is_and = *operator == BinaryOperator::LogicalAnd;
+        let (right_block_name, end_block_name) = if is_and {
+            ("land.rhs", "land.end")
+        } else {
+            ("lor.rhs", "lor.end")
+        };
+
+        let left = self.codegen_expression(left, function);
+        let left_condition =
+            self.builder
+                .icmp(LLVMIntPredicate::LLVMIntNE, left, self.const_int(0));
+        // A nested logical operation leaves the builder in another block than it started in
+        let left_block = self.builder.insert_block();
+
+        let right_block = self
+            .context
+            .create_basic_block_for_function(function, CString::new(right_block_name).unwrap());
+        let end_block = self
+            .context
+            .create_basic_block_for_function(function, CString::new(end_block_name).unwrap());
+        if is_and {
+            self.builder.cond_br(left_condition, right_block, end_block);
+        } else {
+            self.builder.cond_br(left_condition, end_block, right_block);
+        }
+
+        self.builder.position_at_end(right_block);
+        let right = self.codegen_expression(right, function);
+        let right_value = self.compare(LLVMIntPredicate::LLVMIntNE, right, self.const_int(0));
+        let right_block = self.builder.insert_block();
+        self.builder.br(end_block);
+
+        // Skipping the right operand means the result is false for `&&` and true for `||`
+        self.builder.position_at_end(end_block);
+        self.builder.phi(
+            self.int32_type(),
+            &[
+                (self.const_int(u32::from(!is_and)), left_block),
+                (right_value, right_block),
+            ],
+        )
+    }
+
+    /// Compares the values, the `i1` result is zero-extended since comparisons have type int in C
+    fn compare(
+        &self,
+        predicate: LLVMIntPredicate,
+        left: LLVMValueRef,
+        right: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let condition = self.builder.icmp(predicate, left, right);
+
+        self.builder.zext(condition, self.int32_type())
+    }
+}
+
+/// Converts a path to a C string for the LLVM APIs, which can't represent interior nul bytes
+fn path_to_cstring(path: &Path) -> Result<CString> {
+    CString::new(path.to_string_lossy().as_bytes()).map_err(|_| Error::Output {
+        path: path.display().to_string(),
+        message: "path contains a nul byte".to_string(),
+    })
+}
+
+/// Looks up the LLVM target for `triple`, returning LLVM's error message if it is unknown
+fn lookup_target(triple: &CStr) -> std::result::Result<LLVMTargetRef, String> {
+    unsafe {
+        LLVM_InitializeAllTargetInfos();
+        LLVM_InitializeAllTargets();
+        LLVM_InitializeAllTargetMCs();
+        LLVM_InitializeAllAsmPrinters();
+
+        let mut target = ptr::null_mut();
+        let mut message = ptr::null_mut();
+        if LLVMGetTargetFromTriple(triple.as_ptr(), &mut target, &mut message) != 0 {
+            return Err(take_llvm_message(message));
+        }
+
+        Ok(target)
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`. Unlike the hasher of the standard library its output is
+/// specified, so it can be compared across builds of rustcc.
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+/// Takes ownership of an error message allocated by LLVM and returns it as a string
+fn take_llvm_message(message: *mut libc::c_char) -> String {
+    if message.is_null() {
+        return String::new();
+    }
+
+    unsafe {
+        let string = CStr::from_ptr(message)
+            .to_string_lossy()
+            .trim_end()
+            .to_string();
+        LLVMDisposeMessage(message);
+
+        string
+    }
 }
 
 // -- LLVM Wrappers --
@@ -242,9 +1040,39 @@ pub fn set_source_file_name(&self, name: CString) {
         unsafe { LLVMSetSourceFileName(self.0, name.as_ptr(), name.as_bytes().len()) };
     }
 
+    pub fn set_target(&self, triple: &CStr) {
+        unsafe { LLVMSetTarget(self.0, triple.as_ptr()) };
+    }
+
     pub fn add_function(&self, name: CString, function_type: LLVMTypeRef) -> LLVMValueRef {
         unsafe { LLVMAddFunction(self.0, name.as_ptr(), function_type) }
     }
+
+    pub fn named_function(&self, name: &CString) -> LLVMValueRef {
+        unsafe { LLVMGetNamedFunction(self.0, name.as_ptr()) }
+    }
+
+    pub fn functions(&self) -> Vec<LLVMValueRef> {
+        let mut functions = Vec::new();
+
+        let mut function = unsafe { LLVMGetFirstFunction(self.0) };
+        while !function.is_null() {
+            functions.push(function);
+            function = unsafe { LLVMGetNextFunction(function) };
+        }
+
+        functions
+    }
+
+    pub fn print_to_string(&self) -> String {
+        unsafe {
+            let message = LLVMPrintModuleToString(self.0);
+            let string = CStr::from_ptr(message).to_string_lossy().into_owned();
+            LLVMDisposeMessage(message);
+
+            string
+        }
+    }
 }
 
 impl Drop for LLVMModule {
@@ -271,8 +1099,17 @@ fn position_at_end(&self, basic_block: LLVMBasicBlockRef) {
         unsafe { LLVMPositionBuilderAtEnd(self.0, basic_block) };
     }
 
-    fn ret(&self, value: LLVMValueRef) {
-        unsafe { LLVMBuildRet(self.0, value) };
+    fn position_before(&self, instruction: LLVMValueRef) {
+        unsafe { LLVMPositionBuilderBefore(self.0, instruction) };
+    }
+
+    /// Returns the basic block new instructions are inserted into
+    fn insert_block(&self) -> LLVMBasicBlockRef {
+        unsafe { LLVMGetInsertBlock(self.0) }
+    }
+
+    fn ret(&self, value: LLVMValueRef) -> LLVMValueRef {
+        unsafe { LLVMBuildRet(self.0, value) }
     }
 
     fn not(&self, value: LLVMValueRef) -> LLVMValueRef {
@@ -284,6 +1121,125 @@ fn negate(&self, value: LLVMValueRef) -> LLVMValueRef {
         let name = CString::new("neg").unwrap();
         unsafe { LLVMBuildNeg(self.0, value, name.as_ptr()) }
     }
+
+    fn add(&self, left: LLVMValueRef, right: LLVMValueRef) -> LLVMValueRef {
+        let name = CString::new("add").unwrap();
+        unsafe { LLVMBuildAdd(self.0, left, right, name.as_ptr()) }
+    }
+
+    fn sub(&self, left: LLVMValueRef, right: LLVMValueRef) -> LLVMValueRef {
+        let name = CString::new("sub").unwrap();
+        unsafe { LLVMBuildSub(self.0, left, right, name.as_ptr()) }
+    }
+
+    fn mul(&self, left: LLVMValueRef, right: LLVMValueRef) -> LLVMValueRef {
+        let name = CString::new("mul").unwrap();
+        unsafe { LLVMBuildMul(self.0, left, right, name.as_ptr()) }
+    }
+
+    fn sdiv(&self, left: LLVMValueRef, right: LLVMValueRef) -> LLVMValueRef {
+        let name = CString::new("div").unwrap();
+        unsafe { LLVMBuildSDiv(self.0, left, right, name.as_ptr()) }
+    }
+
+    fn srem(&self, left: LLVMValueRef, right: LLVMValueRef) -> LLVMValueRef {
+        let name = CString::new("rem").unwrap();
+        unsafe { LLVMBuildSRem(self.0, left, right, name.as_ptr()) }
+    }
+
+    fn icmp(
+        &self,
+        predicate: LLVMIntPredicate,
+        lhs: LLVMValueRef,
+        rhs: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let name = CString::new("cmp").unwrap();
+        unsafe { LLVMBuildICmp(self.0, predicate, lhs, rhs, name.as_ptr()) }
+    }
+
+    fn zext(&self, value: LLVMValueRef, destination_type: LLVMTypeRef) -> LLVMValueRef {
+        let name = CString::new("zext").unwrap();
+        unsafe { LLVMBuildZExt(self.0, value, destination_type, name.as_ptr()) }
+    }
+
+    #[cfg(test)]
+    fn sext(&self, value: LLVMValueRef, destination_type: LLVMTypeRef) -> LLVMValueRef {
+        let name = CString::new("sext").unwrap();
+        unsafe { llvm_sys::core::LLVMBuildSExt(self.0, value, destination_type, name.as_ptr()) }
+    }
+
+    #[cfg(test)]
+    fn trunc(&self, value: LLVMValueRef, destination_type: LLVMTypeRef) -> LLVMValueRef {
+        let name = CString::new("trunc").unwrap();
+        unsafe { llvm_sys::core::LLVMBuildTrunc(self.0, value, destination_type, name.as_ptr()) }
+    }
+
+    fn alloca(&self, value_type: LLVMTypeRef, name: &str) -> LLVMValueRef {
+        let name = CString::new(name).unwrap_or_default();
+        unsafe { LLVMBuildAlloca(self.0, value_type, name.as_ptr()) }
+    }
+
+    fn load(&self, value_type: LLVMTypeRef, pointer: LLVMValueRef) -> LLVMValueRef {
+        let name = CString::new("load").unwrap();
+        unsafe { LLVMBuildLoad2(self.0, value_type, pointer, name.as_ptr()) }
+    }
+
+    fn store(&self, value: LLVMValueRef, pointer: LLVMValueRef) -> LLVMValueRef {
+        unsafe { LLVMBuildStore(self.0, value, pointer) }
+    }
+
+    fn call(
+        &self,
+        function_type: LLVMTypeRef,
+        function: LLVMValueRef,
+        arguments: &mut [LLVMValueRef],
+    ) -> LLVMValueRef {
+        let name = CString::new("call").unwrap();
+        unsafe {
+            LLVMBuildCall2(
+                self.0,
+                function_type,
+                function,
+                arguments.as_mut_ptr(),
+                arguments.len() as c_uint,
+                name.as_ptr(),
+            )
+        }
+    }
+
+    fn br(&self, destination: LLVMBasicBlockRef) -> LLVMValueRef {
+        unsafe { LLVMBuildBr(self.0, destination) }
+    }
+
+    fn cond_br(
+        &self,
+        condition: LLVMValueRef,
+        then_block: LLVMBasicBlockRef,
+        else_block: LLVMBasicBlockRef,
+    ) -> LLVMValueRef {
+        unsafe { LLVMBuildCondBr(self.0, condition, then_block, else_block) }
+    }
+
+    fn phi(
+        &self,
+        value_type: LLVMTypeRef,
+        incoming: &[(LLVMValueRef, LLVMBasicBlockRef)],
+    ) -> LLVMValueRef {
+        let name = CString::new("phi").unwrap();
+        let phi = unsafe { LLVMBuildPhi(self.0, value_type, name.as_ptr()) };
+
+        let (mut values, mut blocks): (Vec<_>, Vec<_>) = incoming.iter().copied().unzip();
+        unsafe {
+            LLVMAddIncoming(
+                phi,
+                values.as_mut_ptr(),
+                blocks.as_mut_ptr(),
+                incoming.len() as c_uint,
+            );
+        };
+
+        phi
+    }
 }
 
 impl Drop for LLVMBuilder {
@@ -291,3 +1247,437 @@ fn drop(&mut self) {
         unsafe { LLVMDisposeBuilder(self.0) };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        diagnostic_consumer::{DiagnosticConsumer, IgnoreDiagnosticConsumer},
+        lexer::Lexer,
+        parser::Parser,
+        source_file::SourceFile,
+    };
+
+    use std::{cell::RefCell, rc::Rc};
+
+    fn compile(source_file: &SourceFile) -> Codegen {
+        let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            IgnoreDiagnosticConsumer,
+        ))));
+        let tokens = Lexer::new(diagnostic_engine.clone(), source_file).tokenize();
+        let translation_unit = Parser::new(diagnostic_engine, tokens)
+            .parse()
+            .translation_unit;
+
+        let codegen = Codegen::new(&source_file.path);
+        codegen.codegen(&translation_unit).unwrap();
+
+        codegen
+    }
+
+    /// Records the id, location and message of every reported diagnostic
+    #[derive(Debug, Default)]
+    struct RecordingDiagnosticConsumer {
+        diagnostics: Rc<RefCell<Vec<(DiagnosticId, String, String)>>>,
+    }
+
+    impl DiagnosticConsumer for RecordingDiagnosticConsumer {
+        fn report(&self, diagnostic: &Diagnostic) {
+            self.diagnostics.borrow_mut().push((
+                diagnostic.id,
+                diagnostic.source_range.begin.to_string(),
+                diagnostic.message.clone(),
+            ));
+        }
+    }
+
+    fn compile_to_ir(source_file: &SourceFile) -> String {
+        compile(source_file).ir_string()
+    }
+
+    #[test]
+    fn test_function_introspection() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int helper(void) { return 1; }\nint main(void) { return 0; }\n",
+        );
+
+        let codegen = compile(&source_file);
+
+        assert_eq!(codegen.function_count(), 2);
+        assert_eq!(codegen.function_names(), ["helper", "main"]);
+    }
+
+    #[test]
+    fn test_bool_return_is_normalized() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "_Bool is_set(void) { return 42; }\n_Bool is_clear(void) { }\n",
+        );
+
+        let ir = compile_to_ir(&source_file);
+
+        assert!(ir.contains("define i1 @is_set()"));
+        assert!(ir.contains("ret i1 true"));
+        assert!(ir.contains("define i1 @is_clear()"));
+        assert!(ir.contains("ret i1 false"));
+    }
+
+    #[test]
+    fn test_comparison_results_are_widened_once() {
+        let source_file = SourceFile::new("test.c", "int main(void) { return (1 < 2) + (3 < 4); }");
+
+        let codegen = compile(&source_file);
+
+        // Both `i1` results are widened to `int` before the addition, which folds to 2
+        assert!(codegen.verify().is_ok());
+        assert!(codegen.ir_string().contains("ret i32 2"));
+    }
+
+    #[test]
+    fn test_extern_function_declaration() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "extern int answer(void);\nint main(void) { return 0; }\n",
+        );
+
+        let codegen = compile(&source_file);
+
+        assert!(codegen.ir_string().contains("declare i32 @answer()"));
+        assert_eq!(codegen.function_names(), ["answer", "main"]);
+    }
+
+    #[test]
+    fn test_extern_declaration_of_defined_function() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "extern int main(void);\nint main(void) { return 0; }\n",
+        );
+
+        let codegen = compile(&source_file);
+
+        // The definition must reuse the declaration instead of adding a second function
+        assert_eq!(codegen.function_names(), ["main"]);
+        assert!(!codegen.ir_string().contains("declare"));
+    }
+
+    #[test]
+    fn test_source_map_associates_return_line() {
+        let source_file = SourceFile::new("test.c", "int main(void) {\n    return -(~1);\n}\n");
+
+        let codegen = compile(&source_file);
+
+        assert_eq!(
+            codegen.source_map_json(),
+            r#"{"source_file":"test.c","lines":[{"line":2,"instructions":["ret i32 2"]}]}"#
+        );
+    }
+
+    #[test]
+    fn test_source_map_folds_constant_addition() {
+        let source_file = SourceFile::new("test.c", "int main(void) {\n    return 1+2;\n}\n");
+
+        let codegen = compile(&source_file);
+
+        // The addition of two constants is folded, so only the return is mapped
+        assert_eq!(
+            codegen.source_map_json(),
+            r#"{"source_file":"test.c","lines":[{"line":2,"instructions":["ret i32 3"]}]}"#
+        );
+    }
+
+    #[test]
+    fn test_source_map_associates_addition_line() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) {\n    int a = 1;\n    return a+2;\n}\n",
+        );
+
+        let codegen = compile(&source_file);
+        let source_map = codegen.source_map_json();
+
+        assert!(source_map.contains(
+            r#"{"line":3,"instructions":["%load = load i32, i32* %a, align 4","%add = add i32 %load, 2","ret i32 %add"]}"#
+        ));
+    }
+
+    #[test]
+    fn test_source_map_empty_module() {
+        let codegen = Codegen::new("empty.c");
+
+        assert_eq!(
+            codegen.source_map_json(),
+            r#"{"source_file":"empty.c","lines":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_redefinition_is_codegen_error() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) { return 0; }\nint main(void) { return 1; }\n",
+        );
+        let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            IgnoreDiagnosticConsumer,
+        ))));
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+        let translation_unit = Parser::new(diagnostic_engine, tokens)
+            .parse()
+            .translation_unit;
+
+        let codegen = Codegen::new(&source_file.path);
+
+        assert!(matches!(
+            codegen.codegen(&translation_unit),
+            Err(Error::Codegen(message)) if message == "redefinition of function 'main'"
+        ));
+    }
+
+    #[test]
+    fn test_redefinition_is_reported_as_diagnostic() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) { return 0; }\nint main(void) { return 1; }\n",
+        );
+        let consumer = RecordingDiagnosticConsumer::default();
+        let diagnostics = Rc::clone(&consumer.diagnostics);
+        let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(consumer))));
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+        let translation_unit = Parser::new(diagnostic_engine.clone(), tokens)
+            .parse()
+            .translation_unit;
+
+        let codegen = Codegen::with_diagnostic_engine(&source_file.path, diagnostic_engine.clone());
+
+        assert!(matches!(
+            codegen.codegen(&translation_unit),
+            Err(Error::CodegenReported)
+        ));
+        assert_eq!(
+            *diagnostics.borrow(),
+            [(
+                DiagnosticId::FunctionRedefinition,
+                "test.c:2:1".to_string(),
+                "redefinition of function 'main'".to_string()
+            )]
+        );
+        assert!(diagnostic_engine.borrow().error_occurred());
+    }
+
+    #[test]
+    fn test_verify_rejects_block_without_terminator() {
+        let codegen = Codegen::new("test.c");
+
+        // A basic block must end with a terminator instruction like `ret`
+        let function_type = codegen.function_type(codegen.int32_type());
+        let function = codegen
+            .function("main", function_type, SourceRange::invalid())
+            .unwrap();
+        codegen.function_basic_block("entry", function);
+
+        assert!(matches!(codegen.verify(), Err(Error::LlvmVerification(_))));
+    }
+
+    #[test]
+    fn test_verify_empty_module() {
+        assert!(Codegen::new("empty.c").verify().is_ok());
+    }
+
+    #[test]
+    fn test_builder_instructions() {
+        let codegen = Codegen::new("test.c");
+        let builder = &codegen.builder;
+        let int8_type = codegen.context.int8_type();
+        let int32_type = codegen.int32_type();
+        let int64_type = codegen.context.int64_type();
+
+        let function = codegen
+            .function(
+                "main",
+                codegen.function_type(int32_type),
+                SourceRange::invalid(),
+            )
+            .unwrap();
+        let basic_block = |name: &str| {
+            codegen
+                .context
+                .create_basic_block_for_function(function, CString::new(name).unwrap())
+        };
+        let entry_block = basic_block("entry");
+        let then_block = basic_block("then");
+        let else_block = basic_block("else");
+        let merge_block = basic_block("merge");
+
+        builder.position_at_end(entry_block);
+        let pointer = builder.alloca(int32_type, "alloca");
+        builder.store(codegen.const_int(1), pointer);
+        let value = builder.load(int32_type, pointer);
+        let condition = builder.icmp(LLVMIntPredicate::LLVMIntEQ, value, codegen.const_int(1));
+        let second_condition =
+            builder.icmp(LLVMIntPredicate::LLVMIntSLT, value, codegen.const_int(2));
+        builder.zext(condition, int32_type);
+        builder.sext(second_condition, int64_type);
+        builder.trunc(value, int8_type);
+        builder.cond_br(condition, then_block, else_block);
+
+        builder.position_at_end(then_block);
+        builder.br(merge_block);
+
+        builder.position_at_end(else_block);
+        builder.br(merge_block);
+
+        builder.position_at_end(merge_block);
+        let phi = builder.phi(
+            int32_type,
+            &[
+                (codegen.const_int(1), then_block),
+                (codegen.const_int(2), else_block),
+            ],
+        );
+        builder.ret(phi);
+
+        let ir = codegen.ir_string();
+        assert!(ir.contains("%alloca = alloca i32"));
+        assert!(ir.contains("store i32 1, "));
+        assert!(ir.contains("%load = load i32, "));
+        assert!(ir.contains("%cmp = icmp eq i32 %load, 1"));
+        // Instructions with the same name are numbered to keep them unique
+        assert!(ir.contains("%cmp1 = icmp slt i32 %load, 2"));
+        assert!(ir.contains("%zext = zext i1 %cmp to i32"));
+        assert!(ir.contains("%sext = sext i1 %cmp1 to i64"));
+        assert!(ir.contains("%trunc = trunc i32 %load to i8"));
+        assert!(ir.contains("br i1 %cmp, label %then, label %else"));
+        assert!(ir.contains("br label %merge"));
+        assert!(ir.contains("%phi = phi i32 [ 1, %then ], [ 2, %else ]"));
+        assert!(codegen.verify().is_ok());
+    }
+
+    #[test]
+    fn test_binary_operations() {
+        let source_file =
+            SourceFile::new("test.c", "int main(void) { return 1 + 2 * 3 - 8 / 4 % 3; }");
+
+        // Operations on constants are folded by the builder
+        assert!(compile_to_ir(&source_file).contains("ret i32 5"));
+    }
+
+    #[test]
+    fn test_function_introspection_empty_module() {
+        let codegen = Codegen::new("empty.c");
+
+        assert_eq!(codegen.function_count(), 0);
+        assert!(codegen.function_names().is_empty());
+    }
+
+    #[test]
+    fn test_codegen_is_deterministic() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int first(void) { return 1; }\nint second(void) { return -(~2); }\nint main(void) { return 0; }\n",
+        );
+
+        let first_ir = compile_to_ir(&source_file);
+        let second_ir = compile_to_ir(&source_file);
+
+        assert_eq!(first_ir, second_ir);
+    }
+
+    #[test]
+    fn test_codegen_emits_functions_in_source_order() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int zeta(void) { return 1; }\nint alpha(void) { return 2; }\nint main(void) { return 0; }\n",
+        );
+
+        let ir = compile_to_ir(&source_file);
+        let zeta = ir.find("@zeta").unwrap();
+        let alpha = ir.find("@alpha").unwrap();
+        let main = ir.find("@main").unwrap();
+
+        assert!(zeta < alpha && alpha < main);
+    }
+
+    #[test]
+    fn test_module_hash() {
+        assert_eq!(fnv1a_hash(b"a"), 0xAF63_DC4C_8601_EC8C);
+
+        let source_file = SourceFile::new("test.c", "int main(void) { return 0; }");
+        let other_source_file = SourceFile::new("test.c", "int main(void) { return 1; }");
+
+        assert_eq!(
+            compile(&source_file).module_hash(),
+            compile(&source_file).module_hash()
+        );
+        assert_ne!(
+            compile(&source_file).module_hash(),
+            compile(&other_source_file).module_hash()
+        );
+    }
+
+    #[test]
+    fn test_local_variables() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) {\n  int x = 3;\n  { int y; y = x = x + 1; }\n  return x;\n}\n",
+        );
+
+        let codegen = compile(&source_file);
+        let ir = codegen.ir_string();
+
+        assert!(ir.contains("%x = alloca i32"));
+        assert!(ir.contains("%y = alloca i32"));
+        assert!(ir.contains("store i32 3, i32* %x"));
+        assert!(codegen.verify().is_ok());
+    }
+
+    #[test]
+    fn test_volatile_variable_accesses() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) {\n  volatile int x = 1;\n  _Bool b = 2;\n  return x + b;\n}\n",
+        );
+
+        let ir = compile_to_ir(&source_file);
+
+        assert!(ir.contains("store volatile i32 1, i32* %x"));
+        assert!(ir.contains("load volatile i32, i32* %x"));
+        assert!(ir.contains("store i1 true, i1* %b"));
+        assert!(!ir.contains("load volatile i1"));
+    }
+
+    #[test]
+    fn test_while_loop() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) {\n  int x = 10;\n  while (x) x = x - 1;\n  while (0);\n  return x;\n}\n",
+        );
+
+        let codegen = compile(&source_file);
+        let ir = codegen.ir_string();
+
+        assert!(ir.contains("br i1 %cmp, label %while.body, label %while.exit"));
+        // The end of the body jumps back to check the condition again
+        assert!(ir.contains("while.body:"));
+        assert!(ir.contains("br label %while.cond\n"));
+        // The empty body of the second loop only consists of the back-edge
+        assert!(ir.contains("br i1 false, label %while.body3, label %while.exit4"));
+        assert!(ir.contains("while.body3:                                      ; preds = %while.cond2\n  br label %while.cond2\n"));
+        assert!(codegen.verify().is_ok());
+    }
+
+    #[test]
+    fn test_function_call() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "_Bool is_set(void) { return 1; }\nint helper(void) { return 2; }\nint main(void) { return helper() + is_set(); }\n",
+        );
+
+        let codegen = compile(&source_file);
+        let ir = codegen.ir_string();
+
+        assert!(ir.contains("%call = call i32 @helper()"));
+        assert!(ir.contains("%call1 = call i1 @is_set()"));
+        assert!(ir.contains("%zext = zext i1 %call1 to i32"));
+        assert!(codegen.verify().is_ok());
+    }
+}
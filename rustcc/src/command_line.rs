@@ -1,11 +1,122 @@
+use crate::diagnostic::DiagnosticId;
+
 use clap::{
-    Arg, ArgAction, Command, ValueHint, crate_authors, crate_description, crate_name, crate_version,
+    Arg, ArgAction, Command, ValueHint, crate_authors, crate_description, crate_name,
+    crate_version, value_parser,
 };
+use std::{collections::HashMap, path::Path};
 
 pub const ARG_INPUT_FILE: &str = "source_file";
 pub const ARG_PRINT_TOKENS: &str = "PRINT_TOKENS";
 pub const ARG_PRINT_AST: &str = "PRINT_AST";
+pub const ARG_DUMP_SCOPES: &str = "DUMP_SCOPES";
 pub const ARG_PRINT_IR: &str = "PRINT_IR";
+pub const ARG_PRINT_SOURCE_MAP: &str = "PRINT_SOURCE_MAP";
+pub const ARG_SHOW_INPUT: &str = "SHOW_INPUT";
+pub const ARG_MODULE_HASH: &str = "MODULE_HASH";
+pub const ARG_WARNINGS: &str = "WARNINGS";
+pub const ARG_IGNORE_ALL_WARNINGS: &str = "IGNORE_ALL_WARNINGS";
+pub const ARG_WERROR_EXIT_CODE: &str = "WERROR_EXIT_CODE";
+pub const ARG_WRITE_DEPENDENCY_FILE: &str = "WRITE_DEPENDENCY_FILE";
+pub const ARG_DEPENDENCY_FILE: &str = "DEPENDENCY_FILE";
+pub const ARG_DIAGNOSTICS_FORMAT: &str = "DIAGNOSTICS_FORMAT";
+pub const ARG_DIAGNOSTICS_TO_STDERR: &str = "DIAGNOSTICS_TO_STDERR";
+pub const ARG_DIGIT_SEPARATORS: &str = "DIGIT_SEPARATORS";
+pub const ARG_DOLLARS_IN_IDENTIFIERS: &str = "DOLLARS_IN_IDENTIFIERS";
+pub const ARG_PRINT_DIAGNOSTIC_FLAGS: &str = "PRINT_DIAGNOSTIC_FLAGS";
+pub const ARG_OUTPUT: &str = "OUTPUT";
+pub const ARG_COMPILE_ONLY: &str = "COMPILE_ONLY";
+pub const ARG_ERROR_LIMIT: &str = "ERROR_LIMIT";
+pub const ARG_MESSAGE_LENGTH: &str = "MESSAGE_LENGTH";
+pub const ARG_EMIT: &str = "EMIT";
+pub const ARG_TARGET: &str = "TARGET";
+pub const ARG_STD: &str = "STD";
+pub const ARG_PEDANTIC_ERRORS: &str = "PEDANTIC_ERRORS";
+
+pub const EMIT_IR: &str = "ir";
+pub const EMIT_ASM: &str = "asm";
+pub const EMIT_OBJ: &str = "obj";
+
+/// Without `--emit` output files with these extensions receive LLVM IR or assembly instead of an
+/// object file
+pub const IR_FILE_EXTENSION: &str = "ll";
+pub const ASM_FILE_EXTENSION: &str = "s";
+pub const OBJ_FILE_EXTENSION: &str = "o";
+
+pub const WARNING_ERROR: &str = "error";
+pub const WARNING_DISABLE_PREFIX: &str = "no-";
+
+pub const STD_C89: &str = "c89";
+pub const STD_C99: &str = "c99";
+pub const STD_C11: &str = "c11";
+pub const STD_C17: &str = "c17";
+
+pub const DIAGNOSTICS_FORMAT_DEFAULT: &str = "default";
+pub const DIAGNOSTICS_FORMAT_MINIMAL: &str = "minimal";
+pub const DIAGNOSTICS_FORMAT_JSON: &str = "json";
+
+/// Returns the extension of the default output file for an `--emit` kind
+#[must_use]
+pub fn emit_file_extension(emit: &str) -> &'static str {
+    match emit {
+        EMIT_IR => IR_FILE_EXTENSION,
+        EMIT_ASM => ASM_FILE_EXTENSION,
+        _ => OBJ_FILE_EXTENSION,
+    }
+}
+
+/// Returns the `--emit` kind matching the extension of an output file, an object file by default
+#[must_use]
+pub fn emit_for_output_file(path: &Path) -> &'static str {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(IR_FILE_EXTENSION) => EMIT_IR,
+        Some(ASM_FILE_EXTENSION) => EMIT_ASM,
+        _ => EMIT_OBJ,
+    }
+}
+
+/// Returns the diagnostic for a warning option like `null-character` or `no-null-character`
+#[must_use]
+pub fn warning_option_diagnostic(option: &str) -> Option<DiagnosticId> {
+    let flag = option
+        .strip_prefix(WARNING_DISABLE_PREFIX)
+        .unwrap_or(option);
+
+    DiagnosticId::from_flag_name(&format!("-W{flag}"))
+}
+
+/// Finds warning options like `no-null-character` which override an earlier option for the same
+/// warning like `null-character`. Returns the earlier and the overriding option of every conflict,
+/// repeating the same option is no conflict.
+#[must_use]
+pub fn conflicting_warning_options<'a, I: IntoIterator<Item = &'a str>>(
+    options: I,
+) -> Vec<(&'a str, &'a str)> {
+    let mut last_options: HashMap<DiagnosticId, &str> = HashMap::new();
+    let mut conflicts = Vec::new();
+
+    for option in options {
+        let Some(id) = warning_option_diagnostic(option) else {
+            continue;
+        };
+
+        if let Some(last_option) = last_options.insert(id, option) {
+            if last_option != option {
+                conflicts.push((last_option, option));
+            }
+        }
+    }
+
+    conflicts
+}
+
+fn parse_warning_option(option: &str) -> Result<String, String> {
+    if option == WARNING_ERROR || warning_option_diagnostic(option).is_some() {
+        return Ok(option.to_string());
+    }
+
+    Err(format!("unknown warning option '-W{option}'"))
+}
 
 pub fn command_line() -> Command {
     Command::new(crate_name!())
@@ -14,10 +125,38 @@ pub fn command_line() -> Command {
         .version(crate_version!())
         .arg(
             Arg::new(ARG_INPUT_FILE)
-                .required(true)
-                .help("The source file to compile")
+                .required_unless_present(ARG_PRINT_DIAGNOSTIC_FLAGS)
+                .help("The source file to compile, '-' reads it from the standard input")
                 .value_hint(ValueHint::FilePath),
         )
+        .arg(
+            Arg::new(ARG_OUTPUT)
+                .short('o')
+                .long("output")
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("Write the output to <file>, without '--emit' an '.ll' extension writes LLVM IR and an '.s' extension assembly instead of an object file"),
+        )
+        .arg(
+            Arg::new(ARG_EMIT)
+                .long("emit")
+                .value_name("kind")
+                .value_parser([EMIT_IR, EMIT_ASM, EMIT_OBJ])
+                .conflicts_with(ARG_COMPILE_ONLY)
+                .help("The kind of output to write, defaults to the source file with a '.ll', '.s' or '.o' extension"),
+        )
+        .arg(
+            Arg::new(ARG_TARGET)
+                .long("target")
+                .value_name("triple")
+                .help("Generate code for the target <triple>, e.g. 'x86_64-unknown-linux-gnu', instead of the host"),
+        )
+        .arg(
+            Arg::new(ARG_COMPILE_ONLY)
+                .short('c')
+                .action(ArgAction::SetTrue)
+                .help("Write an object file, defaults to the source file with a '.o' extension"),
+        )
         .arg(
             Arg::new(ARG_PRINT_TOKENS)
                 .long("print-tokens")
@@ -30,10 +169,133 @@ pub fn command_line() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Print the abstract syntax tree"),
         )
+        .arg(
+            Arg::new(ARG_DUMP_SCOPES)
+                .long("dump-scopes")
+                .action(ArgAction::SetTrue)
+                .help("Print the scopes of every function and the variables declared in them"),
+        )
         .arg(
             Arg::new(ARG_PRINT_IR)
                 .long("print-ir")
                 .action(ArgAction::SetTrue)
                 .help("Print the LLVM intermediate representation"),
         )
+        .arg(
+            Arg::new(ARG_PRINT_SOURCE_MAP)
+                .long("print-source-map")
+                .action(ArgAction::SetTrue)
+                .help("Print a JSON map from source lines to the generated LLVM instructions"),
+        )
+        .arg(
+            Arg::new(ARG_MODULE_HASH)
+                .long("module-hash")
+                .action(ArgAction::SetTrue)
+                .help("Print a stable hash of the generated LLVM intermediate representation, e.g. to detect codegen changes in CI"),
+        )
+        .arg(
+            Arg::new(ARG_SHOW_INPUT)
+                .long("show-input")
+                .action(ArgAction::SetTrue)
+                .help("Print the source code as read by rustcc with line numbers to stderr, e.g. to check the output of a preprocessor"),
+        )
+        .arg(
+            Arg::new(ARG_WARNINGS)
+                .short('W')
+                .action(ArgAction::Append)
+                .value_name("warning")
+                .value_parser(parse_warning_option)
+                .help("Configure warnings, '-Werror' turns all warnings into errors, '-W<flag>' and '-Wno-<flag>' enable or disable a single warning"),
+        )
+        .arg(
+            Arg::new(ARG_IGNORE_ALL_WARNINGS)
+                .short('w')
+                .action(ArgAction::SetTrue)
+                .help("Suppress all warnings"),
+        )
+        .arg(
+            Arg::new(ARG_WERROR_EXIT_CODE)
+                .long("werror-exit-code")
+                .value_name("code")
+                .value_parser(value_parser!(u8))
+                .help("Exit code used when only '-Werror' warnings failed the compilation"),
+        )
+        .arg(
+            Arg::new(ARG_WRITE_DEPENDENCY_FILE)
+                .long("MD")
+                .action(ArgAction::SetTrue)
+                .help("Write a Makefile-style dependency file"),
+        )
+        .arg(
+            Arg::new(ARG_DEPENDENCY_FILE)
+                .long("MF")
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .requires(ARG_WRITE_DEPENDENCY_FILE)
+                .help("The dependency file to write, defaults to the source file with a '.d' extension"),
+        )
+        .arg(
+            Arg::new(ARG_DIAGNOSTICS_FORMAT)
+                .long("fdiagnostics-format")
+                .value_name("format")
+                .value_parser([
+                    DIAGNOSTICS_FORMAT_DEFAULT,
+                    DIAGNOSTICS_FORMAT_MINIMAL,
+                    DIAGNOSTICS_FORMAT_JSON,
+                ])
+                .default_value(DIAGNOSTICS_FORMAT_DEFAULT)
+                .help("The format of diagnostics, 'minimal' omits the source location and 'json' prints one JSON object per line to stdout"),
+        )
+        .arg(
+            Arg::new(ARG_DIAGNOSTICS_TO_STDERR)
+                .long("fdiagnostics-to-stderr")
+                .action(ArgAction::SetTrue)
+                .help("Print all diagnostics to stderr, by default warnings and notes go to stdout"),
+        )
+        .arg(
+            Arg::new(ARG_ERROR_LIMIT)
+                .long("ferror-limit")
+                .value_name("N")
+                .value_parser(value_parser!(u64))
+                .help("Stop reporting diagnostics after N errors, 0 means no limit"),
+        )
+        .arg(
+            Arg::new(ARG_MESSAGE_LENGTH)
+                .long("fmessage-length")
+                .value_name("N")
+                .value_parser(value_parser!(usize))
+                .help("Wrap diagnostic messages at N columns, 0 means no wrapping"),
+        )
+        .arg(
+            Arg::new(ARG_STD)
+                .long("std")
+                .value_name("standard")
+                .value_parser([STD_C89, STD_C99, STD_C11, STD_C17])
+                .default_value(STD_C17)
+                .help("The C standard to compile for"),
+        )
+        .arg(
+            Arg::new(ARG_PEDANTIC_ERRORS)
+                .long("pedantic-errors")
+                .action(ArgAction::SetTrue)
+                .help("Report code the selected C standard doesn't allow as errors instead of warnings"),
+        )
+        .arg(
+            Arg::new(ARG_DIGIT_SEPARATORS)
+                .long("fdigit-separators")
+                .action(ArgAction::SetTrue)
+                .help("Allow ' and _ as digit separators in integer literals, e.g. 1'000 or 1_000"),
+        )
+        .arg(
+            Arg::new(ARG_DOLLARS_IN_IDENTIFIERS)
+                .long("fdollars-in-identifiers")
+                .action(ArgAction::SetTrue)
+                .help("Allow '$' in identifiers"),
+        )
+        .arg(
+            Arg::new(ARG_PRINT_DIAGNOSTIC_FLAGS)
+                .long("print-diagnostic-flags")
+                .action(ArgAction::SetTrue)
+                .help("Print all warning flags which can be passed to '-W' and exit"),
+        )
 }
@@ -0,0 +1,86 @@
+use std::{fs, io, path::Path};
+
+/// Formats a Makefile-style dependency rule stating that `target` depends on all `dependencies`.
+///
+/// Spaces in paths are escaped, so `make` doesn't treat them as separators.
+///
+/// # Examples
+///
+/// ```
+/// # use rustcc::dependency_file::format_dependency_rule;
+/// let rule = format_dependency_rule("main.o", &["main.c"]);
+///
+/// assert_eq!(rule, "main.o: main.c\n");
+/// ```
+#[must_use]
+pub fn format_dependency_rule<S: AsRef<str>>(target: &str, dependencies: &[S]) -> String {
+    let mut rule = format!("{}:", escape_path(target));
+
+    for dependency in dependencies {
+        rule.push(' ');
+        rule.push_str(&escape_path(dependency.as_ref()));
+    }
+
+    rule.push('\n');
+
+    rule
+}
+
+/// Writes a Makefile-style dependency file to `path` stating that `target` depends on all `dependencies`.
+pub fn write_dependency_file<P: AsRef<Path>, S: AsRef<str>>(
+    path: P,
+    target: &str,
+    dependencies: &[S],
+) -> io::Result<()> {
+    fs::write(path, format_dependency_rule(target, dependencies))
+}
+
+fn escape_path(path: &str) -> String {
+    path.replace(' ', "\\ ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_dependency_rule_single_dependency() {
+        assert_eq!(
+            format_dependency_rule("main.o", &["main.c"]),
+            "main.o: main.c\n"
+        );
+    }
+
+    #[test]
+    fn test_format_dependency_rule_multiple_dependencies() {
+        assert_eq!(
+            format_dependency_rule("main.o", &["main.c", "include/header.h"]),
+            "main.o: main.c include/header.h\n"
+        );
+    }
+
+    #[test]
+    fn test_format_dependency_rule_no_dependencies() {
+        let dependencies: &[&str] = &[];
+
+        assert_eq!(format_dependency_rule("main.o", dependencies), "main.o:\n");
+    }
+
+    #[test]
+    fn test_format_dependency_rule_escapes_spaces() {
+        assert_eq!(
+            format_dependency_rule("my file.o", &["my file.c"]),
+            "my\\ file.o: my\\ file.c\n"
+        );
+    }
+
+    #[test]
+    fn test_write_dependency_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("main.d");
+
+        write_dependency_file(&path, "main.o", &["main.c"]).unwrap();
+
+        assert_eq!(fs::read_to_string(path).unwrap(), "main.o: main.c\n");
+    }
+}
@@ -1,5 +1,5 @@
 use crate::source_range::SourceRange;
-use DiagnosticLevel::{Error, Warning};
+use DiagnosticLevel::{Error, FatalError, Ignored, Note, Warning};
 
 macro_rules! define_diagnostics {
     ($(
@@ -22,6 +22,13 @@ pub const fn level(&self) -> DiagnosticLevel {
                 }
             }
 
+            /// All diagnostics in the order they are defined
+            pub const ALL: &'static [DiagnosticId] = &[
+                $(
+                    DiagnosticId::$name,
+                )*
+            ];
+
             #[must_use]
             pub const fn flag_name(&self) -> &'static str {
                 match self {
@@ -35,16 +42,36 @@ pub const fn flag_name(&self) -> &'static str {
 }
 
 define_diagnostics! {
+    // Command line warnings
+    OptionConflict(Note, "-Woption-conflict"),
+
+    // Command line errors
+    UnknownTarget(Error, ""),
+
     // Lexer warnings
     NullCharacter(Warning, "-Wnull-character"),
+    LineTooLong(Warning, "-Wline-too-long"),
+    FileTooBig(Warning, "-Wfile-too-big"),
+    UnknownEscapeSequence(Warning, "-Wunknown-escape-sequence"),
+    LineCommentNotAllowed(Warning, "-Wcomment"),
 
     // Lexer errors
     UnexpectedCharacter(Error, ""),
     IntegerLiteralTooLarge(Error, ""),
+    InvalidIntegerSuffix(Error, ""),
+    InvalidHexLiteral(Error, ""),
+    InvalidNumericSuffix(Error, ""),
+    MisplacedDigitSeparator(Error, ""),
+    EmptyCharacterLiteral(Error, ""),
+    UnterminatedCharacterLiteral(Error, ""),
+    MultiCharacterLiteral(Error, ""),
+    UnterminatedStringLiteral(Error, ""),
 
     // Lexer fatal errors
 
     // Parser warnings
+    StrictPrototypes(Ignored, "-Wstrict-prototypes"),
+    DeprecatedRegister(Ignored, "-Wdeprecated"),
 
     // Parser errors
     ExpectedFunctionReturnType(Error, ""),
@@ -54,18 +81,79 @@ pub const fn flag_name(&self) -> &'static str {
     ExpectedLeftBrace(Error, ""),
     ExpectedRightBrace(Error, ""),
     ExpectedSemicolon(Error, ""),
-    ExpectedReturnKeyword(Error, ""),
     ExpectedIntegerLiteral(Error, ""),
     ExpectedVoidInParameterList(Error, ""),
     ExpectedExpression(Error, ""),
     MissingClosingParenthesis(Error, ""),
+    RestrictRequiresPointer(Error, ""),
+    ExpectedIdentifier(Error, ""),
+    UndeclaredIdentifier(Error, ""),
+    VariableRedefinition(Error, ""),
+    ExpressionNotAssignable(Error, ""),
+    UndeclaredFunction(Error, ""),
+    TooManyArguments(Error, ""),
+    CallWithoutPrototypeNotSupported(Error, ""),
+    TrailingComma(Error, ""),
+    StringLiteralNotSupported(Error, ""),
+    DeclarationAsLoopBody(Error, ""),
 
     // Parser fatal errors
+
+    // Codegen errors
+    FunctionRedefinition(Error, ""),
+    ConflictingTypes(Error, ""),
+    CodegenFailed(Error, ""),
+
+    // Diagnostic engine fatal errors
+    TooManyErrors(FatalError, ""),
+}
+
+impl DiagnosticId {
+    /// Returns the diagnostic controlled by the given warning flag like `-Wnull-character`
+    #[must_use]
+    pub fn from_flag_name(flag_name: &str) -> Option<DiagnosticId> {
+        if flag_name.is_empty() {
+            return None;
+        }
+
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|id| id.flag_name() == flag_name)
+    }
+
+    /// Whether the diagnostic is about code the selected C standard doesn't allow, these are
+    /// turned into errors by `--pedantic-errors`
+    #[must_use]
+    pub const fn is_pedantic(&self) -> bool {
+        matches!(self, DiagnosticId::LineCommentNotAllowed)
+    }
+
+    /// Whether the diagnostic is about the command line instead of the source code, so it has no
+    /// source location
+    #[must_use]
+    pub const fn is_command_line(&self) -> bool {
+        matches!(
+            self,
+            DiagnosticId::OptionConflict | DiagnosticId::UnknownTarget
+        )
+    }
+
+    /// All warning flags like `-Wnull-character` in the order their diagnostics are defined
+    pub fn flag_names() -> impl Iterator<Item = &'static str> {
+        Self::ALL
+            .iter()
+            .map(DiagnosticId::flag_name)
+            .filter(|flag_name| !flag_name.is_empty())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
 pub enum DiagnosticLevel {
     Ignored,
+    /// Informs about something which is not a problem, it is neither counted nor promoted by
+    /// `-Werror`
+    Note,
     Warning,
     Error,
     FatalError,
@@ -101,6 +189,11 @@ pub const fn is_ignored(&self) -> bool {
         matches!(self.level, DiagnosticLevel::Ignored)
     }
 
+    #[must_use]
+    pub const fn is_note(&self) -> bool {
+        matches!(self.level, DiagnosticLevel::Note)
+    }
+
     #[must_use]
     pub const fn is_warning(&self) -> bool {
         matches!(self.level, DiagnosticLevel::Warning)
@@ -127,8 +220,15 @@ pub fn upgrade_warning_to_error(&mut self) {
         }
     }
 
+    pub fn enable_warning(&mut self) {
+        if self.level == DiagnosticLevel::Ignored {
+            self.level = DiagnosticLevel::Warning;
+        }
+    }
+
+    /// Ignores a warning or note, they are disabled by the same `-Wno-<flag>` options
     pub fn ignore_warning(&mut self) {
-        if self.level == DiagnosticLevel::Warning {
+        if matches!(self.level, DiagnosticLevel::Warning | DiagnosticLevel::Note) {
             self.level = DiagnosticLevel::Ignored;
         }
     }
@@ -1,8 +1,18 @@
 use colored::Colorize;
 
-use crate::diagnostic::{Diagnostic, DiagnosticLevel};
+use crate::{
+    diagnostic::{Diagnostic, DiagnosticId, DiagnosticLevel},
+    json::escape_json,
+    source_location::SourceLocation,
+    source_range::SourceRange,
+};
 
-use std::fmt::Debug;
+use std::{
+    cell::RefCell,
+    fmt::{self, Debug},
+    iter,
+    rc::Rc,
+};
 
 pub trait DiagnosticConsumer: Debug {
     fn report(&self, diagnostic: &Diagnostic);
@@ -18,10 +28,54 @@ impl DiagnosticConsumer for IgnoreDiagnosticConsumer {
     fn report(&self, _diagnostic: &Diagnostic) {}
 }
 
+// -- Collecting Diagnostic Consumer --
+
+/// A diagnostic consumer which only remembers the ids of the reported diagnostics. Clones share the
+/// collected ids, so a clone can inspect them after the original was handed to an engine.
+#[derive(Default, Debug, Clone)]
+pub struct CollectingDiagnosticConsumer {
+    ids: Rc<RefCell<Vec<DiagnosticId>>>,
+}
+
+impl CollectingDiagnosticConsumer {
+    /// The ids of all diagnostics reported so far in the order they were reported
+    #[must_use]
+    pub fn ids(&self) -> Vec<DiagnosticId> {
+        self.ids.borrow().clone()
+    }
+}
+
+impl DiagnosticConsumer for CollectingDiagnosticConsumer {
+    fn report(&self, diagnostic: &Diagnostic) {
+        self.ids.borrow_mut().push(diagnostic.id);
+    }
+}
+
 // -- Default Diagnostic Consumer --
 
 #[derive(Default, Debug)]
-pub struct DefaultDiagnosticConsumer;
+pub struct DefaultDiagnosticConsumer {
+    all_to_stderr: bool,
+    message_length: usize,
+}
+
+impl DefaultDiagnosticConsumer {
+    /// If `all_to_stderr` is true warnings and notes are printed to stderr as well
+    #[must_use]
+    pub const fn new(all_to_stderr: bool) -> Self {
+        Self {
+            all_to_stderr,
+            message_length: 0,
+        }
+    }
+
+    /// Wrap messages at `message_length` columns, 0 disables wrapping
+    #[must_use]
+    pub const fn with_message_length(mut self, message_length: usize) -> Self {
+        self.message_length = message_length;
+        self
+    }
+}
 
 /// The default consumer prints all warnings to stdout and errors to stderr
 impl DiagnosticConsumer for DefaultDiagnosticConsumer {
@@ -35,33 +89,423 @@ fn report(&self, diagnostic: &Diagnostic) {
             "May not report empty messages"
         );
 
-        let begin_location = &diagnostic.source_range.begin.to_string().bold();
-        let message = &diagnostic.message;
+        // A diagnostic about the command line belongs to no source location
+        let begin_location = if diagnostic.id.is_command_line() {
+            String::new()
+        } else {
+            format!("{}: ", diagnostic.source_range.begin)
+        };
+        let message = &wrap_message(
+            &diagnostic.message,
+            begin_location.len() + level_name(diagnostic.level).len() + ": ".len(),
+            self.message_length,
+        );
+        let begin_location = &begin_location.bold();
 
         match diagnostic.level {
-            DiagnosticLevel::Warning => {
-                println!("{begin_location}: {} {message}", "warning:".yellow())
-            }
+            DiagnosticLevel::Note => print_line(
+                self.all_to_stderr,
+                format_args!("{begin_location}{} {message}", "note:".bold()),
+            ),
+            DiagnosticLevel::Warning => print_line(
+                self.all_to_stderr,
+                format_args!("{begin_location}{} {message}", "warning:".yellow()),
+            ),
             DiagnosticLevel::Error => {
-                eprintln!("{begin_location}: {} {message}", "error:".red().bold())
+                eprintln!("{begin_location}{} {message}", "error:".red().bold())
             }
             DiagnosticLevel::FatalError => {
-                eprintln!(
-                    "{begin_location}: {} {message}",
-                    "fatal error:".red().bold()
-                )
+                eprintln!("{begin_location}{} {message}", "fatal error:".red().bold())
             }
             DiagnosticLevel::Ignored => {
                 unreachable!("Unexpected diagnostic level");
             }
         }
 
+        // Print the offending source line, next to the message so errors stay on stderr
+        if let Some((source_line, caret_line)) = source_snippet(&diagnostic.source_range) {
+            let to_stderr = self.all_to_stderr || diagnostic.is_error_or_fatal();
+
+            print_line(to_stderr, format_args!("{source_line}"));
+            print_line(to_stderr, format_args!("{}", caret_line.green().bold()));
+        }
+
         // Print any associated notes
         for note in &diagnostic.notes {
             let note_begin_location = &note.source_range.begin;
             let note_message = &note.message;
 
-            println!("{note_begin_location}: note: {note_message}");
+            print_line(
+                self.all_to_stderr,
+                format_args!("{note_begin_location}: note: {note_message}"),
+            );
+        }
+    }
+}
+
+// -- Minimal Diagnostic Consumer --
+
+#[derive(Default, Debug)]
+pub struct MinimalDiagnosticConsumer {
+    all_to_stderr: bool,
+    message_length: usize,
+}
+
+impl MinimalDiagnosticConsumer {
+    /// If `all_to_stderr` is true warnings and notes are printed to stderr as well
+    #[must_use]
+    pub const fn new(all_to_stderr: bool) -> Self {
+        Self {
+            all_to_stderr,
+            message_length: 0,
         }
     }
+
+    /// Wrap messages at `message_length` columns, 0 disables wrapping
+    #[must_use]
+    pub const fn with_message_length(mut self, message_length: usize) -> Self {
+        self.message_length = message_length;
+        self
+    }
+}
+
+/// Prints diagnostics like the default consumer but without any source location, so the output
+/// stays stable when only line or column information changes
+impl DiagnosticConsumer for MinimalDiagnosticConsumer {
+    fn report(&self, diagnostic: &Diagnostic) {
+        debug_assert!(
+            !diagnostic.is_ignored(),
+            "May not report ignored diagnostics"
+        );
+        debug_assert!(
+            !diagnostic.message.is_empty(),
+            "May not report empty messages"
+        );
+
+        let message = &wrap_message(
+            &diagnostic.message,
+            level_name(diagnostic.level).len() + ": ".len(),
+            self.message_length,
+        );
+
+        match diagnostic.level {
+            DiagnosticLevel::Note => print_line(
+                self.all_to_stderr,
+                format_args!("{} {message}", "note:".bold()),
+            ),
+            DiagnosticLevel::Warning => print_line(
+                self.all_to_stderr,
+                format_args!("{} {message}", "warning:".yellow()),
+            ),
+            DiagnosticLevel::Error => eprintln!("{} {message}", "error:".red().bold()),
+            DiagnosticLevel::FatalError => eprintln!("{} {message}", "fatal error:".red().bold()),
+            DiagnosticLevel::Ignored => {
+                unreachable!("Unexpected diagnostic level");
+            }
+        }
+
+        // Print any associated notes
+        for note in &diagnostic.notes {
+            print_line(self.all_to_stderr, format_args!("note: {}", note.message));
+        }
+    }
+}
+
+// -- JSON Diagnostic Consumer --
+
+/// Prints every diagnostic as a JSON object on a line of its own to stdout, meant for editors and
+/// other tools. For example
+///
+/// ```json
+/// {"level":"error","message":"expected ';'","file":"main.c","line":2,"column":11,"flag":null,"notes":[]}
+/// ```
+///
+/// Notes are objects with the `message`, `file`, `line` and `column` fields. `file` is `null` for
+/// locations outside of any file, `line` and `column` for invalid locations. `flag` is the warning
+/// flag controlling the diagnostic, if any.
+#[derive(Default, Debug)]
+pub struct JsonDiagnosticConsumer;
+
+impl DiagnosticConsumer for JsonDiagnosticConsumer {
+    fn report(&self, diagnostic: &Diagnostic) {
+        debug_assert!(
+            !diagnostic.is_ignored(),
+            "May not report ignored diagnostics"
+        );
+
+        println!("{}", diagnostic_json(diagnostic));
+    }
+}
+
+/// Formats a diagnostic as a single line JSON object, see [`JsonDiagnosticConsumer`]
+fn diagnostic_json(diagnostic: &Diagnostic) -> String {
+    let flag = match diagnostic.id.flag_name() {
+        "" => "null".to_string(),
+        flag_name => format!("\"{}\"", escape_json(flag_name)),
+    };
+    let notes = diagnostic
+        .notes
+        .iter()
+        .map(|note| {
+            format!(
+                "{{\"message\":\"{}\",{}}}",
+                escape_json(&strip_colors(&note.message)),
+                location_json(&note.source_range.begin)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        "{{\"level\":\"{}\",\"message\":\"{}\",{},\"flag\":{flag},\"notes\":[{notes}]}}",
+        level_name(diagnostic.level),
+        escape_json(&strip_colors(&diagnostic.message)),
+        location_json(&diagnostic.source_range.begin)
+    )
+}
+
+/// Formats the `file`, `line` and `column` fields of a location
+fn location_json(location: &SourceLocation) -> String {
+    let file = location.source_file.map_or_else(
+        || "null".to_string(),
+        |source_file| format!("\"{}\"", escape_json(&source_file.path)),
+    );
+
+    if location.is_valid() {
+        format!(
+            "\"file\":{file},\"line\":{},\"column\":{}",
+            location.line, location.column
+        )
+    } else {
+        format!("\"file\":{file},\"line\":null,\"column\":null")
+    }
+}
+
+/// Returns the source line the range begins on and a line marking the range below it, with a `^`
+/// under the first character and `~` under the rest of the range. Ranges spanning multiple lines
+/// are only marked up to the end of their first line.
+///
+/// Tabs before the range are copied to the caret line so it stays aligned however wide tabs are
+/// displayed. Returns `None` if the range has no source file.
+fn source_snippet(source_range: &SourceRange) -> Option<(String, String)> {
+    let begin = &source_range.begin;
+    let end = &source_range.end;
+    let source_line = begin
+        .source_file?
+        .lines()
+        .nth(begin.line.checked_sub(1)? as usize)
+        .unwrap_or_default();
+
+    let begin_column = begin.column.max(1) as usize;
+    let end_column = if end.line == begin.line {
+        (end.column as usize).max(begin_column)
+    } else {
+        source_line.chars().count().max(begin_column)
+    };
+
+    let mut caret_line = source_line
+        .chars()
+        .chain(iter::repeat(' '))
+        .take(begin_column - 1)
+        .map(|character| if character == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    caret_line.push('^');
+    caret_line.extend(iter::repeat_n('~', end_column - begin_column));
+
+    Some((source_line.to_string(), caret_line))
+}
+
+/// The name of a diagnostic level as printed before the message
+fn level_name(level: DiagnosticLevel) -> &'static str {
+    match level {
+        DiagnosticLevel::Ignored => "ignored",
+        DiagnosticLevel::Note => "note",
+        DiagnosticLevel::Warning => "warning",
+        DiagnosticLevel::Error => "error",
+        DiagnosticLevel::FatalError => "fatal error",
+    }
+}
+
+/// Wraps `message` at word boundaries so no line is longer than `message_length` columns. The
+/// first line starts after `indent` columns of prefix, the following lines are indented to line up
+/// with it unless that would leave less than half of the width for the message. A `message_length`
+/// of 0 disables wrapping, and a single word longer than a line is never split.
+fn wrap_message(message: &str, indent: usize, message_length: usize) -> String {
+    if message_length == 0 {
+        return message.to_string();
+    }
+
+    let mut wrapped = String::with_capacity(message.len());
+    let mut column = indent;
+    let indent = if indent * 2 > message_length {
+        0
+    } else {
+        indent
+    };
+    for (index, word) in message.split(' ').enumerate() {
+        let word_width = display_width(word);
+
+        if index > 0 {
+            if column + 1 + word_width > message_length && column > indent {
+                wrapped.push('\n');
+                wrapped.extend(iter::repeat_n(' ', indent));
+                column = indent;
+            } else {
+                wrapped.push(' ');
+                column += 1;
+            }
+        }
+
+        wrapped.push_str(word);
+        column += word_width;
+    }
+
+    wrapped
+}
+
+/// The number of columns `text` takes up in a terminal, ignoring the escape sequences coloring it
+fn display_width(text: &str) -> usize {
+    strip_colors(text).chars().count()
+}
+
+/// Removes the escape sequences coloring parts of `text`, like the bold quoted code in messages
+fn strip_colors(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut characters = text.chars();
+    while let Some(character) = characters.next() {
+        if character == '\x1b' {
+            // Skip a sequence like `\x1b[1m` up to and including its final letter
+            characters.by_ref().find(char::is_ascii_alphabetic);
+        } else {
+            stripped.push(character);
+        }
+    }
+
+    stripped
+}
+
+/// Prints a line to stderr if requested, otherwise to stdout
+fn print_line(to_stderr: bool, line: fmt::Arguments) {
+    if to_stderr {
+        eprintln!("{line}");
+    } else {
+        println!("{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        diagnostic::{DiagnosticId, DiagnosticNote},
+        source_file::SourceFile,
+    };
+
+    fn snippet(
+        content: &str,
+        begin: (usize, u32, u32),
+        end: (usize, u32, u32),
+    ) -> (String, String) {
+        let source_file = SourceFile::new("test.c", content);
+        let range = SourceRange::new(
+            SourceLocation::new(&source_file, begin.0, begin.1, begin.2),
+            SourceLocation::new(&source_file, end.0, end.1, end.2),
+        );
+
+        source_snippet(&range).unwrap()
+    }
+
+    #[test]
+    fn test_source_snippet() {
+        let (source_line, caret_line) = snippet(
+            "int main(void) {\n    return 0\n}\n",
+            (21, 2, 5),
+            (28, 2, 12),
+        );
+
+        assert_eq!(source_line, "    return 0");
+        assert_eq!(caret_line, "    ^~~~~~~~");
+    }
+
+    #[test]
+    fn test_source_snippet_tabs() {
+        let (source_line, caret_line) = snippet("\t\treturn @;\n", (9, 1, 10), (9, 1, 10));
+
+        assert_eq!(source_line, "\t\treturn @;");
+        assert_eq!(caret_line, "\t\t       ^");
+    }
+
+    #[test]
+    fn test_source_snippet_multiple_lines() {
+        let (source_line, caret_line) = snippet("int x = 1 +\n  2;\n", (8, 1, 9), (14, 2, 3));
+
+        assert_eq!(source_line, "int x = 1 +");
+        assert_eq!(caret_line, "        ^~~");
+    }
+
+    #[test]
+    fn test_wrap_message() {
+        let message = "a function declaration without a prototype is deprecated";
+
+        assert_eq!(wrap_message(message, 7, 0), message);
+        assert_eq!(
+            wrap_message(message, 7, 30),
+            "a function declaration\n       without a prototype is\n       deprecated"
+        );
+
+        // Every line of the wrapped message fits into the configured width
+        for (index, line) in wrap_message(message, 7, 30).lines().enumerate() {
+            let prefix_width = if index == 0 { 7 } else { 0 };
+            assert!(prefix_width + line.len() <= 30, "'{line}' is too long");
+        }
+    }
+
+    #[test]
+    fn test_wrap_message_long_word() {
+        assert_eq!(
+            wrap_message("unknown identifier_much_longer_than_a_line", 7, 20),
+            "unknown\n       identifier_much_longer_than_a_line"
+        );
+    }
+
+    #[test]
+    fn test_wrap_message_long_prefix() {
+        assert_eq!(
+            wrap_message("expected 'void' keyword for parameter list", 30, 40),
+            "expected\n'void' keyword for parameter list"
+        );
+    }
+
+    #[test]
+    fn test_display_width_ignores_colors() {
+        assert_eq!(display_width("'\x1b[1mabc\x1b[0m'"), 5);
+    }
+
+    #[test]
+    fn test_diagnostic_json() {
+        let source_file = SourceFile::new("dir/\"quoted\".c", "int main() {}");
+        let location = SourceLocation::new(&source_file, 9, 1, 10);
+        let mut diagnostic = Diagnostic::new(
+            DiagnosticId::StrictPrototypes,
+            location,
+            "a function declaration without a prototype",
+        );
+        diagnostic.enable_warning();
+        diagnostic.add_note(DiagnosticNote {
+            source_range: SourceLocation::new_scratch(1, 1).into(),
+            message: format!("add '{}'", "void".bold()),
+        });
+
+        assert_eq!(
+            diagnostic_json(&diagnostic),
+            r#"{"level":"warning","message":"a function declaration without a prototype","file":"dir/\"quoted\".c","line":1,"column":10,"flag":"-Wstrict-prototypes","notes":[{"message":"add 'void'","file":null,"line":1,"column":1}]}"#
+        );
+    }
+
+    #[test]
+    fn test_source_snippet_scratch_location() {
+        let location = SourceLocation::new_scratch(1, 1);
+
+        assert_eq!(source_snippet(&SourceRange::new(location, location)), None);
+    }
 }
@@ -1,15 +1,27 @@
-use crate::{diagnostic::Diagnostic, diagnostic_consumer::DiagnosticConsumer};
+use crate::{
+    diagnostic::{Diagnostic, DiagnosticId},
+    diagnostic_consumer::DiagnosticConsumer,
+};
+
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug)]
 pub struct DiagnosticEngine {
     number_of_warnings: u64,
     number_of_errors: u64,
+    number_of_promoted_warnings: u64,
     error_limit: u64,
+    /// Set once the error limit was reached, all further diagnostics except fatal errors are dropped
+    error_limit_exceeded: bool,
     consumer: Box<dyn DiagnosticConsumer>,
     error_occurred: bool,
     fatal_error_occurred: bool,
     ignore_all_warnings: bool,
     warnings_as_errors: bool,
+    pedantic_errors: bool,
+    warning_overrides: HashMap<DiagnosticId, bool>,
+    deduplicate_diagnostics: bool,
+    reported_diagnostics: HashSet<(DiagnosticId, String, String)>,
 }
 
 impl DiagnosticEngine {
@@ -18,22 +30,51 @@ pub fn new(consumer: Box<dyn DiagnosticConsumer>) -> Self {
         Self {
             number_of_warnings: 0,
             number_of_errors: 0,
+            number_of_promoted_warnings: 0,
             error_limit: 0,
+            error_limit_exceeded: false,
             consumer,
             error_occurred: false,
             fatal_error_occurred: false,
             ignore_all_warnings: false,
             warnings_as_errors: false,
+            pedantic_errors: false,
+            warning_overrides: HashMap::new(),
+            deduplicate_diagnostics: false,
+            reported_diagnostics: HashSet::new(),
         }
     }
 
     pub fn report(&mut self, diagnostic: &mut Diagnostic) {
+        if self.deduplicate_diagnostics && !self.insert_reported_diagnostic(diagnostic) {
+            return;
+        }
+
+        match self.warning_overrides.get(&diagnostic.id) {
+            Some(true) => diagnostic.enable_warning(),
+            Some(false) => diagnostic.ignore_warning(),
+            None => {}
+        }
+
         if self.ignore_all_warnings {
             diagnostic.ignore_warning();
         }
 
-        if self.warnings_as_errors {
+        if self.pedantic_errors && diagnostic.id.is_pedantic() {
+            diagnostic.upgrade_warning_to_error();
+        }
+
+        if self.warnings_as_errors && diagnostic.is_warning() {
             diagnostic.upgrade_warning_to_error();
+            self.number_of_promoted_warnings += 1;
+        }
+
+        if diagnostic.is_ignored() {
+            return;
+        }
+
+        if self.error_limit_exceeded && !diagnostic.is_fatal_error() {
+            return;
         }
 
         if diagnostic.is_error_or_fatal() {
@@ -50,6 +91,69 @@ pub fn report(&mut self, diagnostic: &mut Diagnostic) {
         }
 
         self.consumer.report(diagnostic);
+
+        // Stop flooding the output once the limit is reached, e.g. by a parser failing to recover
+        if !self.error_limit_exceeded && self.error_limit_reached() {
+            self.error_limit_exceeded = true;
+
+            self.report(&mut Diagnostic::new(
+                DiagnosticId::TooManyErrors,
+                diagnostic.source_range,
+                "too many errors emitted, stopping now",
+            ));
+        }
+    }
+
+    /// Sets the number of errors after which all further diagnostics are suppressed, 0 means no limit
+    pub fn set_error_limit(&mut self, error_limit: u64) {
+        self.error_limit = error_limit;
+    }
+
+    pub fn set_warnings_as_errors(&mut self, warnings_as_errors: bool) {
+        self.warnings_as_errors = warnings_as_errors;
+    }
+
+    /// Turns warnings about code the selected C standard doesn't allow into errors, unlike
+    /// `-Werror` they don't count as promoted warnings
+    pub fn set_pedantic_errors(&mut self, pedantic_errors: bool) {
+        self.pedantic_errors = pedantic_errors;
+    }
+
+    /// Suppresses all warnings, including the ones enabled with `-W<flag>` or promoted by `-Werror`
+    pub fn set_ignore_all_warnings(&mut self, ignore_all_warnings: bool) {
+        self.ignore_all_warnings = ignore_all_warnings;
+    }
+
+    /// Enables or disables the warning with the given id, overriding its default level
+    pub fn set_warning_enabled(&mut self, id: DiagnosticId, enabled: bool) {
+        self.warning_overrides.insert(id, enabled);
+    }
+
+    /// When enabled, a diagnostic with the same id, location and message as an already reported one
+    /// is suppressed
+    pub fn set_deduplicate_diagnostics(&mut self, deduplicate_diagnostics: bool) {
+        self.deduplicate_diagnostics = deduplicate_diagnostics;
+    }
+
+    /// Forgets everything reported so far, so the engine can be reused for the next compilation.
+    /// The configuration like the error limit or `-Werror` is kept.
+    pub fn reset(&mut self) {
+        self.number_of_warnings = 0;
+        self.number_of_errors = 0;
+        self.number_of_promoted_warnings = 0;
+        self.error_limit_exceeded = false;
+        self.error_occurred = false;
+        self.fatal_error_occurred = false;
+        self.reported_diagnostics.clear();
+    }
+
+    /// Remembers the diagnostic as reported. Returns false if it was already reported before
+    fn insert_reported_diagnostic(&mut self, diagnostic: &Diagnostic) -> bool {
+        self.reported_diagnostics.insert((
+            diagnostic.id,
+            diagnostic.source_range.begin.to_string(),
+            diagnostic.message.clone(),
+        ))
     }
 
     #[must_use]
@@ -57,6 +161,45 @@ pub const fn error_occurred(&self) -> bool {
         self.error_occurred
     }
 
+    /// Returns true if errors occurred but every one of them was a warning turned into an error
+    #[must_use]
+    pub const fn only_promoted_warnings_occurred(&self) -> bool {
+        self.error_occurred && self.number_of_errors == self.number_of_promoted_warnings
+    }
+
+    #[must_use]
+    pub const fn number_of_errors(&self) -> u64 {
+        self.number_of_errors
+    }
+
+    #[must_use]
+    pub const fn number_of_warnings(&self) -> u64 {
+        self.number_of_warnings
+    }
+
+    /// Summarizes the reported diagnostics like clang, e.g. `2 warnings and 1 error generated.`
+    /// Returns `None` if neither warnings nor errors were reported.
+    #[must_use]
+    pub fn summary(&self) -> Option<String> {
+        let count = |number: u64, noun: &str| match number {
+            0 => None,
+            1 => Some(format!("1 {noun}")),
+            _ => Some(format!("{number} {noun}s")),
+        };
+
+        let counts = match (
+            count(self.number_of_warnings, "warning"),
+            count(self.number_of_errors, "error"),
+        ) {
+            (None, None) => return None,
+            (Some(warnings), None) => warnings,
+            (None, Some(errors)) => errors,
+            (Some(warnings), Some(errors)) => format!("{warnings} and {errors}"),
+        };
+
+        Some(format!("{counts} generated."))
+    }
+
     #[must_use]
     pub const fn fatal_error_occurred(&self) -> bool {
         self.fatal_error_occurred
@@ -67,3 +210,266 @@ pub const fn error_limit_reached(&self) -> bool {
         self.error_limit > 0 && self.number_of_errors >= self.error_limit
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        source_file::SourceFile, source_location::SourceLocation, source_range::SourceRange,
+    };
+
+    use std::{cell::Cell, rc::Rc};
+
+    #[derive(Debug, Default)]
+    struct CountingDiagnosticConsumer {
+        count: Rc<Cell<usize>>,
+    }
+
+    impl DiagnosticConsumer for CountingDiagnosticConsumer {
+        fn report(&self, _diagnostic: &Diagnostic) {
+            self.count.set(self.count.get() + 1);
+        }
+    }
+
+    fn engine_with_counter(deduplicate: bool) -> (DiagnosticEngine, Rc<Cell<usize>>) {
+        let consumer = CountingDiagnosticConsumer::default();
+        let count = Rc::clone(&consumer.count);
+
+        let mut engine = DiagnosticEngine::new(Box::new(consumer));
+        engine.set_deduplicate_diagnostics(deduplicate);
+
+        (engine, count)
+    }
+
+    fn report_repeatedly(engine: &mut DiagnosticEngine, location: SourceLocation, times: usize) {
+        // Mimics a recovery loop which keeps reporting at the same token
+        for _ in 0..times {
+            let mut diagnostic = Diagnostic::new(
+                DiagnosticId::ExpectedExpression,
+                location,
+                "expected expression",
+            );
+            engine.report(&mut diagnostic);
+        }
+    }
+
+    #[test]
+    fn test_pedantic_errors() {
+        let source_file = SourceFile::new("test.c", "// comment");
+        let location = SourceLocation::new(&source_file, 0, 1, 1);
+        let (mut engine, count) = engine_with_counter(false);
+        engine.set_pedantic_errors(true);
+
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::LineCommentNotAllowed,
+            location,
+            "// comments are not allowed in C89",
+        ));
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::NullCharacter,
+            location,
+            "null character ignored",
+        ));
+
+        assert_eq!(count.get(), 2);
+        assert_eq!(engine.number_of_errors, 1);
+        assert_eq!(engine.number_of_warnings, 1);
+        assert!(!engine.only_promoted_warnings_occurred());
+    }
+
+    #[test]
+    fn test_notes_are_not_promoted() {
+        let (mut engine, count) = engine_with_counter(false);
+        engine.set_warnings_as_errors(true);
+
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::OptionConflict,
+            SourceRange::invalid(),
+            "'-Wno-comment' overrides the earlier '-Wcomment'",
+        ));
+
+        assert_eq!(count.get(), 1);
+        assert_eq!(engine.number_of_errors, 0);
+        assert_eq!(engine.number_of_warnings, 0);
+        assert_eq!(engine.summary(), None);
+    }
+
+    #[test]
+    fn test_duplicates_reported_without_deduplication() {
+        let source_file = SourceFile::new("test.c", "return;");
+        let (mut engine, count) = engine_with_counter(false);
+
+        report_repeatedly(&mut engine, SourceLocation::new(&source_file, 6, 1, 7), 3);
+
+        assert_eq!(count.get(), 3);
+        assert_eq!(engine.number_of_errors, 3);
+    }
+
+    #[test]
+    fn test_duplicates_suppressed_with_deduplication() {
+        let source_file = SourceFile::new("test.c", "return;");
+        let (mut engine, count) = engine_with_counter(true);
+
+        report_repeatedly(&mut engine, SourceLocation::new(&source_file, 6, 1, 7), 3);
+
+        assert_eq!(count.get(), 1);
+        assert_eq!(engine.number_of_errors, 1);
+        assert!(engine.error_occurred());
+    }
+
+    #[test]
+    fn test_deduplication_keeps_different_locations() {
+        let source_file = SourceFile::new("test.c", "return;");
+        let (mut engine, count) = engine_with_counter(true);
+
+        report_repeatedly(&mut engine, SourceLocation::new(&source_file, 5, 1, 6), 2);
+        report_repeatedly(&mut engine, SourceLocation::new(&source_file, 6, 1, 7), 2);
+
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn test_deduplication_keeps_different_messages() {
+        let source_file = SourceFile::new("test.c", "return;");
+        let location = SourceLocation::new(&source_file, 6, 1, 7);
+        let (mut engine, count) = engine_with_counter(true);
+
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::ExpectedExpression,
+            location,
+            "expected expression",
+        ));
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::ExpectedExpression,
+            location,
+            "expected expression after 'return'",
+        ));
+
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn test_error_limit() {
+        let source_file = SourceFile::new("test.c", "return;");
+        let (mut engine, count) = engine_with_counter(false);
+        engine.set_error_limit(3);
+
+        report_repeatedly(&mut engine, SourceLocation::new(&source_file, 6, 1, 7), 10);
+
+        // The three errors followed by the fatal error about stopping
+        assert_eq!(count.get(), 4);
+        assert!(engine.error_limit_reached());
+        assert!(engine.fatal_error_occurred());
+    }
+
+    #[test]
+    fn test_no_error_limit_by_default() {
+        let source_file = SourceFile::new("test.c", "return;");
+        let (mut engine, count) = engine_with_counter(false);
+
+        report_repeatedly(&mut engine, SourceLocation::new(&source_file, 6, 1, 7), 10);
+
+        assert_eq!(count.get(), 10);
+        assert!(!engine.error_limit_reached());
+    }
+
+    #[test]
+    fn test_ignore_all_warnings() {
+        let source_file = SourceFile::new("test.c", "int main() {}");
+        let location = SourceLocation::new(&source_file, 9, 1, 10);
+        let (mut engine, count) = engine_with_counter(false);
+        engine.set_ignore_all_warnings(true);
+        engine.set_warnings_as_errors(true);
+
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::NullCharacter,
+            location,
+            "null character ignored",
+        ));
+
+        assert_eq!(count.get(), 0);
+        assert!(!engine.error_occurred());
+    }
+
+    #[test]
+    fn test_warning_overrides() {
+        let source_file = SourceFile::new("test.c", "int main() {}");
+        let location = SourceLocation::new(&source_file, 9, 1, 10);
+        let (mut engine, count) = engine_with_counter(false);
+
+        // Off by default
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::StrictPrototypes,
+            location,
+            "no prototype",
+        ));
+        assert_eq!(count.get(), 0);
+
+        engine.set_warning_enabled(DiagnosticId::StrictPrototypes, true);
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::StrictPrototypes,
+            location,
+            "no prototype",
+        ));
+        assert_eq!(count.get(), 1);
+        assert_eq!(engine.number_of_warnings(), 1);
+
+        engine.set_warning_enabled(DiagnosticId::NullCharacter, false);
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::NullCharacter,
+            location,
+            "null character ignored",
+        ));
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn test_reset() {
+        let source_file = SourceFile::new("test.c", "return;");
+        let location = SourceLocation::new(&source_file, 6, 1, 7);
+        let (mut engine, count) = engine_with_counter(true);
+        engine.set_error_limit(1);
+
+        report_repeatedly(&mut engine, location, 1);
+        assert!(engine.error_occurred());
+        assert!(engine.fatal_error_occurred());
+
+        engine.reset();
+
+        assert!(!engine.error_occurred());
+        assert!(!engine.fatal_error_occurred());
+        assert!(!engine.error_limit_reached());
+        assert_eq!(engine.number_of_errors(), 0);
+        assert_eq!(engine.number_of_warnings(), 0);
+
+        // The same diagnostic is reported again and the error limit still applies
+        report_repeatedly(&mut engine, location, 1);
+        assert_eq!(count.get(), 4);
+        assert!(engine.fatal_error_occurred());
+    }
+
+    #[test]
+    fn test_summary() {
+        let source_file = SourceFile::new("test.c", "int main() {}");
+        let location = SourceLocation::new(&source_file, 9, 1, 10);
+        let (mut engine, _) = engine_with_counter(false);
+        assert_eq!(engine.summary(), None);
+
+        engine.report(&mut Diagnostic::new(
+            DiagnosticId::NullCharacter,
+            location,
+            "null character ignored",
+        ));
+        assert_eq!(engine.summary().as_deref(), Some("1 warning generated."));
+
+        report_repeatedly(&mut engine, location, 2);
+        assert_eq!(
+            engine.summary().as_deref(),
+            Some("1 warning and 2 errors generated.")
+        );
+
+        engine.reset();
+        report_repeatedly(&mut engine, location, 1);
+        assert_eq!(engine.summary().as_deref(), Some("1 error generated."));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::source_manager::SourceManagerError;
+use std::fmt;
+
+/// The errors which can occur while compiling a translation unit
+#[derive(Debug)]
+pub enum Error {
+    /// A source file could not be read
+    Io {
+        path: String,
+        error: SourceManagerError,
+    },
+    /// The lexer reported errors, the diagnostics were passed to the diagnostic engine
+    Lex { number_of_errors: u64 },
+    /// The parser reported errors, the diagnostics were passed to the diagnostic engine
+    Parse { number_of_errors: u64 },
+    /// The translation unit could not be lowered to LLVM IR
+    Codegen(String),
+    /// The code generator reported an error, the diagnostic was passed to the diagnostic engine
+    CodegenReported,
+    /// LLVM rejected the generated module
+    LlvmVerification(String),
+    /// The generated code could not be written to an output file
+    Output { path: String, message: String },
+    /// LLVM doesn't support the requested target triple
+    UnknownTarget { triple: String, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, error } => write!(f, "error reading file '{path}': {error}"),
+            Error::Lex { number_of_errors } => {
+                write!(f, "lexing failed with {number_of_errors} error(s)")
+            }
+            Error::Parse { number_of_errors } => {
+                write!(f, "parsing failed with {number_of_errors} error(s)")
+            }
+            Error::Codegen(message) => write!(f, "code generation failed: {message}"),
+            Error::CodegenReported => write!(f, "code generation failed"),
+            Error::LlvmVerification(message) => write!(f, "LLVM module is invalid: {message}"),
+            Error::Output { path, message } => {
+                write!(f, "error writing output file '{path}': {message}")
+            }
+            Error::UnknownTarget { triple, message } => {
+                write!(f, "unknown target triple '{triple}': {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = Error::Io {
+            path: "missing.c".to_string(),
+            error: SourceManagerError::NotFound,
+        };
+        assert_eq!(
+            error.to_string(),
+            "error reading file 'missing.c': no such file or directory"
+        );
+
+        let error = Error::Parse {
+            number_of_errors: 2,
+        };
+        assert_eq!(error.to_string(), "parsing failed with 2 error(s)");
+
+        let error = Error::UnknownTarget {
+            triple: "not-a-target".to_string(),
+            message: "no such target".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "unknown target triple 'not-a-target': no such target"
+        );
+    }
+
+    #[test]
+    fn test_source() {
+        let error = Error::Io {
+            path: "missing.c".to_string(),
+            error: SourceManagerError::NotFound,
+        };
+        assert!(std::error::Error::source(&error).is_some());
+
+        let error = Error::Codegen("message".to_string());
+        assert!(std::error::Error::source(&error).is_none());
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt::Write;
+
+/// Escapes a string so it can be embedded in a JSON string literal
+pub fn escape_json(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+
+    for character in string.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if character.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", character as u32);
+            }
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_json("line\nbreak"), r"line\nbreak");
+        assert_eq!(escape_json("line\r\nbreak"), r"line\r\nbreak");
+        assert_eq!(escape_json("\u{1}"), r"\u0001");
+    }
+}
@@ -9,21 +9,42 @@
     source_file::SourceFile,
     source_location::SourceLocation,
     source_range::SourceRange,
-    token::{Token, TokenList},
+    token::{IntegerSuffix, Token, TokenList},
 };
 
+/// The states of the lexer's state machine
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum LexerState {
+pub enum LexerState {
     Start,
     Identifier,
     IntegerLiteral,
     IntegerLiteralOverflow,
+    CharacterLiteral,
+    StringLiteral,
     AfterSlash,
     LineComment,
     MultiLineComment,
     MultiLineCommentAfterStar,
     AfterMinus,
     AfterPlus,
+    AfterLess,
+    AfterGreater,
+    AfterEqual,
+    AfterExclamation,
+    AfterAmpersand,
+    AfterPipe,
+}
+
+/// What happened during a single step of the lexer, see [`Lexer::step`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepResult<'a> {
+    /// The state of the lexer after the step
+    pub state: LexerState,
+    pub index: usize,
+    pub line: u32,
+    pub column: u32,
+    /// The token emitted during the step, if any
+    pub emitted_token: Option<Token<'a>>,
 }
 
 pub struct Lexer<'a> {
@@ -35,6 +56,18 @@ pub struct Lexer<'a> {
     line: u32,
     column: u32,
     index: usize,
+    line_begin_index: usize,
+
+    line_too_long_reported: bool,
+    file_too_big_reported: bool,
+    line_comment_reported: bool,
+
+    digit_separators: bool,
+    dollars_in_identifiers: bool,
+    line_comments: bool,
+
+    /// The radix of the integer literal currently being lexed, 16 after a `0x` prefix otherwise 10
+    integer_literal_radix: u32,
 
     token_begin_location: SourceLocation<'a>,
     token_end_location: SourceLocation<'a>,
@@ -55,37 +88,178 @@ pub fn new(
             line: 1,
             column: 1,
             index: 0,
+            line_begin_index: 0,
+            line_too_long_reported: false,
+            file_too_big_reported: false,
+            line_comment_reported: false,
+            digit_separators: false,
+            dollars_in_identifiers: false,
+            line_comments: true,
+            integer_literal_radix: 10,
             token_begin_location: SourceLocation::invalid(),
             token_end_location: SourceLocation::invalid(),
             queued_tokens: TokenList::new(),
         }
     }
 
+    /// Allow `'` and `_` as digit separators inside integer literals, e.g. `1'000` or `1_000`
+    pub fn set_digit_separators(&mut self, enabled: bool) {
+        self.digit_separators = enabled;
+    }
+
+    /// Allow `$` to appear in identifiers, including as the first character
+    pub fn set_dollars_in_identifiers(&mut self, enabled: bool) {
+        self.dollars_in_identifiers = enabled;
+    }
+
+    /// Whether `//` comments are part of the language, which they are since C99. When disabled they
+    /// are still lexed as comments, but the first one is warned about.
+    pub fn set_line_comments(&mut self, enabled: bool) {
+        self.line_comments = enabled;
+    }
+
     #[must_use]
     pub fn is_finished(&self) -> bool {
         self.index >= self.source_file.content.len()
     }
 
-    pub fn tokenize(&mut self) -> TokenList {
+    pub fn tokenize(&mut self) -> TokenList<'a> {
         while !self.is_finished() {
             self.advance_state_machine();
         }
 
+        // Give the current state the chance to emit its pending token at the end of the file
+        if self.state != LexerState::Start {
+            self.advance_state_machine();
+        }
+
         self.queued_tokens.drain(..).collect()
     }
 
+    /// Performs a single transition of the state machine and reports what happened. This is meant
+    /// for visualizing how the lexer works, use [`Lexer::tokenize`] to lex a whole file.
+    ///
+    /// Emitted tokens stay queued, so a later call to [`Lexer::tokenize`] still returns them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustcc::diagnostic_consumer::IgnoreDiagnosticConsumer;
+    /// # use rustcc::diagnostic_engine::DiagnosticEngine;
+    /// # use rustcc::lexer::{Lexer, LexerState};
+    /// # use rustcc::source_file::SourceFile;
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+    ///     IgnoreDiagnosticConsumer,
+    /// ))));
+    /// let source_file = SourceFile::new("test.c", "x");
+    /// let mut lexer = Lexer::new(diagnostic_engine, &source_file);
+    ///
+    /// let step = lexer.step();
+    /// assert_eq!(step.state, LexerState::Identifier);
+    /// assert_eq!(step.emitted_token, None);
+    ///
+    /// let step = lexer.step();
+    /// assert_eq!(step.state, LexerState::Start);
+    /// assert!(step.emitted_token.unwrap().is_identifier());
+    /// assert!(lexer.is_finished());
+    /// ```
+    pub fn step(&mut self) -> StepResult<'a> {
+        let number_of_queued_tokens = self.queued_tokens.len();
+
+        self.advance_state_machine();
+
+        let emitted_token = if self.queued_tokens.len() > number_of_queued_tokens {
+            self.queued_tokens.back().cloned()
+        } else {
+            None
+        };
+
+        StepResult {
+            state: self.state,
+            index: self.index,
+            line: self.line,
+            column: self.column,
+            emitted_token,
+        }
+    }
+
     fn peek_next(&self) -> Option<char> {
         self.source_file.content[self.index..].chars().next()
     }
 
+    fn peek_second(&self) -> Option<char> {
+        self.source_file.content[self.index..].chars().nth(1)
+    }
+
     fn consume_character(&mut self) {
         // Get current character
         let current_character = self.peek_next().unwrap();
 
-        self.column += 1;
+        // Saturate the column instead of overflowing on absurdly long lines
+        if self.column == u32::MAX {
+            if !self.line_too_long_reported {
+                self.line_too_long_reported = true;
+
+                let location = self.line_begin_location();
+                self.diagnostic(
+                    DiagnosticId::LineTooLong,
+                    location,
+                    format!(
+                        "line is too long, column numbers beyond {} are not tracked",
+                        u32::MAX
+                    ),
+                );
+            }
+        } else {
+            self.column += 1;
+        }
+
         self.index += current_character.len_utf8();
     }
 
+    /// Consumes a line terminator, which is `\n`, `\r\n` or a lone `\r`
+    fn consume_newline(&mut self) {
+        if self.peek_next() == Some('\r') {
+            self.consume_character();
+        }
+        if self.peek_next() == Some('\n') {
+            self.consume_character();
+        }
+
+        self.start_new_line();
+    }
+
+    /// Must be called after consuming a line terminator
+    fn start_new_line(&mut self) {
+        // Saturate the line instead of overflowing on files with absurdly many lines
+        if self.line == u32::MAX {
+            if !self.file_too_big_reported {
+                self.file_too_big_reported = true;
+
+                let location = SourceLocation::new(self.source_file, 0, 1, 1);
+                self.diagnostic(
+                    DiagnosticId::FileTooBig,
+                    location,
+                    format!(
+                        "file has too many lines, line numbers beyond {} are not tracked",
+                        u32::MAX
+                    ),
+                );
+            }
+        } else {
+            self.line += 1;
+        }
+
+        self.column = 1;
+        self.line_begin_index = self.index;
+    }
+
+    #[must_use]
+    fn line_begin_location(&self) -> SourceLocation<'a> {
+        SourceLocation::new(self.source_file, self.line_begin_index, self.line, 1)
+    }
+
     #[must_use]
     fn current_location(&self) -> SourceLocation<'a> {
         SourceLocation::new(self.source_file, self.index, self.line, self.column)
@@ -108,29 +282,305 @@ fn diagnostic_here<S: Into<String>>(&self, id: DiagnosticId, message: S) -> Diag
         self.diagnostic(id, location, message)
     }
 
+    fn is_identifier_start(&self, character: char) -> bool {
+        character.is_ascii_alphabetic()
+            || character == '_'
+            || (character == '$' && self.dollars_in_identifiers)
+    }
+
+    fn is_identifier_continue(&self, character: char) -> bool {
+        self.is_identifier_start(character) || character.is_ascii_digit()
+    }
+
+    /// Consumes a digit separator if the next character is one and they are enabled, returning
+    /// whether it did. A separator must be directly followed by another digit of the current
+    /// radix, otherwise it is diagnosed as misplaced.
+    fn lex_digit_separator(&mut self) -> bool {
+        if !self.digit_separators || !matches!(self.peek_next(), Some('\'' | '_')) {
+            return false;
+        }
+
+        let separator_location = self.current_location();
+        self.token_end_location = separator_location;
+        self.consume_character();
+
+        if !self
+            .peek_next()
+            .is_some_and(|character| character.is_digit(self.integer_literal_radix))
+        {
+            self.diagnostic(
+                DiagnosticId::MisplacedDigitSeparator,
+                separator_location,
+                "digit separator must be placed between digits",
+            );
+        }
+
+        true
+    }
+
+    /// Lexes the optional suffix of an integer literal like `u`, `l` or `ull`. Any other letters
+    /// directly following the literal like in `123abc` are diagnosed as an invalid suffix.
+    fn lex_integer_suffix(&mut self) -> IntegerSuffix {
+        let Some(first_character) = self.peek_next() else {
+            return IntegerSuffix::None;
+        };
+        if !first_character.is_ascii_alphabetic() && first_character != '_' {
+            return IntegerSuffix::None;
+        }
+
+        let source_file = self.source_file;
+        let suffix_begin_location = self.current_location();
+
+        // Consume all characters which could belong to the suffix
+        while let Some(character) = self.peek_next() {
+            if !character.is_ascii_alphanumeric() && character != '_' {
+                break;
+            }
+
+            self.token_end_location = self.current_location();
+            self.consume_character();
+        }
+
+        let suffix = &source_file.content[suffix_begin_location.index..self.index];
+
+        IntegerSuffix::from_suffix(suffix).unwrap_or_else(|| {
+            // Differentiate between a malformed integer suffix and something else entirely
+            let id = if matches!(first_character, 'u' | 'U' | 'l' | 'L') {
+                DiagnosticId::InvalidIntegerSuffix
+            } else {
+                DiagnosticId::InvalidNumericSuffix
+            };
+
+            self.diagnostic(
+                id,
+                SourceRange::new(suffix_begin_location, self.token_end_location),
+                format!("invalid suffix '{}' on integer literal", suffix.bold()),
+            );
+
+            IntegerSuffix::None
+        })
+    }
+
+    /// Lexes the rest of a character literal after its opening quote. Returns `None` if the
+    /// literal is unterminated or too long, which has already been diagnosed.
+    fn lex_character_literal(&mut self) -> Option<Token<'a>> {
+        let value = match self.peek_next() {
+            Some('\'') => {
+                self.token_end_location = self.current_location();
+                self.consume_character();
+
+                let range = SourceRange::new(self.token_begin_location, self.token_end_location);
+                self.diagnostic(
+                    DiagnosticId::EmptyCharacterLiteral,
+                    range,
+                    "empty character constant",
+                );
+
+                // Still emit a token so the parser doesn't report a missing expression as well
+                return Some(Token::new_character_literal(0, range));
+            }
+            None | Some('\n' | '\r') => {
+                self.diagnostic(
+                    DiagnosticId::UnterminatedCharacterLiteral,
+                    self.token_begin_location,
+                    "missing terminating ' character",
+                );
+                return None;
+            }
+            Some('\\') => self.lex_escape_sequence(),
+            Some(character) => {
+                self.consume_character();
+                character as u32
+            }
+        };
+
+        if self.peek_next() != Some('\'') {
+            // Skip the rest of the literal, up to the closing quote or the end of the line
+            while let Some(character) = self.peek_next() {
+                if matches!(character, '\n' | '\r') {
+                    break;
+                }
+
+                self.token_end_location = self.current_location();
+                self.consume_character();
+
+                if character == '\'' {
+                    self.diagnostic(
+                        DiagnosticId::MultiCharacterLiteral,
+                        SourceRange::new(self.token_begin_location, self.token_end_location),
+                        "multi-character character constants are not supported",
+                    );
+                    return None;
+                }
+            }
+
+            self.diagnostic(
+                DiagnosticId::UnterminatedCharacterLiteral,
+                self.token_begin_location,
+                "missing terminating ' character",
+            );
+            return None;
+        }
+
+        self.token_end_location = self.current_location();
+        self.consume_character();
+
+        Some(Token::new_character_literal(
+            value,
+            SourceRange::new(self.token_begin_location, self.token_end_location),
+        ))
+    }
+
+    /// Lexes the rest of a string literal after its opening quote, decoding escape sequences like
+    /// in character literals. Returns `None` if the string is unterminated, which has already been
+    /// diagnosed.
+    fn lex_string_literal(&mut self) -> Option<Token<'a>> {
+        let mut value = Vec::new();
+
+        loop {
+            match self.peek_next() {
+                Some('"') => {
+                    self.token_end_location = self.current_location();
+                    self.consume_character();
+
+                    return Some(Token::new_string_literal(
+                        value,
+                        SourceRange::new(self.token_begin_location, self.token_end_location),
+                    ));
+                }
+                None | Some('\n' | '\r') => {
+                    self.diagnostic(
+                        DiagnosticId::UnterminatedStringLiteral,
+                        self.token_begin_location,
+                        "missing terminating '\"' character",
+                    );
+                    return None;
+                }
+                Some('\\') => {
+                    // Octal escapes like `\377` stand for a single byte, truncated like in other
+                    // compilers, while the other escapes stand for a character
+                    let is_numeric = self
+                        .peek_second()
+                        .is_some_and(|character| character.is_digit(8));
+                    let escaped = self.lex_escape_sequence();
+
+                    if is_numeric {
+                        value.push(escaped as u8);
+                    } else {
+                        let character =
+                            char::from_u32(escaped).unwrap_or(char::REPLACEMENT_CHARACTER);
+                        value.extend_from_slice(character.encode_utf8(&mut [0; 4]).as_bytes());
+                    }
+                }
+                Some(character) => {
+                    self.consume_character();
+                    value.extend_from_slice(character.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+            }
+        }
+    }
+
+    /// Lexes an escape sequence like `\n` or `\0` starting at the backslash and returns the value
+    /// of the character it stands for. Unknown escape sequences are diagnosed and stand for the
+    /// escaped character itself.
+    fn lex_escape_sequence(&mut self) -> u32 {
+        let escape_begin_location = self.current_location();
+        self.consume_character();
+
+        let Some(character) = self
+            .peek_next()
+            .filter(|character| !matches!(character, '\n' | '\r'))
+        else {
+            // The missing closing quote is diagnosed by the caller
+            return u32::from('\\');
+        };
+
+        // Up to three octal digits like in `\0` or `\101`
+        if character.is_digit(8) {
+            let mut value = 0;
+            for _ in 0..3 {
+                let Some(digit) = self.peek_next().and_then(|character| character.to_digit(8))
+                else {
+                    break;
+                };
+
+                value = value * 8 + digit;
+                self.consume_character();
+            }
+
+            return value;
+        }
+
+        let escape_end_location = self.current_location();
+        self.consume_character();
+
+        // TODO: Support hexadecimal escape sequences like `\x41`
+        match character {
+            'n' => 0x0A,
+            't' => 0x09,
+            'r' => 0x0D,
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0C,
+            'v' => 0x0B,
+            '\\' | '\'' | '"' | '?' => character as u32,
+            _ => {
+                self.diagnostic(
+                    DiagnosticId::UnknownEscapeSequence,
+                    SourceRange::new(escape_begin_location, escape_end_location),
+                    format!(
+                        "unknown escape sequence '{}'",
+                        format!("\\{character}").bold()
+                    ),
+                );
+
+                character as u32
+            }
+        }
+    }
+
     // -- Emit Token functions --
 
     fn advance_state_machine(&mut self) {
         match self.state {
             LexerState::Start => match self.peek_next() {
                 // Whitespaces and newlines
-                Some('\n') => {
-                    self.consume_character();
-
-                    self.line += 1;
-                    self.column = 1;
+                Some('\n' | '\r') => {
+                    self.consume_newline();
                 }
                 Some(character) if character.is_whitespace() => {
                     self.consume_character();
                 }
 
-                Some(character) if character.is_ascii_alphabetic() || character == '_' => {
+                Some(character) if self.is_identifier_start(character) => {
                     self.token_begin_location = self.current_location();
                     self.state = LexerState::Identifier;
                 }
                 Some(character) if character.is_ascii_digit() => {
                     self.token_begin_location = self.current_location();
                     self.state = LexerState::IntegerLiteral;
+
+                    // Consume the prefix of a hexadecimal literal like `0x1F`
+                    if character == '0' && matches!(self.peek_second(), Some('x' | 'X')) {
+                        self.consume_character();
+                        self.token_end_location = self.current_location();
+                        self.consume_character();
+
+                        self.integer_literal_radix = 16;
+                    } else {
+                        self.integer_literal_radix = 10;
+                    }
+                }
+
+                Some('\'') => {
+                    self.token_begin_location = self.current_location();
+                    self.consume_character();
+                    self.state = LexerState::CharacterLiteral;
+                }
+                Some('"') => {
+                    self.token_begin_location = self.current_location();
+                    self.consume_character();
+                    self.state = LexerState::StringLiteral;
                 }
 
                 Some('/') => {
@@ -174,6 +624,12 @@ fn advance_state_machine(&mut self) {
                     self.queued_tokens.push_back(Token::new_semicolon(location));
                     self.consume_character();
                 }
+                Some(',') => {
+                    let location = self.current_location();
+
+                    self.queued_tokens.push_back(Token::new_comma(location));
+                    self.consume_character();
+                }
                 Some('~') => {
                     let location = self.current_location();
 
@@ -205,6 +661,42 @@ fn advance_state_machine(&mut self) {
                     self.queued_tokens.push_back(Token::new_percent(location));
                     self.consume_character();
                 }
+                Some('<') => {
+                    self.token_begin_location = self.current_location();
+
+                    self.state = LexerState::AfterLess;
+                    self.consume_character();
+                }
+                Some('>') => {
+                    self.token_begin_location = self.current_location();
+
+                    self.state = LexerState::AfterGreater;
+                    self.consume_character();
+                }
+                Some('=') => {
+                    self.token_begin_location = self.current_location();
+
+                    self.state = LexerState::AfterEqual;
+                    self.consume_character();
+                }
+                Some('!') => {
+                    self.token_begin_location = self.current_location();
+
+                    self.state = LexerState::AfterExclamation;
+                    self.consume_character();
+                }
+                Some('&') => {
+                    self.token_begin_location = self.current_location();
+
+                    self.state = LexerState::AfterAmpersand;
+                    self.consume_character();
+                }
+                Some('|') => {
+                    self.token_begin_location = self.current_location();
+
+                    self.state = LexerState::AfterPipe;
+                    self.consume_character();
+                }
 
                 Some('\0') => {
                     self.diagnostic_here(DiagnosticId::NullCharacter, "null character ignored");
@@ -229,7 +721,7 @@ fn advance_state_machine(&mut self) {
 
             LexerState::Identifier => loop {
                 match self.peek_next() {
-                    Some(character) if character.is_ascii_alphanumeric() || character == '_' => {
+                    Some(character) if self.is_identifier_continue(character) => {
                         self.token_end_location = self.current_location();
                         self.consume_character();
                     }
@@ -248,18 +740,20 @@ fn advance_state_machine(&mut self) {
             },
 
             LexerState::IntegerLiteral => {
+                let radix = self.integer_literal_radix;
                 let mut value: u32 = 0;
+                let mut has_digits = false;
                 loop {
                     match self.peek_next() {
-                        Some(character) if character.is_ascii_digit() => {
-                            // Multiply the current value by 10 and check for any overflow
-                            let Some(temp_value) = value.checked_mul(10) else {
+                        Some(character) if character.is_digit(radix) => {
+                            // Multiply the current value by the radix and check for any overflow
+                            let Some(temp_value) = value.checked_mul(radix) else {
                                 self.state = LexerState::IntegerLiteralOverflow;
                                 break;
                             };
 
-                            // Convert the current character to an actual base 10 number
-                            let character_value = character.to_digit(10).unwrap();
+                            // Convert the current character to an actual number
+                            let character_value = character.to_digit(radix).unwrap();
 
                             // Add the current character value to the current value and check for any overflow
                             let Some(temp_value) = temp_value.checked_add(character_value) else {
@@ -269,12 +763,28 @@ fn advance_state_machine(&mut self) {
 
                             // Update the current value and consume the character
                             value = temp_value;
+                            has_digits = true;
                             self.token_end_location = self.current_location();
                             self.consume_character();
                         }
+                        _ if self.lex_digit_separator() => {}
                         _ => {
+                            if !has_digits {
+                                self.diagnostic(
+                                    DiagnosticId::InvalidHexLiteral,
+                                    SourceRange::new(
+                                        self.token_begin_location,
+                                        self.token_end_location,
+                                    ),
+                                    "hexadecimal literal requires at least one digit",
+                                );
+                            }
+
+                            let suffix = self.lex_integer_suffix();
+
                             let token = Token::new_integer_literal(
                                 value,
+                                suffix,
                                 SourceRange::new(
                                     self.token_begin_location,
                                     self.token_end_location,
@@ -292,12 +802,16 @@ fn advance_state_machine(&mut self) {
             LexerState::IntegerLiteralOverflow => {
                 loop {
                     match self.peek_next() {
-                        Some(character) if character.is_ascii_digit() => {
+                        Some(character) if character.is_digit(self.integer_literal_radix) => {
                             // Consume all digit characters until we reach a non-digit character
                             self.token_end_location = self.current_location();
                             self.consume_character();
                         }
+                        _ if self.lex_digit_separator() => {}
                         _ => {
+                            // Also consume any suffix so it doesn't get lexed as an identifier
+                            self.lex_integer_suffix();
+
                             self.diagnostic(
                                 DiagnosticId::IntegerLiteralTooLarge,
                                 SourceRange::new(
@@ -314,12 +828,39 @@ fn advance_state_machine(&mut self) {
                 }
             }
 
+            LexerState::CharacterLiteral => {
+                if let Some(token) = self.lex_character_literal() {
+                    self.queued_tokens.push_back(token);
+                }
+
+                self.state = LexerState::Start;
+            }
+
+            LexerState::StringLiteral => {
+                if let Some(token) = self.lex_string_literal() {
+                    self.queued_tokens.push_back(token);
+                }
+
+                self.state = LexerState::Start;
+            }
+
             LexerState::AfterSlash => {
                 match self.peek_next() {
                     Some('/') => {
                         // Two slashes in a row, the rest of the line thus is a comment
                         self.consume_character();
                         self.state = LexerState::LineComment;
+
+                        // Like clang only warn once per file, instead of for every comment
+                        if !self.line_comments && !self.line_comment_reported {
+                            self.line_comment_reported = true;
+
+                            self.diagnostic(
+                                DiagnosticId::LineCommentNotAllowed,
+                                self.token_begin_location,
+                                "// comments are not allowed in C89",
+                            );
+                        }
                     }
                     Some('*') => {
                         // Start of a multi-line comment
@@ -327,26 +868,18 @@ fn advance_state_machine(&mut self) {
                         self.state = LexerState::MultiLineComment;
                     }
 
-                    Some(_) => {
+                    _ => {
                         self.queued_tokens
                             .push_back(Token::new_slash(self.token_begin_location));
 
                         self.state = LexerState::Start;
                     }
-
-                    None => {
-                        self.queued_tokens
-                            .push_back(Token::new_slash(self.token_begin_location));
-                    }
                 }
             }
 
             LexerState::LineComment => match self.peek_next() {
-                Some('\n') => {
-                    self.consume_character();
-
-                    self.line += 1;
-                    self.column = 1;
+                Some('\n' | '\r') => {
+                    self.consume_newline();
 
                     self.state = LexerState::Start;
                 }
@@ -364,11 +897,8 @@ fn advance_state_machine(&mut self) {
                     self.state = LexerState::MultiLineCommentAfterStar;
                 }
 
-                Some('\n') => {
-                    self.consume_character();
-
-                    self.line += 1;
-                    self.column = 1;
+                Some('\n' | '\r') => {
+                    self.consume_newline();
                 }
 
                 Some(_) => {
@@ -388,11 +918,8 @@ fn advance_state_machine(&mut self) {
                         self.state = LexerState::Start;
                     }
 
-                    Some('\n') => {
-                        self.consume_character();
-
-                        self.line += 1;
-                        self.column = 1;
+                    Some('\n' | '\r') => {
+                        self.consume_newline();
 
                         self.state = LexerState::MultiLineComment;
                     }
@@ -420,17 +947,21 @@ fn advance_state_machine(&mut self) {
                     self.state = LexerState::Start;
                 }
 
-                Some(_) => {
-                    self.queued_tokens
-                        .push_back(Token::new_minus(self.token_begin_location));
+                Some('>') => {
+                    let location =
+                        SourceRange::new(self.token_begin_location, self.current_location());
+
+                    self.consume_character();
+                    self.queued_tokens.push_back(Token::new_arrow(location));
 
                     self.state = LexerState::Start;
                 }
 
-                None => {
-                    let location =
-                        SourceRange::new(self.token_begin_location, self.current_location());
-                    self.queued_tokens.push_back(Token::new_minus(location));
+                _ => {
+                    self.queued_tokens
+                        .push_back(Token::new_minus(self.token_begin_location));
+
+                    self.state = LexerState::Start;
                 }
             },
 
@@ -452,6 +983,791 @@ fn advance_state_machine(&mut self) {
                     self.state = LexerState::Start;
                 }
             },
+
+            LexerState::AfterLess => match self.peek_next() {
+                Some('=') => {
+                    let location =
+                        SourceRange::new(self.token_begin_location, self.current_location());
+
+                    self.consume_character();
+                    self.queued_tokens
+                        .push_back(Token::new_less_equal(location));
+
+                    self.state = LexerState::Start;
+                }
+
+                _ => {
+                    self.queued_tokens
+                        .push_back(Token::new_less(self.token_begin_location));
+
+                    self.state = LexerState::Start;
+                }
+            },
+
+            LexerState::AfterGreater => match self.peek_next() {
+                Some('=') => {
+                    let location =
+                        SourceRange::new(self.token_begin_location, self.current_location());
+
+                    self.consume_character();
+                    self.queued_tokens
+                        .push_back(Token::new_greater_equal(location));
+
+                    self.state = LexerState::Start;
+                }
+
+                _ => {
+                    self.queued_tokens
+                        .push_back(Token::new_greater(self.token_begin_location));
+
+                    self.state = LexerState::Start;
+                }
+            },
+
+            LexerState::AfterEqual => match self.peek_next() {
+                Some('=') => {
+                    let location =
+                        SourceRange::new(self.token_begin_location, self.current_location());
+
+                    self.consume_character();
+                    self.queued_tokens
+                        .push_back(Token::new_equal_equal(location));
+
+                    self.state = LexerState::Start;
+                }
+
+                _ => {
+                    self.queued_tokens
+                        .push_back(Token::new_equal(self.token_begin_location));
+
+                    self.state = LexerState::Start;
+                }
+            },
+
+            LexerState::AfterExclamation => match self.peek_next() {
+                Some('=') => {
+                    let location =
+                        SourceRange::new(self.token_begin_location, self.current_location());
+
+                    self.consume_character();
+                    self.queued_tokens
+                        .push_back(Token::new_exclamation_equal(location));
+
+                    self.state = LexerState::Start;
+                }
+
+                _ => {
+                    // Only the comparison operator is supported so far
+                    self.diagnostic(
+                        DiagnosticId::UnexpectedCharacter,
+                        self.token_begin_location,
+                        format!("unexpected character '{}' found", "!".bold()),
+                    );
+
+                    self.state = LexerState::Start;
+                }
+            },
+
+            LexerState::AfterAmpersand => match self.peek_next() {
+                Some('&') => {
+                    let location =
+                        SourceRange::new(self.token_begin_location, self.current_location());
+
+                    self.consume_character();
+                    self.queued_tokens
+                        .push_back(Token::new_ampersand_ampersand(location));
+
+                    self.state = LexerState::Start;
+                }
+
+                _ => {
+                    // Only the logical operator is supported so far
+                    self.diagnostic(
+                        DiagnosticId::UnexpectedCharacter,
+                        self.token_begin_location,
+                        format!("unexpected character '{}' found", "&".bold()),
+                    );
+
+                    self.state = LexerState::Start;
+                }
+            },
+
+            LexerState::AfterPipe => match self.peek_next() {
+                Some('|') => {
+                    let location =
+                        SourceRange::new(self.token_begin_location, self.current_location());
+
+                    self.consume_character();
+                    self.queued_tokens.push_back(Token::new_pipe_pipe(location));
+
+                    self.state = LexerState::Start;
+                }
+
+                _ => {
+                    // Only the logical operator is supported so far
+                    self.diagnostic(
+                        DiagnosticId::UnexpectedCharacter,
+                        self.token_begin_location,
+                        format!("unexpected character '{}' found", "|".bold()),
+                    );
+
+                    self.state = LexerState::Start;
+                }
+            },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diagnostic_consumer::IgnoreDiagnosticConsumer, token::TokenKind};
+
+    fn diagnostic_engine() -> Rc<RefCell<DiagnosticEngine>> {
+        Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            IgnoreDiagnosticConsumer,
+        ))))
+    }
+
+    #[test]
+    fn test_line_comments_in_c89() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "// first\nint main(void) { // second\n  return 0; /* not a line comment */\n}\n",
+        );
+        let lex = |line_comments: bool| {
+            let diagnostic_engine = diagnostic_engine();
+            let mut lexer = Lexer::new(diagnostic_engine.clone(), &source_file);
+            lexer.set_line_comments(line_comments);
+            let tokens = lexer.tokenize();
+
+            (
+                tokens.len(),
+                diagnostic_engine.borrow().number_of_warnings(),
+            )
+        };
+
+        // The comments are lexed the same way, C89 only warns about the first one
+        assert_eq!(lex(false), (10, 1));
+        assert_eq!(lex(true), (10, 0));
+    }
+
+    #[test]
+    fn test_column_saturates_on_long_line() {
+        let source_file = SourceFile::new("test.c", "x    ");
+        let diagnostic_engine = diagnostic_engine();
+        let mut lexer = Lexer::new(diagnostic_engine.clone(), &source_file);
+
+        // Pretend the line is already almost at the maximum number of columns
+        lexer.step();
+        lexer.step();
+        lexer.column = u32::MAX - 1;
+
+        let tokens = lexer.tokenize();
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(lexer.column, u32::MAX);
+        assert!(lexer.line_too_long_reported);
+        // The diagnostic is only reported once for all the characters past the maximum
+        assert_eq!(diagnostic_engine.borrow().number_of_warnings(), 1);
+    }
+
+    #[test]
+    fn test_line_saturates_on_file_too_big() {
+        let source_file = SourceFile::new("test.c", "x\n\n\n");
+        let diagnostic_engine = diagnostic_engine();
+        let mut lexer = Lexer::new(diagnostic_engine.clone(), &source_file);
+
+        // Pretend the file already has almost the maximum number of lines
+        lexer.step();
+        lexer.step();
+        lexer.line = u32::MAX - 1;
+
+        lexer.tokenize();
+
+        assert_eq!(lexer.line, u32::MAX);
+        assert_eq!(lexer.column, 1);
+        assert!(lexer.file_too_big_reported);
+        assert_eq!(diagnostic_engine.borrow().number_of_warnings(), 1);
+    }
+
+    fn lex_with_digit_separators(content: &str) -> (Vec<TokenKind>, Rc<RefCell<DiagnosticEngine>>) {
+        let source_file = SourceFile::new("test.c", content);
+        let diagnostic_engine = diagnostic_engine();
+        let mut lexer = Lexer::new(diagnostic_engine.clone(), &source_file);
+        lexer.set_digit_separators(true);
+
+        let kinds = lexer
+            .tokenize()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect();
+        (kinds, diagnostic_engine)
+    }
+
+    #[test]
+    fn test_digit_separators() {
+        let (kinds, diagnostic_engine) = lex_with_digit_separators("1'000 1_000_000 2'5u");
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::IntegerLiteral(1000, IntegerSuffix::None),
+                TokenKind::IntegerLiteral(1_000_000, IntegerSuffix::None),
+                TokenKind::IntegerLiteral(25, IntegerSuffix::Unsigned),
+            ]
+        );
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 0);
+    }
+
+    #[test]
+    fn test_misplaced_digit_separators() {
+        // Doubled, trailing and before a suffix
+        let (kinds, diagnostic_engine) = lex_with_digit_separators("1__0 1' 2_u");
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::IntegerLiteral(10, IntegerSuffix::None),
+                TokenKind::IntegerLiteral(1, IntegerSuffix::None),
+                TokenKind::IntegerLiteral(2, IntegerSuffix::Unsigned),
+            ]
+        );
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 3);
+    }
+
+    #[test]
+    fn test_digit_separators_disabled() {
+        let source_file = SourceFile::new("test.c", "1_000");
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        // Without the flag the separator starts an invalid suffix
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 1);
+    }
+
+    #[test]
+    fn test_integer_suffixes() {
+        assert_eq!(
+            token_kinds("42u 100L 7ULL 3lu"),
+            vec![
+                TokenKind::IntegerLiteral(42, IntegerSuffix::Unsigned),
+                TokenKind::IntegerLiteral(100, IntegerSuffix::Long),
+                TokenKind::IntegerLiteral(7, IntegerSuffix::UnsignedLongLong),
+                TokenKind::IntegerLiteral(3, IntegerSuffix::UnsignedLong),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_integer_suffixes() {
+        for source in ["1uu", "1lL", "1lul", "1lll"] {
+            let source_file = SourceFile::new("test.c", source);
+            let diagnostic_engine = diagnostic_engine();
+            let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+            // The whole suffix belongs to the literal, which is kept without a suffix
+            assert_eq!(
+                tokens.iter().map(|token| &token.kind).collect::<Vec<_>>(),
+                [&TokenKind::IntegerLiteral(1, IntegerSuffix::None)],
+                "{source}"
+            );
+            assert_eq!(diagnostic_engine.borrow().number_of_errors(), 1, "{source}");
+        }
+    }
+
+    #[test]
+    fn test_hex_literal() {
+        let source_file = SourceFile::new("test.c", "0xFF 0Xabcdef12 0xffffffffu 0x0");
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        assert_eq!(
+            tokens.iter().map(|token| &token.kind).collect::<Vec<_>>(),
+            [
+                &TokenKind::IntegerLiteral(255, IntegerSuffix::None),
+                &TokenKind::IntegerLiteral(0xABCD_EF12, IntegerSuffix::None),
+                &TokenKind::IntegerLiteral(u32::MAX, IntegerSuffix::Unsigned),
+                &TokenKind::IntegerLiteral(0, IntegerSuffix::None),
+            ]
+        );
+        assert_eq!(tokens[0].range.source_text(), Some("0xFF"));
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 0);
+    }
+
+    #[test]
+    fn test_hex_literal_too_large() {
+        let source_file = SourceFile::new("test.c", "0x100000000;");
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].kind, TokenKind::Semicolon);
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 1);
+    }
+
+    #[test]
+    fn test_hex_literal_without_digits() {
+        let source_file = SourceFile::new("test.c", "0x;");
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].range.source_text(), Some("0x"));
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 1);
+    }
+
+    #[test]
+    fn test_hex_literal_with_digit_separators() {
+        let (kinds, diagnostic_engine) = lex_with_digit_separators("0xFF'FF");
+
+        assert_eq!(
+            kinds,
+            vec![TokenKind::IntegerLiteral(0xFFFF, IntegerSuffix::None)]
+        );
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 0);
+    }
+
+    #[test]
+    fn test_dollars_in_identifiers() {
+        let source_file = SourceFile::new("test.c", "a$b $c");
+        let diagnostic_engine = diagnostic_engine();
+        let mut lexer = Lexer::new(diagnostic_engine.clone(), &source_file);
+        lexer.set_dollars_in_identifiers(true);
+
+        let kinds = lexer
+            .tokenize()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Identifier("a$b".to_string()),
+                TokenKind::Identifier("$c".to_string()),
+            ]
+        );
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 0);
+    }
+
+    #[test]
+    fn test_dollars_in_identifiers_disabled() {
+        let source_file = SourceFile::new("test.c", "a$b");
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        // The dollar sign is an unexpected character which splits the identifier
+        let kinds = tokens
+            .into_iter()
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Identifier("a".to_string()),
+                TokenKind::Identifier("b".to_string()),
+            ]
+        );
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 1);
+    }
+
+    fn token_kinds(content: &str) -> Vec<TokenKind> {
+        let source_file = SourceFile::new("test.c", content);
+
+        Lexer::new(diagnostic_engine(), &source_file)
+            .tokenize()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_tilde_minus() {
+        assert_eq!(
+            token_kinds("~-5"),
+            vec![
+                TokenKind::Tilde,
+                TokenKind::Minus,
+                TokenKind::IntegerLiteral(5, IntegerSuffix::None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_minus_minus() {
+        assert_eq!(
+            token_kinds("--x - 5"),
+            vec![
+                TokenKind::MinusMinus,
+                TokenKind::Identifier("x".to_string()),
+                TokenKind::Minus,
+                TokenKind::IntegerLiteral(5, IntegerSuffix::None),
+            ]
+        );
+    }
+
+    fn token_texts(content: &str) -> Vec<String> {
+        let source_file = SourceFile::new("test.c", content);
+
+        Lexer::new(diagnostic_engine(), &source_file)
+            .tokenize()
+            .into_iter()
+            .map(|token| token.range.source_text().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_comma() {
+        assert_eq!(
+            token_texts("f(a,b , c)"),
+            ["f", "(", "a", ",", "b", ",", "c", ")"]
+        );
+        assert_eq!(token_kinds(","), vec![TokenKind::Comma]);
+    }
+
+    #[test]
+    fn test_minus_maximal_munch() {
+        assert_eq!(token_texts("a---b"), ["a", "--", "-", "b"]);
+        assert_eq!(token_texts("--x"), ["--", "x"]);
+        assert_eq!(token_texts("x->y"), ["x", "->", "y"]);
+        assert_eq!(token_texts("x-->y"), ["x", "--", ">", "y"]);
+        assert_eq!(token_texts("x- >y"), ["x", "-", ">", "y"]);
+
+        assert_eq!(
+            token_kinds("x->y"),
+            vec![
+                TokenKind::Identifier("x".to_string()),
+                TokenKind::Arrow,
+                TokenKind::Identifier("y".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plus() {
+        assert_eq!(
+            token_kinds("1 + 2"),
+            vec![
+                TokenKind::IntegerLiteral(1, IntegerSuffix::None),
+                TokenKind::Plus,
+                TokenKind::IntegerLiteral(2, IntegerSuffix::None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plus_plus_maximal_munch() {
+        assert_eq!(
+            token_kinds("+++ +"),
+            vec![TokenKind::PlusPlus, TokenKind::Plus, TokenKind::Plus]
+        );
+    }
+
+    #[test]
+    fn test_star() {
+        assert_eq!(
+            token_kinds("3 * 4"),
+            vec![
+                TokenKind::IntegerLiteral(3, IntegerSuffix::None),
+                TokenKind::Star,
+                TokenKind::IntegerLiteral(4, IntegerSuffix::None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_star_next_to_comment() {
+        // Only a star directly after a slash starts a comment
+        assert_eq!(
+            token_kinds("3 */* comment */* 4"),
+            vec![
+                TokenKind::IntegerLiteral(3, IntegerSuffix::None),
+                TokenKind::Star,
+                TokenKind::Star,
+                TokenKind::IntegerLiteral(4, IntegerSuffix::None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operators_at_end_of_file() {
+        assert_eq!(
+            token_kinds("x--"),
+            vec![
+                TokenKind::Identifier("x".to_string()),
+                TokenKind::MinusMinus
+            ]
+        );
+        assert_eq!(token_kinds("-"), vec![TokenKind::Minus]);
+        assert_eq!(token_kinds("+"), vec![TokenKind::Plus]);
+        assert_eq!(token_kinds("/"), vec![TokenKind::Slash]);
+    }
+
+    #[test]
+    fn test_comparison_operators_maximal_munch() {
+        assert_eq!(
+            token_kinds("< <= > >= == != <<= =="),
+            vec![
+                TokenKind::Less,
+                TokenKind::LessEqual,
+                TokenKind::Greater,
+                TokenKind::GreaterEqual,
+                TokenKind::EqualEqual,
+                TokenKind::ExclamationEqual,
+                TokenKind::Less,
+                TokenKind::LessEqual,
+                TokenKind::EqualEqual,
+            ]
+        );
+        assert_eq!(token_kinds("1<2"), token_kinds("1 < 2"));
+        assert_eq!(token_kinds("<"), vec![TokenKind::Less]);
+        assert_eq!(token_kinds(">="), vec![TokenKind::GreaterEqual]);
+    }
+
+    #[test]
+    fn test_lone_equal_and_exclamation() {
+        let source_file = SourceFile::new("test.c", "= ! =!=");
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        // Logical not isn't supported yet
+        let kinds = tokens
+            .into_iter()
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Equal,
+                TokenKind::Equal,
+                TokenKind::ExclamationEqual
+            ]
+        );
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 1);
+    }
+
+    #[test]
+    fn test_equal_maximal_munch() {
+        assert_eq!(token_texts("x=y==z"), ["x", "=", "y", "==", "z"]);
+        assert_eq!(token_texts("x===y"), ["x", "==", "=", "y"]);
+        assert_eq!(token_texts("x="), ["x", "="]);
+    }
+
+    #[test]
+    fn test_logical_operators() {
+        assert_eq!(
+            token_kinds("a&&b || c|||"),
+            vec![
+                TokenKind::Identifier("a".to_string()),
+                TokenKind::AmpersandAmpersand,
+                TokenKind::Identifier("b".to_string()),
+                TokenKind::PipePipe,
+                TokenKind::Identifier("c".to_string()),
+                TokenKind::PipePipe,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_character_literals() {
+        assert_eq!(
+            token_kinds(r#"'A' '\n' '\t' '\\' '\'' '\0' '\101' '\"'"#),
+            vec![
+                TokenKind::CharacterLiteral(65),
+                TokenKind::CharacterLiteral(10),
+                TokenKind::CharacterLiteral(9),
+                TokenKind::CharacterLiteral(92),
+                TokenKind::CharacterLiteral(39),
+                TokenKind::CharacterLiteral(0),
+                TokenKind::CharacterLiteral(65),
+                TokenKind::CharacterLiteral(34),
+            ]
+        );
+
+        let source_file = SourceFile::new("test.c", r"'\n';");
+        let tokens = Lexer::new(diagnostic_engine(), &source_file).tokenize();
+        assert_eq!(tokens[0].range.source_text(), Some(r"'\n'"));
+        assert_eq!(tokens[1].kind, TokenKind::Semicolon);
+    }
+
+    #[test]
+    fn test_malformed_character_literals() {
+        let lex = |source: &str| {
+            let source_file = SourceFile::new("test.c", source);
+            let diagnostic_engine = diagnostic_engine();
+            let kinds = Lexer::new(diagnostic_engine.clone(), &source_file)
+                .tokenize()
+                .into_iter()
+                .map(|token| token.kind)
+                .collect::<Vec<_>>();
+            let number_of_errors = diagnostic_engine.borrow().number_of_errors();
+
+            (kinds, number_of_errors)
+        };
+
+        // The rest of the line belongs to an unterminated literal
+        assert_eq!(lex("''"), (vec![TokenKind::CharacterLiteral(0)], 1));
+        assert_eq!(lex("'a"), (vec![], 1));
+        assert_eq!(lex("'a;\n;"), (vec![TokenKind::Semicolon], 1));
+        assert_eq!(lex("'\\"), (vec![], 1));
+        assert_eq!(lex("'ab';"), (vec![TokenKind::Semicolon], 1));
+    }
+
+    #[test]
+    fn test_unknown_escape_sequence() {
+        let source_file = SourceFile::new("test.c", r"'\q'");
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        assert_eq!(tokens[0].kind, TokenKind::CharacterLiteral(u32::from('q')));
+        assert_eq!(diagnostic_engine.borrow().number_of_warnings(), 1);
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 0);
+    }
+
+    #[test]
+    fn test_string_literals() {
+        let source_file = SourceFile::new("test.c", r#""hi\n" "" "a\"b\\c\0";"#);
+        let tokens = Lexer::new(diagnostic_engine(), &source_file).tokenize();
+
+        assert_eq!(
+            tokens.iter().map(|token| &token.kind).collect::<Vec<_>>(),
+            [
+                &TokenKind::StringLiteral(b"hi\n".to_vec()),
+                &TokenKind::StringLiteral(Vec::new()),
+                &TokenKind::StringLiteral(b"a\"b\\c\0".to_vec()),
+                &TokenKind::Semicolon,
+            ]
+        );
+        assert_eq!(tokens[0].range.source_text(), Some(r#""hi\n""#));
+    }
+
+    #[test]
+    fn test_string_literal_bytes() {
+        let source_file = SourceFile::new("test.c", r#""\377" "\1010" "ä\t""#);
+        let tokens = Lexer::new(diagnostic_engine(), &source_file).tokenize();
+
+        // Octal escapes are single bytes while other characters are encoded as UTF-8
+        assert_eq!(
+            tokens.iter().map(|token| &token.kind).collect::<Vec<_>>(),
+            [
+                &TokenKind::StringLiteral(vec![0xFF]),
+                &TokenKind::StringLiteral(b"A0".to_vec()),
+                &TokenKind::StringLiteral(vec![0xC3, 0xA4, b'\t']),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_string_literal() {
+        let source_file = SourceFile::new("test.c", "  \"hi;\n;");
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        // The string swallows the rest of the line but not the next one
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].kind, TokenKind::Semicolon);
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 1);
+
+        // A backslash can't escape the end of the file
+        let source_file = SourceFile::new("test.c", "\"hi\\");
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+
+        assert!(tokens.is_empty());
+        assert_eq!(diagnostic_engine.borrow().number_of_errors(), 2);
+    }
+
+    #[test]
+    fn test_line_endings() {
+        for content in [
+            "a\nb",
+            "a\r\nb",
+            "a\rb",
+            "a // c\r\nb",
+            "a // c\rb",
+            "a /* \r\n */b",
+        ] {
+            let source_file = SourceFile::new("test.c", content);
+            let tokens = Lexer::new(diagnostic_engine(), &source_file).tokenize();
+
+            assert_eq!(tokens.len(), 2, "{content:?}");
+            assert_eq!(
+                (tokens[1].range.begin.line, tokens[1].range.begin.column),
+                (2, if content.contains("*/") { 4 } else { 1 }),
+                "{content:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_blank_crlf_lines() {
+        let source_file = SourceFile::new("test.c", "a\r\n\r\n\r\rb");
+        let tokens = Lexer::new(diagnostic_engine(), &source_file).tokenize();
+
+        assert_eq!(tokens[1].range.begin.line, 5);
+        assert_eq!(tokens[1].range.begin.column, 1);
+    }
+
+    #[test]
+    fn test_step_through_declaration() {
+        let source_file = SourceFile::new("test.c", "int x");
+        let mut lexer = Lexer::new(diagnostic_engine(), &source_file);
+
+        let mut steps = Vec::new();
+        while !lexer.is_finished() {
+            let step = lexer.step();
+            steps.push((
+                step.state,
+                step.index,
+                step.line,
+                step.column,
+                step.emitted_token.map(|token| token.kind),
+            ));
+        }
+
+        assert_eq!(
+            steps,
+            [
+                (LexerState::Identifier, 0, 1, 1, None),
+                (LexerState::Start, 3, 1, 4, Some(TokenKind::KeywordInt)),
+                (LexerState::Start, 4, 1, 5, None),
+                (LexerState::Identifier, 4, 1, 5, None),
+                (
+                    LexerState::Start,
+                    5,
+                    1,
+                    6,
+                    Some(TokenKind::Identifier("x".to_string()))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step_keeps_tokens_for_tokenize() {
+        let source_file = SourceFile::new("test.c", "int x");
+        let mut lexer = Lexer::new(diagnostic_engine(), &source_file);
+
+        lexer.step();
+        lexer.step();
+
+        let tokens = lexer.tokenize();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].kind, TokenKind::KeywordInt);
+    }
+
+    #[test]
+    fn test_step_tracks_newlines() {
+        let source_file = SourceFile::new("test.c", "\n;");
+        let mut lexer = Lexer::new(diagnostic_engine(), &source_file);
+
+        let step = lexer.step();
+        assert_eq!((step.index, step.line, step.column), (1, 2, 1));
+        assert_eq!(step.emitted_token, None);
+
+        let step = lexer.step();
+        assert_eq!((step.index, step.line, step.column), (2, 2, 2));
+        assert_eq!(step.emitted_token.unwrap().kind, TokenKind::Semicolon);
+    }
+}
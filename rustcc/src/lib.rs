@@ -1,18 +1,36 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    ffi::OsString,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    rc::Rc,
+};
 
+use clap::ArgMatches;
 use codegen::Codegen;
-use diagnostic_consumer::DefaultDiagnosticConsumer;
+use diagnostic::{Diagnostic, DiagnosticId};
+use diagnostic_consumer::{
+    DefaultDiagnosticConsumer, DiagnosticConsumer, JsonDiagnosticConsumer,
+    MinimalDiagnosticConsumer,
+};
 use diagnostic_engine::DiagnosticEngine;
+use error::{Error, Result};
 use parser::Parser;
+use source_file::SourceFile;
 use source_manager::{RealFSSourceManager, SourceManager};
+use source_range::SourceRange;
 
 pub mod ast;
 pub mod codegen;
 pub mod command_line;
+pub mod dependency_file;
 pub mod diagnostic;
 pub mod diagnostic_builder;
 pub mod diagnostic_consumer;
 pub mod diagnostic_engine;
+pub mod error;
+pub(crate) mod json;
 pub mod lexer;
 pub mod parser;
 pub mod source_file;
@@ -21,67 +39,472 @@
 pub mod source_range;
 pub mod token;
 
-pub fn run_main() {
+/// Compiles `source` as if it was read from `file_path` and returns the generated code.
+///
+/// All diagnostics are reported to `diagnostic_engine`, an error is returned if any stage failed.
+pub fn compile_str(
+    diagnostic_engine: Rc<RefCell<DiagnosticEngine>>,
+    file_path: &str,
+    source: &str,
+) -> Result<Codegen> {
+    let source_file = SourceFile::new(file_path, source);
+
+    compile_source_file(diagnostic_engine, &source_file)
+}
+
+/// Loads `file_path` using `source_manager` and compiles it, see [`compile_str`].
+pub fn compile_file<'a, M: SourceManager<'a>>(
+    diagnostic_engine: Rc<RefCell<DiagnosticEngine>>,
+    source_manager: &M,
+    file_path: &'a str,
+) -> Result<Codegen> {
+    let source_file = source_manager.load_file(file_path)?;
+
+    compile_source_file(diagnostic_engine, source_file)
+}
+
+fn compile_source_file(
+    diagnostic_engine: Rc<RefCell<DiagnosticEngine>>,
+    source_file: &SourceFile,
+) -> Result<Codegen> {
+    let number_of_errors = || diagnostic_engine.borrow().number_of_errors();
+
+    let errors_before_lexing = number_of_errors();
+    let tokens = lexer::Lexer::new(diagnostic_engine.clone(), source_file).tokenize();
+    if number_of_errors() > errors_before_lexing {
+        return Err(Error::Lex {
+            number_of_errors: number_of_errors() - errors_before_lexing,
+        });
+    }
+
+    let errors_before_parsing = number_of_errors();
+    let parse_result = Parser::new(diagnostic_engine.clone(), tokens).parse();
+    if parse_result.had_errors {
+        return Err(Error::Parse {
+            number_of_errors: number_of_errors() - errors_before_parsing,
+        });
+    }
+
+    let codegen = Codegen::with_diagnostic_engine(&source_file.path, diagnostic_engine);
+    codegen.codegen(&parse_result.translation_unit)?;
+
+    Ok(codegen)
+}
+
+pub fn run_main() -> ExitCode {
     // Handle command line arguments
     let command_line_matches = command_line::command_line().get_matches();
 
+    run(
+        &command_line_matches,
+        None,
+        &mut Output {
+            stdout: &mut io::stdout(),
+            stderr: &mut io::stderr(),
+        },
+    )
+}
+
+/// Runs the compiler like [`run_main`] with `arguments` instead of the process arguments, the first
+/// one being the program name. All diagnostics are reported to `diagnostic_consumer` instead of
+/// the one selected by `-fdiagnostics-format` and nothing is printed, so the printing options and
+/// command line errors only affect the exit code.
+pub fn run_with_diagnostic_consumer<I, T>(
+    arguments: I,
+    diagnostic_consumer: Box<dyn DiagnosticConsumer>,
+) -> ExitCode
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    match command_line::command_line().try_get_matches_from(arguments) {
+        Ok(command_line_matches) => run(
+            &command_line_matches,
+            Some(diagnostic_consumer),
+            &mut Output {
+                stdout: &mut io::sink(),
+                stderr: &mut io::sink(),
+            },
+        ),
+        Err(error) => {
+            if error.use_stderr() {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+    }
+}
+
+/// Where the compiler prints everything besides the diagnostics
+struct Output<'a> {
+    stdout: &'a mut dyn Write,
+    stderr: &'a mut dyn Write,
+}
+
+fn run(
+    command_line_matches: &ArgMatches,
+    diagnostic_consumer: Option<Box<dyn DiagnosticConsumer>>,
+    output: &mut Output,
+) -> ExitCode {
+    // Print the warning flags, e.g. for tools checking which of them are covered by tests
+    if command_line_matches.get_flag(command_line::ARG_PRINT_DIAGNOSTIC_FLAGS) {
+        for flag_name in DiagnosticId::flag_names() {
+            let _ = writeln!(output.stdout, "{flag_name}");
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
     // Get the first command line argument as the file path
     let file_path: &String = command_line_matches
         .get_one(command_line::ARG_INPUT_FILE)
         .unwrap();
 
-    // Create our source manager
-    let source_manager = RealFSSourceManager::new();
-
     // Create our diagnostic consumer
-    let diagnostic_consumer = Box::new(DefaultDiagnosticConsumer);
+    let diagnostics_format: &String = command_line_matches
+        .get_one(command_line::ARG_DIAGNOSTICS_FORMAT)
+        .unwrap();
+    let diagnostics_to_stderr =
+        command_line_matches.get_flag(command_line::ARG_DIAGNOSTICS_TO_STDERR);
+    let message_length = command_line_matches
+        .get_one::<usize>(command_line::ARG_MESSAGE_LENGTH)
+        .copied()
+        .unwrap_or_default();
+    let diagnostic_consumer =
+        diagnostic_consumer.unwrap_or_else(|| match diagnostics_format.as_str() {
+            command_line::DIAGNOSTICS_FORMAT_MINIMAL => Box::new(
+                MinimalDiagnosticConsumer::new(diagnostics_to_stderr)
+                    .with_message_length(message_length),
+            ),
+            command_line::DIAGNOSTICS_FORMAT_JSON => Box::new(JsonDiagnosticConsumer),
+            _ => Box::new(
+                DefaultDiagnosticConsumer::new(diagnostics_to_stderr)
+                    .with_message_length(message_length),
+            ),
+        });
 
     // Create our diagnostic engine
     let diagnostic_engine = Rc::new(RefCell::from(DiagnosticEngine::new(diagnostic_consumer)));
 
+    // Limit the number of reported errors
+    if let Some(error_limit) = command_line_matches.get_one::<u64>(command_line::ARG_ERROR_LIMIT) {
+        diagnostic_engine.borrow_mut().set_error_limit(*error_limit);
+    }
+
+    // Configure warnings
+    if command_line_matches.get_flag(command_line::ARG_IGNORE_ALL_WARNINGS) {
+        diagnostic_engine.borrow_mut().set_ignore_all_warnings(true);
+    }
+    if command_line_matches.get_flag(command_line::ARG_PEDANTIC_ERRORS) {
+        diagnostic_engine.borrow_mut().set_pedantic_errors(true);
+    }
+    let warnings = command_line_matches
+        .get_many::<String>(command_line::ARG_WARNINGS)
+        .unwrap_or_default()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    for warning in &warnings {
+        if *warning == command_line::WARNING_ERROR {
+            diagnostic_engine.borrow_mut().set_warnings_as_errors(true);
+        } else if let Some(id) = command_line::warning_option_diagnostic(warning) {
+            let enabled = !warning.starts_with(command_line::WARNING_DISABLE_PREFIX);
+            diagnostic_engine
+                .borrow_mut()
+                .set_warning_enabled(id, enabled);
+        }
+    }
+
+    // The last of contradicting warning options wins, which is easy to miss in long command lines
+    for (earlier, later) in command_line::conflicting_warning_options(warnings) {
+        diagnostic_engine.borrow_mut().report(&mut Diagnostic::new(
+            DiagnosticId::OptionConflict,
+            SourceRange::invalid(),
+            format!("'-W{later}' overrides the earlier '-W{earlier}'"),
+        ));
+    }
+
+    // Compile the input file and summarize the diagnostics reported while doing so
+    let exit_code = compile_input_file(command_line_matches, file_path, &diagnostic_engine, output);
+
+    // The JSON output is meant for tools, which can count the diagnostics themselves
+    let summary = if diagnostics_format == command_line::DIAGNOSTICS_FORMAT_JSON {
+        None
+    } else {
+        diagnostic_engine.borrow().summary()
+    };
+    if let Some(summary) = summary {
+        if diagnostics_to_stderr || diagnostic_engine.borrow().error_occurred() {
+            let _ = writeln!(output.stderr, "{summary}");
+        } else {
+            let _ = writeln!(output.stdout, "{summary}");
+        }
+    }
+
+    exit_code
+}
+
+/// Compiles the input file as configured on the command line and returns the exit code
+fn compile_input_file(
+    command_line_matches: &ArgMatches,
+    file_path: &str,
+    diagnostic_engine: &Rc<RefCell<DiagnosticEngine>>,
+    output: &mut Output,
+) -> ExitCode {
+    // Create our source manager
+    let source_manager = RealFSSourceManager::new();
+
     // Load the input file into our source manager
-    let source_file = match source_manager.load_file(file_path.as_str()) {
-        Some(source) => source,
-        None => {
-            eprintln!("Error reading file: '{file_path}'");
-            // TODO: Once we recover the error handling, print the error message here
-            //eprintln!("{error}");
-
-            std::process::exit(1);
+    let source_file = match source_manager.load_file(file_path) {
+        Ok(source) => source,
+        Err(error) => {
+            let _ = writeln!(output.stderr, "{error}");
+
+            return ExitCode::FAILURE;
         }
     };
 
+    // Write the dependency file
+    if command_line_matches.get_flag(command_line::ARG_WRITE_DEPENDENCY_FILE) {
+        let dependency_file_path = command_line_matches
+            .get_one::<String>(command_line::ARG_DEPENDENCY_FILE)
+            .map_or_else(|| Path::new(file_path).with_extension("d"), PathBuf::from);
+        let target = Path::new(file_path).with_extension("o");
+        let target = target.file_name().unwrap_or_default().to_string_lossy();
+
+        if let Err(error) = dependency_file::write_dependency_file(
+            &dependency_file_path,
+            &target,
+            &source_manager.loaded_paths(),
+        ) {
+            let _ = writeln!(
+                output.stderr,
+                "Error writing dependency file '{}': {error}",
+                dependency_file_path.display()
+            );
+
+            return ExitCode::FAILURE;
+        }
+    }
+
+    // Print the source code exactly as we are going to lex it
+    if command_line_matches.get_flag(command_line::ARG_SHOW_INPUT) {
+        let _ = write!(output.stderr, "{}", source_file.dump_numbered());
+    }
+
     // Create a lexer
     let mut lexer = lexer::Lexer::new(diagnostic_engine.clone(), source_file);
+    lexer.set_digit_separators(command_line_matches.get_flag(command_line::ARG_DIGIT_SEPARATORS));
+    lexer.set_dollars_in_identifiers(
+        command_line_matches.get_flag(command_line::ARG_DOLLARS_IN_IDENTIFIERS),
+    );
+    lexer.set_line_comments(
+        command_line_matches
+            .get_one::<String>(command_line::ARG_STD)
+            .is_none_or(|standard| standard != command_line::STD_C89),
+    );
     let tokens = lexer.tokenize();
 
     // Print all tokens
     if command_line_matches.get_flag(command_line::ARG_PRINT_TOKENS) {
         for token in &tokens {
-            println!("{}", token.dump());
+            let _ = writeln!(output.stdout, "{}", token.dump());
         }
     }
 
     // Create a parser
     let mut parser = Parser::new(diagnostic_engine.clone(), tokens);
-    let translation_unit = parser.parse();
+    let parse_result = parser.parse();
+    let translation_unit = parse_result.translation_unit;
 
     // Print the abstract syntax tree (AST)
     if command_line_matches.get_flag(command_line::ARG_PRINT_AST) {
-        println!("{}", translation_unit.dump());
+        let _ = write!(output.stdout, "{}", translation_unit.dump());
+    }
+
+    // Print the scopes and the variables declared in them
+    if command_line_matches.get_flag(command_line::ARG_DUMP_SCOPES) {
+        let _ = write!(output.stdout, "{}", translation_unit.dump_scopes());
+    }
+
+    // Don't generate code for a partially parsed translation unit
+    if parse_result.had_errors {
+        return exit_code(command_line_matches, &diagnostic_engine.borrow());
     }
 
     // Codegen the translation unit
-    let codegen = Codegen::new(file_path);
+    let codegen = Codegen::with_diagnostic_engine(&source_file.path, diagnostic_engine.clone());
+    let codegen = match command_line_matches.get_one::<String>(command_line::ARG_TARGET) {
+        Some(triple) => match codegen.targeting(triple) {
+            Ok(codegen) => codegen,
+            Err(error) => {
+                diagnostic_engine.borrow_mut().report(&mut Diagnostic::new(
+                    DiagnosticId::UnknownTarget,
+                    SourceRange::invalid(),
+                    error.to_string(),
+                ));
 
-    codegen.codegen(&translation_unit);
+                return exit_code(command_line_matches, &diagnostic_engine.borrow());
+            }
+        },
+        None => codegen,
+    };
+
+    match codegen.codegen(&translation_unit) {
+        Ok(()) => {}
+        Err(Error::CodegenReported) => {
+            return exit_code(command_line_matches, &diagnostic_engine.borrow());
+        }
+        Err(error) => {
+            let _ = writeln!(output.stderr, "{error}");
+
+            return ExitCode::FAILURE;
+        }
+    }
 
     // Print the LLVM intermediate representation (IR)
     if command_line_matches.get_flag(command_line::ARG_PRINT_IR) {
-        codegen.dump();
+        let _ = write!(output.stderr, "{}", codegen.ir_string());
+    }
+
+    // Print the mapping from source lines to LLVM instructions
+    if command_line_matches.get_flag(command_line::ARG_PRINT_SOURCE_MAP) {
+        let _ = writeln!(output.stdout, "{}", codegen.source_map_json());
+    }
+
+    // Print the fingerprint of the generated module
+    if command_line_matches.get_flag(command_line::ARG_MODULE_HASH) {
+        let _ = writeln!(output.stdout, "{:016x}", codegen.module_hash());
+    }
+
+    // Write the generated code to the output file, without '--emit' its kind depends on the extension
+    let emit = command_line_matches
+        .get_one::<String>(command_line::ARG_EMIT)
+        .map(String::as_str);
+    let output_path = command_line_matches
+        .get_one::<String>(command_line::ARG_OUTPUT)
+        .map(PathBuf::from)
+        .or_else(|| {
+            let extension = match emit {
+                Some(emit) => command_line::emit_file_extension(emit),
+                None if command_line_matches.get_flag(command_line::ARG_COMPILE_ONLY) => {
+                    command_line::OBJ_FILE_EXTENSION
+                }
+                None => return None,
+            };
+
+            Some(Path::new(file_path).with_extension(extension))
+        });
+    if let Some(output_path) = output_path {
+        let result = match emit.unwrap_or_else(|| command_line::emit_for_output_file(&output_path))
+        {
+            command_line::EMIT_IR => codegen.write_ir_to_file(&output_path),
+            command_line::EMIT_ASM => codegen.emit_assembly(&output_path),
+            _ => codegen.write_object_file(&output_path),
+        };
+
+        if let Err(error) = result {
+            let _ = writeln!(output.stderr, "{error}");
+
+            return ExitCode::FAILURE;
+        }
+    }
+
+    exit_code(command_line_matches, &diagnostic_engine.borrow())
+}
+
+/// Determines the exit code from the diagnostics reported so far
+fn exit_code(command_line_matches: &ArgMatches, diagnostic_engine: &DiagnosticEngine) -> ExitCode {
+    // Use the custom exit code if we only failed because of warnings turned into errors
+    if diagnostic_engine.only_promoted_warnings_occurred() {
+        if let Some(exit_code) =
+            command_line_matches.get_one::<u8>(command_line::ARG_WERROR_EXIT_CODE)
+        {
+            return ExitCode::from(*exit_code);
+        }
+    }
+
+    if diagnostic_engine.error_occurred() {
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        diagnostic_consumer::IgnoreDiagnosticConsumer,
+        source_manager::{EmptySourceManager, VirtualSourceManager},
+    };
+
+    fn diagnostic_engine() -> Rc<RefCell<DiagnosticEngine>> {
+        Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            IgnoreDiagnosticConsumer,
+        ))))
     }
 
-    if diagnostic_engine.borrow().error_occurred() {
-        std::process::exit(1);
+    #[test]
+    fn test_compile_str() {
+        let codegen = compile_str(
+            diagnostic_engine(),
+            "test.c",
+            "int main(void) { return 0; }",
+        )
+        .unwrap();
+
+        assert_eq!(codegen.function_names(), ["main"]);
+    }
+
+    #[test]
+    fn test_compile_file() {
+        let mut source_manager = VirtualSourceManager::new();
+        source_manager.add_file("test.c", "int main(void) { return 0; }");
+
+        let codegen = compile_file(diagnostic_engine(), &source_manager, "test.c").unwrap();
+
+        assert_eq!(codegen.function_names(), ["main"]);
+    }
+
+    #[test]
+    fn test_compile_file_io_error() {
+        let result = compile_file(diagnostic_engine(), &EmptySourceManager, "missing.c");
+
+        assert!(matches!(result, Err(Error::Io { path, .. }) if path == "missing.c"));
+    }
+
+    #[test]
+    fn test_compile_str_lex_error() {
+        let result = compile_str(
+            diagnostic_engine(),
+            "test.c",
+            "int main(void) { return @; }",
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Lex {
+                number_of_errors: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_compile_str_parse_error() {
+        let result = compile_str(diagnostic_engine(), "test.c", "int main(void) { return 0 }");
+
+        assert!(matches!(result, Err(Error::Parse { .. })));
+    }
+
+    #[test]
+    fn test_compile_str_codegen_error() {
+        let result = compile_str(
+            diagnostic_engine(),
+            "test.c",
+            "int main(void) { return 0; }\nint main(void) { return 1; }",
+        );
+
+        assert!(matches!(result, Err(Error::CodegenReported)));
     }
 }
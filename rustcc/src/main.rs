@@ -1,5 +1,7 @@
+use std::process::ExitCode;
+
 use rustcc::run_main;
 
-fn main() {
-    run_main();
+fn main() -> ExitCode {
+    run_main()
 }
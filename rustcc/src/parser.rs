@@ -2,21 +2,68 @@
 
 use crate::{
     ast::{
-        Expression, ExpressionKind, FunctionDefinition, Statement, TranslationUnit, UnaryOperator,
+        BinaryOperator, Expression, ExpressionKind, FunctionDeclaration, FunctionDefinition,
+        Qualifiers, Statement, StatementKind, StorageClass, TranslationUnit, Type, UnaryOperator,
     },
     diagnostic::{Diagnostic, DiagnosticId},
     diagnostic_builder::DiagnosticBuilder,
     diagnostic_engine::DiagnosticEngine,
+    lexer::Lexer,
+    source_file::SourceFile,
     source_range::SourceRange,
     token::{Token, TokenKind, TokenList},
 };
 
 // TODO: This is a mess probably need to completely rethink and rewrite this
 
+/// Lexes and parses exactly one statement from the given source file.
+///
+/// Returns `None` if the source file doesn't contain exactly one valid statement.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{cell::RefCell, rc::Rc};
+/// # use rustcc::diagnostic_consumer::IgnoreDiagnosticConsumer;
+/// # use rustcc::diagnostic_engine::DiagnosticEngine;
+/// # use rustcc::parser::parse_statement_from_source;
+/// # use rustcc::source_file::SourceFile;
+/// let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+///     IgnoreDiagnosticConsumer,
+/// ))));
+/// let source_file = SourceFile::new("test.c", "return 0;");
+///
+/// assert!(parse_statement_from_source(diagnostic_engine, &source_file).is_some());
+/// ```
+pub fn parse_statement_from_source<'a>(
+    diagnostic_engine: Rc<RefCell<DiagnosticEngine>>,
+    source_file: &'a SourceFile,
+) -> Option<Statement<'a>> {
+    let tokens = Lexer::new(diagnostic_engine.clone(), source_file).tokenize();
+    let mut parser = Parser::new(diagnostic_engine, tokens);
+
+    parser.parse_single_statement()
+}
+
+/// The result of parsing a translation unit.
+///
+/// The translation unit contains everything which could be parsed, even if errors occurred.
+#[derive(Debug, Clone)]
+pub struct ParseResult<'a> {
+    pub translation_unit: TranslationUnit<'a>,
+    /// Whether any errors were reported while parsing
+    pub had_errors: bool,
+}
+
 pub struct Parser<'a> {
     diagnostic_engine: Rc<RefCell<DiagnosticEngine>>,
     tokens: TokenList<'a>,
     index: RefCell<usize>,
+    /// The names of the variables declared in each enclosing block, the innermost block is last
+    scopes: RefCell<Vec<Vec<String>>>,
+    /// The names of the functions declared or defined so far together with whether any of their
+    /// declarations has a prototype, only these functions can be called
+    functions: RefCell<Vec<(String, bool)>>,
 }
 
 impl<'a> Parser<'a> {
@@ -28,11 +75,13 @@ pub fn new(
             diagnostic_engine,
             tokens,
             index: RefCell::from(0),
+            scopes: RefCell::from(Vec::new()),
+            functions: RefCell::from(Vec::new()),
         }
     }
 
     fn diagnostic<S: Into<String>, R: Into<SourceRange<'a>>>(
-        &'a self,
+        &self,
         id: DiagnosticId,
         source_range: R,
         message: S,
@@ -42,6 +91,62 @@ fn diagnostic<S: Into<String>, R: Into<SourceRange<'a>>>(
         DiagnosticBuilder::new(self.diagnostic_engine.clone(), diagnostic)
     }
 
+    fn expect_identifier(&self) -> Option<&Token<'a>> {
+        let token = self.peek_next().filter(|token| token.is_identifier())?;
+        self.consume();
+
+        Some(token)
+    }
+
+    /// Declares a variable in the innermost block, a block may only declare a name once
+    fn declare_variable(&self, name: &str, range: SourceRange<'a>) {
+        let mut scopes = self.scopes.borrow_mut();
+        let Some(scope) = scopes.last_mut() else {
+            return;
+        };
+
+        if scope.iter().any(|declared_name| declared_name == name) {
+            self.diagnostic(
+                DiagnosticId::VariableRedefinition,
+                range,
+                format!("redefinition of '{}'", name),
+            );
+            return;
+        }
+
+        scope.push(name.to_string());
+    }
+
+    fn is_variable_declared(&self, name: &str) -> bool {
+        self.scopes
+            .borrow()
+            .iter()
+            .any(|scope| scope.iter().any(|declared_name| declared_name == name))
+    }
+
+    fn declare_function(&self, name: &str, has_prototype: bool) {
+        let mut functions = self.functions.borrow_mut();
+
+        // Once a declaration has a prototype the function keeps it, even for later declarations
+        // without one like `int f(void); int f();`
+        match functions
+            .iter_mut()
+            .find(|(declared_name, _)| declared_name == name)
+        {
+            Some((_, declared_has_prototype)) => *declared_has_prototype |= has_prototype,
+            None => functions.push((name.to_string(), has_prototype)),
+        }
+    }
+
+    /// Returns whether the function has a prototype, or `None` if it wasn't declared
+    fn function_has_prototype(&self, name: &str) -> Option<bool> {
+        self.functions
+            .borrow()
+            .iter()
+            .find(|(declared_name, _)| declared_name == name)
+            .map(|(_, has_prototype)| *has_prototype)
+    }
+
     fn is_finished(&self) -> bool {
         *self.index.borrow() >= self.tokens.len()
     }
@@ -52,10 +157,23 @@ fn current_token_source_range(&self) -> SourceRange<'a> {
             .unwrap_or_default()
     }
 
+    fn previous_token_source_range(&self) -> SourceRange<'a> {
+        self.index
+            .borrow()
+            .checked_sub(1)
+            .and_then(|index| self.tokens.get(index))
+            .map(|token| token.range)
+            .unwrap_or_default()
+    }
+
     fn peek_next(&self) -> Option<&Token<'a>> {
         self.tokens.get(*self.index.borrow())
     }
 
+    fn peek_after_next(&self) -> Option<&Token<'a>> {
+        self.tokens.get(*self.index.borrow() + 1)
+    }
+
     fn consume(&self) {
         *self.index.borrow_mut() += 1;
     }
@@ -77,21 +195,136 @@ fn expect(&self, token_kind: TokenKind) -> Option<&Token<'a>> {
         None
     }
 
-    pub fn parse(&mut self) -> TranslationUnit {
+    pub fn parse(&mut self) -> ParseResult<'a> {
+        let number_of_errors = self.diagnostic_engine.borrow().number_of_errors();
         let mut translation_unit = TranslationUnit::new();
 
         while !self.is_finished() {
-            if let Some(function_definition) = self.parse_function_definition() {
+            let begin = self.current_token_source_range();
+            let is_extern = self.expect(TokenKind::KeywordExtern).is_some();
+            let Some(signature) = self.parse_function_signature() else {
+                continue;
+            };
+
+            // A declaration ends with a semicolon and a definition has a body, with or without
+            // 'extern'. Without either an 'extern' function is more likely meant as a declaration.
+            let is_declaration = match self.peek_next().map(|token| &token.kind) {
+                Some(TokenKind::Semicolon) => true,
+                Some(TokenKind::LeftBrace) => false,
+                _ => is_extern,
+            };
+
+            if is_declaration {
+                translation_unit
+                    .declarations
+                    .push(self.parse_function_declaration(begin, is_extern, signature));
+            } else if let Some(function_definition) =
+                self.parse_function_definition(begin, is_extern, signature)
+            {
                 translation_unit.function.push(function_definition);
             }
         }
 
-        translation_unit
+        ParseResult {
+            translation_unit,
+            had_errors: self.diagnostic_engine.borrow().number_of_errors() > number_of_errors,
+        }
     }
 
-    fn parse_function_definition(&self) -> Option<FunctionDefinition> {
-        // First parse the function return type.
-        // TODO: For now we only support 'int' return type.
+    /// Finishes a function declaration like `int f(void);` starting at `begin` after its signature
+    fn parse_function_declaration(
+        &self,
+        begin: SourceRange<'a>,
+        is_extern: bool,
+        (return_type, name, has_prototype): (Type, String, bool),
+    ) -> FunctionDeclaration<'a> {
+        // Require a semicolon instead of a body
+        let range = match self.expect(TokenKind::Semicolon) {
+            Some(semicolon_token) => begin.merge(semicolon_token.range),
+            None => {
+                self.diagnostic(
+                    DiagnosticId::ExpectedSemicolon,
+                    self.current_token_source_range(),
+                    "expected ';'",
+                );
+                begin
+            }
+        };
+
+        FunctionDeclaration {
+            name,
+            return_type,
+            is_extern,
+            has_prototype,
+            range,
+        }
+    }
+
+    /// Finishes a function definition starting at `begin` by parsing the body after its signature
+    fn parse_function_definition(
+        &self,
+        begin: SourceRange<'a>,
+        is_extern: bool,
+        (return_type, name, has_prototype): (Type, String, bool),
+    ) -> Option<FunctionDefinition<'a>> {
+        // Parse the function body
+        let body = self.parse_compound_statement()?;
+
+        Some(FunctionDefinition {
+            name,
+            return_type,
+            has_prototype,
+            is_extern,
+            range: begin.merge(body.range),
+            body,
+        })
+    }
+
+    /// Parses an optional `auto` or `register` storage-class specifier, without one a local variable
+    /// is `auto`
+    fn parse_storage_class(&self) -> StorageClass {
+        if let Some(register_token) = self.expect(TokenKind::KeywordRegister) {
+            self.diagnostic(
+                DiagnosticId::DeprecatedRegister,
+                register_token.range,
+                "'register' storage class specifier is deprecated",
+            );
+            return StorageClass::Register;
+        }
+
+        self.expect(TokenKind::KeywordAuto);
+
+        StorageClass::Auto
+    }
+
+    /// Parses any number of `volatile` and `restrict` qualifiers, repeating a qualifier has no effect
+    fn parse_type_qualifiers(&self) -> Qualifiers {
+        let mut qualifiers = Qualifiers::default();
+
+        loop {
+            if self.expect(TokenKind::KeywordVolatile).is_some() {
+                qualifiers.is_volatile = true;
+            } else if let Some(restrict_token) = self.expect(TokenKind::KeywordRestrict) {
+                // TODO: Allow 'restrict' once there are pointer types
+                self.diagnostic(
+                    DiagnosticId::RestrictRequiresPointer,
+                    restrict_token.range,
+                    "restrict requires a pointer type",
+                );
+                qualifiers.is_restrict = true;
+            } else {
+                return qualifiers;
+            }
+        }
+    }
+
+    /// Parses the type of a function or variable, falling back to `int` if there is none.
+    // TODO: For now we only support 'int' and '_Bool'.
+    fn parse_type_specifier(&self) -> Type {
+        if self.expect(TokenKind::KeywordBool).is_some() {
+            return Type::Bool;
+        }
+
         if self.expect(TokenKind::KeywordInt).is_none() {
             self.diagnostic(
                 DiagnosticId::ExpectedFunctionReturnType,
@@ -100,6 +333,19 @@ fn parse_function_definition(&self) -> Option<FunctionDefinition> {
             );
         }
 
+        Type::Int
+    }
+
+    /// Parses the return type, name and parameter list of a function. Returns the return type, the
+    /// name and whether the function has a prototype.
+    fn parse_function_signature(&self) -> Option<(Type, String, bool)> {
+        // Qualifiers of the return type have no effect, C drops them from the function type
+        self.parse_type_qualifiers();
+
+        // First parse the function return type.
+        let return_type = self.parse_type_specifier();
+        self.parse_type_qualifiers();
+
         // Parse the function name
         let Some(name_token) = self.consume_next() else {
             self.diagnostic(
@@ -115,7 +361,8 @@ fn parse_function_definition(&self) -> Option<FunctionDefinition> {
             .source_text()
             .map(|text| text.to_string())
             .unwrap_or_default();
-        if !name_token.is_identifier() || name.is_empty() {
+        let is_valid_name = name_token.is_identifier() && !name.is_empty();
+        if !is_valid_name {
             self.diagnostic(
                 DiagnosticId::ExpectedFunctionName,
                 self.current_token_source_range(),
@@ -133,8 +380,20 @@ fn parse_function_definition(&self) -> Option<FunctionDefinition> {
         }
 
         // TODO: Now we would parse the function parameters, but for now just skip them
-        // We currently require a void parameter
-        if self.expect(TokenKind::KeywordVoid).is_none() {
+        // We currently require a void parameter or an empty parameter list
+        let mut has_prototype = true;
+        if let Some(right_parenthesis) = self
+            .peek_next()
+            .filter(|token| token.kind == TokenKind::RightParenthesis)
+        {
+            has_prototype = false;
+
+            self.diagnostic(
+                DiagnosticId::StrictPrototypes,
+                right_parenthesis.range,
+                "a function declaration without a prototype is deprecated in all versions of C",
+            );
+        } else if self.expect(TokenKind::KeywordVoid).is_none() {
             self.diagnostic(
                 DiagnosticId::ExpectedVoidInParameterList,
                 self.current_token_source_range(),
@@ -151,46 +410,240 @@ fn parse_function_definition(&self) -> Option<FunctionDefinition> {
             );
         }
 
-        // Require an open brace
+        // The function can be called from now on, including recursively from its own body
+        if is_valid_name {
+            self.declare_function(&name, has_prototype);
+        }
+
+        Some((return_type, name, has_prototype))
+    }
+
+    /// Parses exactly one statement, failing if any tokens are left over afterwards.
+    ///
+    /// This is mainly useful for testing the statement grammar without wrapping it in a function.
+    pub fn parse_single_statement(&mut self) -> Option<Statement<'a>> {
+        let statement = self.parse_statement()?;
+
+        if !self.is_finished() {
+            return None;
+        }
+
+        Some(statement)
+    }
+
+    fn parse_statement(&self) -> Option<Statement<'a>> {
+        match self.peek_next().map(|token| &token.kind) {
+            Some(TokenKind::LeftBrace) => self.parse_compound_statement(),
+            Some(TokenKind::KeywordReturn) => self.parse_return_statement(),
+            Some(TokenKind::KeywordWhile) => self.parse_while_statement(),
+            Some(TokenKind::Semicolon) => self.parse_empty_statement(),
+            Some(
+                TokenKind::KeywordInt
+                | TokenKind::KeywordBool
+                | TokenKind::KeywordVolatile
+                | TokenKind::KeywordRestrict
+                | TokenKind::KeywordAuto
+                | TokenKind::KeywordRegister,
+            ) => self.parse_declaration(),
+            _ => self.parse_expression_statement(),
+        }
+    }
+
+    /// Parses a block of zero or more statements enclosed in braces
+    fn parse_compound_statement(&self) -> Option<Statement<'a>> {
+        let begin = self.current_token_source_range().begin;
+
+        // Require an open brace, without it there is no telling where the block would end
         if self.expect(TokenKind::LeftBrace).is_none() {
             self.diagnostic(
                 DiagnosticId::ExpectedLeftBrace,
                 self.current_token_source_range(),
                 "expected '{'",
             );
+            return None;
         }
 
-        // Parse the function body
-        let body = self.parse_statement()?;
+        // Parse statements until the closing brace, the variables declared by them are only visible
+        // inside of the block
+        self.scopes.borrow_mut().push(Vec::new());
+        let statements = self.parse_statements_until_right_brace();
+        self.scopes.borrow_mut().pop();
+        let statements = statements?;
 
         // Require a closing brace
-        if self.expect(TokenKind::RightBrace).is_none() {
+        let end = match self.expect(TokenKind::RightBrace) {
+            Some(right_brace) => right_brace.range.end,
+            None => {
+                self.diagnostic(
+                    DiagnosticId::ExpectedRightBrace,
+                    self.current_token_source_range(),
+                    "expected '}'",
+                );
+
+                statements
+                    .last()
+                    .map_or(begin, |statement: &Statement| statement.range.end)
+            }
+        };
+
+        Some(Statement::new_compound(
+            statements,
+            SourceRange { begin, end },
+        ))
+    }
+
+    fn parse_statements_until_right_brace(&self) -> Option<Vec<Statement<'a>>> {
+        let mut statements = Vec::new();
+        while !self.is_finished()
+            && self
+                .peek_next()
+                .is_some_and(|token| token.kind != TokenKind::RightBrace)
+        {
+            statements.push(self.parse_statement()?);
+        }
+
+        Some(statements)
+    }
+
+    /// Parses a local variable declaration like `int x = 3;` and declares the variable in the
+    /// innermost block
+    fn parse_declaration(&self) -> Option<Statement<'a>> {
+        let begin = self.current_token_source_range();
+
+        // Parse the storage class and the type
+        let storage_class = self.parse_storage_class();
+        let mut qualifiers = self.parse_type_qualifiers();
+        let value_type = self.parse_type_specifier();
+        let trailing_qualifiers = self.parse_type_qualifiers();
+        qualifiers.is_volatile |= trailing_qualifiers.is_volatile;
+        qualifiers.is_restrict |= trailing_qualifiers.is_restrict;
+
+        // Parse the variable name
+        let Some(name_token) = self.expect_identifier() else {
             self.diagnostic(
-                DiagnosticId::ExpectedRightBrace,
+                DiagnosticId::ExpectedIdentifier,
                 self.current_token_source_range(),
-                "expected '}'",
+                "expected identifier",
             );
-        }
+            return None;
+        };
+        let name = name_token.range.source_text().unwrap_or_default();
+
+        // The variable is already visible in its own initializer
+        self.declare_variable(name, name_token.range);
+
+        // Parse the optional initializer
+        let initializer = if self.expect(TokenKind::Equal).is_some() {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
 
-        Some(FunctionDefinition { name, body })
+        // Require a semicolon
+        let Some(semicolon_token) = self.expect(TokenKind::Semicolon) else {
+            self.diagnostic(
+                DiagnosticId::ExpectedSemicolon,
+                self.current_token_source_range(),
+                "expected ';'",
+            );
+            return None;
+        };
+
+        Some(Statement::new(
+            StatementKind::Declaration {
+                name: name.to_string(),
+                value_type,
+                qualifiers,
+                storage_class,
+                initializer,
+            },
+            begin.merge(semicolon_token.range),
+        ))
     }
 
-    fn parse_statement(&self) -> Option<Statement> {
-        // TODO: Statement can be all sorts of things, for now we only allow the return statement
-        self.parse_return_statement()
+    /// Parses an expression followed by a semicolon like `x = 3;`
+    fn parse_expression_statement(&self) -> Option<Statement<'a>> {
+        let expression = self.parse_expression()?;
+
+        // Require a semicolon
+        let Some(semicolon_token) = self.expect(TokenKind::Semicolon) else {
+            self.diagnostic(
+                DiagnosticId::ExpectedSemicolon,
+                self.current_token_source_range(),
+                "expected ';'",
+            );
+            return None;
+        };
+
+        let range = expression.range.merge(semicolon_token.range);
+        Some(Statement::new_expression(expression, range))
     }
 
-    fn parse_return_statement(&self) -> Option<Statement> {
-        // Require the 'return' keyword
-        let Some(return_token) = self.expect(TokenKind::KeywordReturn) else {
+    fn parse_empty_statement(&self) -> Option<Statement<'a>> {
+        let semicolon_token = self.expect(TokenKind::Semicolon)?;
+
+        Some(Statement::new(StatementKind::Empty, semicolon_token.range))
+    }
+
+    fn parse_while_statement(&self) -> Option<Statement<'a>> {
+        let while_token = self.expect(TokenKind::KeywordWhile)?;
+
+        // Parse the parenthesized condition
+        if self.expect(TokenKind::LeftParenthesis).is_none() {
+            self.diagnostic(
+                DiagnosticId::ExpectedLeftParenthesis,
+                self.current_token_source_range(),
+                "expected '('",
+            );
+            return None;
+        }
+
+        let Some(condition) = self.parse_expression() else {
             self.diagnostic(
-                DiagnosticId::ExpectedReturnKeyword,
+                DiagnosticId::ExpectedExpression,
                 self.current_token_source_range(),
-                "expected 'return' keyword",
+                "expected expression",
             );
             return None;
         };
 
+        if self.expect(TokenKind::RightParenthesis).is_none() {
+            self.diagnostic(
+                DiagnosticId::ExpectedRightParenthesis,
+                self.current_token_source_range(),
+                "expected ')'",
+            );
+            return None;
+        }
+
+        // Parse the body, which is a block of its own even without braces
+        self.scopes.borrow_mut().push(Vec::new());
+        let body = self.parse_statement();
+        self.scopes.borrow_mut().pop();
+        let body = body?;
+
+        // A declaration isn't a statement, so it can't be the body
+        if matches!(body.kind, StatementKind::Declaration { .. }) {
+            self.diagnostic(
+                DiagnosticId::DeclarationAsLoopBody,
+                body.range,
+                "a declaration can't be the body of a while loop",
+            );
+        }
+
+        let range = while_token.range.merge(body.range);
+        Some(Statement::new(
+            StatementKind::While {
+                condition,
+                body: Box::new(body),
+            },
+            range,
+        ))
+    }
+
+    fn parse_return_statement(&self) -> Option<Statement<'a>> {
+        let return_token = self.expect(TokenKind::KeywordReturn)?;
+
         // Parse the expression
         let Some(expression) = self.parse_expression() else {
             self.diagnostic(
@@ -213,16 +666,91 @@ fn parse_return_statement(&self) -> Option<Statement> {
 
         Some(Statement::new_return(
             expression,
-            SourceRange {
-                begin: return_token.range.begin,
-                end: semicolon_token.range.end,
-            },
+            return_token.range.merge(semicolon_token.range),
         ))
     }
 
     // -- Expressions --
 
-    fn parse_expression(&self) -> Option<Expression> {
+    fn parse_expression(&self) -> Option<Expression<'a>> {
+        self.parse_assignment_expression()
+    }
+
+    /// Parses an assignment like `x = y = 3`, which binds weaker than all binary operators and is
+    /// right associative
+    fn parse_assignment_expression(&self) -> Option<Expression<'a>> {
+        let target = self.parse_binary_expression(0)?;
+
+        // TODO: Compound assignments like `+=` need to combine the value with the target
+        if self
+            .peek_next()
+            .is_none_or(|token| !token.kind.is_assignment_operator())
+        {
+            return Some(target);
+        }
+        self.consume();
+
+        let value = self.parse_assignment_expression()?;
+
+        // Only variables can be assigned to, parentheses around them don't matter
+        let mut variable = &target;
+        while let ExpressionKind::Parenthesis(expression) = &variable.kind {
+            variable = expression;
+        }
+        let ExpressionKind::Variable(name) = &variable.kind else {
+            self.diagnostic(
+                DiagnosticId::ExpressionNotAssignable,
+                target.range,
+                "expression is not assignable",
+            );
+            // The error doesn't affect the surrounding syntax, so parsing can go on
+            return Some(target);
+        };
+
+        let range = target.range.merge(value.range);
+        Some(Expression {
+            kind: ExpressionKind::Assignment {
+                name: name.clone(),
+                value: Box::new(value),
+            },
+            range,
+        })
+    }
+
+    /// Parses a chain of binary operations using precedence climbing, only operators with at least
+    /// `min_precedence` are consumed so tighter binding operators end up deeper in the tree
+    fn parse_binary_expression(&self, min_precedence: u8) -> Option<Expression<'a>> {
+        let mut left = self.parse_operand()?;
+
+        while let Some((operator, precedence)) = self
+            .peek_next()
+            .and_then(|token| binary_operator(&token.kind))
+        {
+            if precedence < min_precedence {
+                break;
+            }
+            self.consume();
+
+            // All binary operators are left associative, so the right hand side only takes
+            // operators which bind tighter
+            let right = self.parse_binary_expression(precedence + 1)?;
+            let range = left.range.merge(right.range);
+
+            left = Expression {
+                kind: ExpressionKind::BinaryOperation {
+                    operator,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                range,
+            };
+        }
+
+        Some(left)
+    }
+
+    /// Parses the operand of a binary operation, which is a unary or primary expression
+    fn parse_operand(&self) -> Option<Expression<'a>> {
         let Some(token) = self.peek_next() else {
             self.diagnostic(
                 DiagnosticId::ExpectedExpression,
@@ -233,9 +761,20 @@ fn parse_expression(&self) -> Option<Expression> {
         };
 
         match token.kind {
-            TokenKind::IntegerLiteral(_) => self.parse_integer_literal(),
+            TokenKind::IntegerLiteral(..) | TokenKind::CharacterLiteral(_) => {
+                self.parse_integer_literal()
+            }
+            TokenKind::StringLiteral(_) => self.parse_string_literal(),
             TokenKind::Minus | TokenKind::Tilde => self.parse_unary_expression(),
             TokenKind::LeftParenthesis => self.parse_parenthesis_expression(),
+            TokenKind::Identifier(_)
+                if self
+                    .peek_after_next()
+                    .is_some_and(|token| token.kind == TokenKind::LeftParenthesis) =>
+            {
+                self.parse_call()
+            }
+            TokenKind::Identifier(_) => self.parse_variable(),
             _ => {
                 self.diagnostic(
                     DiagnosticId::ExpectedExpression,
@@ -247,11 +786,12 @@ fn parse_expression(&self) -> Option<Expression> {
         }
     }
 
-    fn parse_integer_literal(&self) -> Option<Expression> {
+    fn parse_integer_literal(&self) -> Option<Expression<'a>> {
         let token = self.consume_next()?;
 
         let value = match token.kind {
-            TokenKind::IntegerLiteral(value) => value,
+            // A character constant is just an integer with the value of its character
+            TokenKind::IntegerLiteral(value, _) | TokenKind::CharacterLiteral(value) => value,
             _ => {
                 self.diagnostic(
                     DiagnosticId::ExpectedIntegerLiteral,
@@ -268,7 +808,188 @@ fn parse_integer_literal(&self) -> Option<Expression> {
         })
     }
 
-    fn parse_unary_expression(&self) -> Option<Expression> {
+    /// Parses one or more adjacent string literals, which C concatenates into a single literal
+    fn parse_string_literal(&self) -> Option<Expression<'a>> {
+        let mut value = Vec::new();
+        let mut range = self.current_token_source_range();
+
+        while let Some(token) = self.peek_next() {
+            let TokenKind::StringLiteral(part) = &token.kind else {
+                break;
+            };
+
+            value.extend_from_slice(part);
+            range = range.merge(token.range);
+            self.consume();
+        }
+
+        // TODO: Allow string literals once there are array and pointer types
+        self.diagnostic(
+            DiagnosticId::StringLiteralNotSupported,
+            range,
+            "string literals are not supported yet",
+        );
+
+        Some(Expression {
+            kind: ExpressionKind::StringLiteral(value),
+            range,
+        })
+    }
+
+    /// Parses the use of a variable, which must have been declared in an enclosing block
+    fn parse_variable(&self) -> Option<Expression<'a>> {
+        let token = self.consume_next()?;
+        let name = token.range.source_text().unwrap_or_default();
+
+        if !self.is_variable_declared(name) {
+            self.diagnostic(
+                DiagnosticId::UndeclaredIdentifier,
+                token.range,
+                format!("use of undeclared identifier '{}'", name),
+            );
+        }
+
+        Some(Expression {
+            kind: ExpressionKind::Variable(name.to_string()),
+            range: token.range,
+        })
+    }
+
+    /// Parses a function call like `f(1, 2)`, the function must have been declared before
+    fn parse_call(&self) -> Option<Expression<'a>> {
+        let name_token = self.consume_next()?;
+        let name = name_token.range.source_text().unwrap_or_default();
+
+        // Skip the '('
+        self.consume();
+
+        // Parse the comma separated arguments. After an error the rest of the arguments is skipped,
+        // so a single mistake doesn't derail the whole statement.
+        let mut arguments = Vec::new();
+        let mut right_parenthesis = self.expect(TokenKind::RightParenthesis);
+        while right_parenthesis.is_none() {
+            let Some(argument) = self.parse_expression() else {
+                right_parenthesis = self.skip_to_right_parenthesis();
+                break;
+            };
+            arguments.push(argument);
+
+            if let Some(token) = self.expect(TokenKind::RightParenthesis) {
+                right_parenthesis = Some(token);
+            } else if let Some(comma_token) = self.expect(TokenKind::Comma) {
+                // Unlike an initializer list the arguments may not end with a comma
+                right_parenthesis = self.expect(TokenKind::RightParenthesis);
+                if right_parenthesis.is_some() {
+                    self.diagnostic(
+                        DiagnosticId::TrailingComma,
+                        comma_token.range,
+                        "unexpected trailing comma in argument list",
+                    );
+                }
+            } else if self.peek_next().is_some_and(|token| {
+                !matches!(
+                    token.kind,
+                    TokenKind::Semicolon | TokenKind::LeftBrace | TokenKind::RightBrace
+                )
+            }) {
+                self.diagnostic(
+                    DiagnosticId::ExpectedRightParenthesis,
+                    self.current_token_source_range(),
+                    "expected ',' or ')'",
+                );
+                right_parenthesis = self.skip_to_right_parenthesis();
+                break;
+            } else {
+                break;
+            }
+        }
+
+        // Without the closing parenthesis the call ends at the last token which belongs to it
+        let Some(right_parenthesis) = right_parenthesis else {
+            self.diagnostic(
+                DiagnosticId::ExpectedRightParenthesis,
+                self.current_token_source_range(),
+                "expected ')'",
+            );
+            return Some(Expression {
+                kind: ExpressionKind::Call {
+                    name: name.to_string(),
+                    arguments,
+                },
+                range: name_token.range.merge(self.previous_token_source_range()),
+            });
+        };
+
+        let arguments_range = arguments
+            .first()
+            .zip(arguments.last())
+            .map(|(first, last)| first.range.merge(last.range));
+        match (self.function_has_prototype(name), arguments_range) {
+            (None, _) => {
+                self.diagnostic(
+                    DiagnosticId::UndeclaredFunction,
+                    name_token.range,
+                    format!("call to undeclared function '{name}'"),
+                );
+            }
+            // TODO: Functions can't have parameters yet
+            (Some(true), Some(arguments_range)) => {
+                self.diagnostic(
+                    DiagnosticId::TooManyArguments,
+                    arguments_range,
+                    format!(
+                        "too many arguments to function call, expected 0, have {}",
+                        arguments.len()
+                    ),
+                );
+            }
+            // Any arguments are allowed without a prototype, but calls can't pass them yet
+            // TODO: Pass the arguments once functions can have parameters
+            (Some(false), Some(arguments_range)) => {
+                self.diagnostic(
+                    DiagnosticId::CallWithoutPrototypeNotSupported,
+                    arguments_range,
+                    format!(
+                        "passing arguments to function '{name}' without a prototype is not supported yet"
+                    ),
+                );
+            }
+            (Some(_), None) => {}
+        }
+
+        Some(Expression {
+            kind: ExpressionKind::Call {
+                name: name.to_string(),
+                arguments,
+            },
+            range: name_token.range.merge(right_parenthesis.range),
+        })
+    }
+
+    /// Skips everything up to and including the ')' closing the current parenthesis, but not past
+    /// the end of the statement. Returns the closing parenthesis if there is one.
+    fn skip_to_right_parenthesis(&self) -> Option<&Token<'a>> {
+        let mut depth = 0_usize;
+
+        while let Some(token) = self.peek_next() {
+            match token.kind {
+                TokenKind::Semicolon | TokenKind::LeftBrace | TokenKind::RightBrace => return None,
+                TokenKind::LeftParenthesis => depth += 1,
+                TokenKind::RightParenthesis if depth == 0 => {
+                    self.consume();
+                    return Some(token);
+                }
+                TokenKind::RightParenthesis => depth -= 1,
+                _ => {}
+            }
+
+            self.consume();
+        }
+
+        None
+    }
+
+    fn parse_unary_expression(&self) -> Option<Expression<'a>> {
         let operator_token = self.consume_next()?;
 
         let operator = match operator_token.kind {
@@ -279,11 +1000,8 @@ fn parse_unary_expression(&self) -> Option<Expression> {
             }
         };
 
-        let expression = self.parse_expression()?;
-        let range = SourceRange {
-            begin: operator_token.range.begin,
-            end: expression.range.end,
-        };
+        let expression = self.parse_operand()?;
+        let range = operator_token.range.merge(expression.range);
 
         Some(Expression {
             kind: ExpressionKind::UnaryOperation {
@@ -294,7 +1012,7 @@ fn parse_unary_expression(&self) -> Option<Expression> {
         })
     }
 
-    fn parse_parenthesis_expression(&self) -> Option<Expression> {
+    fn parse_parenthesis_expression(&self) -> Option<Expression<'a>> {
         // Opening parenthesis
         let opnening_parenthesis_token = self.expect(TokenKind::LeftParenthesis)?;
 
@@ -310,12 +1028,9 @@ fn parse_parenthesis_expression(&self) -> Option<Expression> {
             );
         };
 
-        let range = SourceRange {
-            begin: opnening_parenthesis_token.range.begin,
-            end: closing_paren_token
-                .map(|token| token.range.end)
-                .unwrap_or(expression.range.end),
-        };
+        let range = opnening_parenthesis_token
+            .range
+            .merge(closing_paren_token.map_or(expression.range, |token| token.range));
 
         Some(Expression {
             kind: ExpressionKind::Parenthesis(Box::new(expression)),
@@ -323,3 +1038,523 @@ fn parse_parenthesis_expression(&self) -> Option<Expression> {
         })
     }
 }
+
+/// Returns the binary operator for the token and its precedence, higher binds tighter
+fn binary_operator(token_kind: &TokenKind) -> Option<(BinaryOperator, u8)> {
+    match token_kind {
+        TokenKind::PipePipe => Some((BinaryOperator::LogicalOr, 1)),
+        TokenKind::AmpersandAmpersand => Some((BinaryOperator::LogicalAnd, 2)),
+        TokenKind::EqualEqual => Some((BinaryOperator::Equal, 3)),
+        TokenKind::ExclamationEqual => Some((BinaryOperator::NotEqual, 3)),
+        TokenKind::Less => Some((BinaryOperator::Less, 4)),
+        TokenKind::Greater => Some((BinaryOperator::Greater, 4)),
+        TokenKind::LessEqual => Some((BinaryOperator::LessEqual, 4)),
+        TokenKind::GreaterEqual => Some((BinaryOperator::GreaterEqual, 4)),
+        TokenKind::Plus => Some((BinaryOperator::Add, 5)),
+        TokenKind::Minus => Some((BinaryOperator::Subtract, 5)),
+        TokenKind::Star => Some((BinaryOperator::Multiply, 6)),
+        TokenKind::Slash => Some((BinaryOperator::Divide, 6)),
+        TokenKind::Percent => Some((BinaryOperator::Remainder, 6)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic_consumer::{CollectingDiagnosticConsumer, IgnoreDiagnosticConsumer};
+
+    fn diagnostic_engine() -> Rc<RefCell<DiagnosticEngine>> {
+        Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            IgnoreDiagnosticConsumer,
+        ))))
+    }
+
+    fn parse_source(source_file: &SourceFile) -> ParseResult<'_> {
+        let diagnostic_engine = diagnostic_engine();
+        let tokens = Lexer::new(diagnostic_engine.clone(), source_file).tokenize();
+
+        Parser::new(diagnostic_engine, tokens).parse()
+    }
+
+    /// Parses `source` and returns the ids of all reported diagnostics
+    fn parse_diagnostic_ids(source: &str) -> Vec<DiagnosticId> {
+        let source_file = SourceFile::new("test.c", source);
+        let consumer = CollectingDiagnosticConsumer::default();
+        let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            consumer.clone(),
+        ))));
+        let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+        Parser::new(diagnostic_engine, tokens).parse();
+
+        consumer.ids()
+    }
+
+    #[test]
+    fn test_parse_without_errors() {
+        let source_file = SourceFile::new("test.c", "int main(void) { return 0; }");
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        assert_eq!(parse_result.translation_unit.function.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_keeps_partial_translation_unit() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) { return 0; }\nint broken(void) { return ; }\n",
+        );
+        let parse_result = parse_source(&source_file);
+
+        assert!(parse_result.had_errors);
+        assert_eq!(parse_result.translation_unit.function.len(), 1);
+        assert_eq!(parse_result.translation_unit.function[0].name, "main");
+    }
+
+    #[test]
+    fn test_parse_multiple_statements() {
+        let source_file =
+            SourceFile::new("test.c", "int main(void) {\n  return 1;\n  return 2;\n}\n");
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        assert_eq!(
+            parse_result.translation_unit.dump(),
+            "TranslationUnit\n  \
+             FunctionDefinition \"main\" 1:1-4:1\n    \
+             CompoundStatement 1:16-4:1\n      \
+             ReturnStatement 2:3-2:11\n        \
+             IntegerLiteral (1) 2:10\n      \
+             ReturnStatement 3:3-3:11\n        \
+             IntegerLiteral (2) 3:10\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_function_definition_range() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) { return 0; }\n\nvolatile _Bool\nis_set(void) {\n  return 1;\n}\n",
+        );
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        let functions = &parse_result.translation_unit.function;
+        assert_eq!(
+            functions[0].range.source_text(),
+            Some("int main(void) { return 0; }")
+        );
+        assert!(
+            parse_result
+                .translation_unit
+                .dump()
+                .contains("  FunctionDefinition \"is_set\" _Bool 3:1-6:1\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_local_variables() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) { int x = 1; volatile _Bool b; b = (x) = 2; return x; }",
+        );
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        let StatementKind::Compound(statements) =
+            &parse_result.translation_unit.function[0].body.kind
+        else {
+            panic!("expected a compound statement");
+        };
+        assert_eq!(statements[0].range.source_text(), Some("int x = 1;"));
+        assert!(matches!(
+            &statements[1].kind,
+            StatementKind::Declaration {
+                value_type: Type::Bool,
+                qualifiers: Qualifiers {
+                    is_volatile: true,
+                    ..
+                },
+                initializer: None,
+                ..
+            }
+        ));
+        assert_eq!(statements[2].range.source_text(), Some("b = (x) = 2;"));
+    }
+
+    #[test]
+    fn test_parse_while_statement() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) { int x = 2; while (x) { x = x - 1; } while (0); return x; }",
+        );
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        let StatementKind::Compound(statements) =
+            &parse_result.translation_unit.function[0].body.kind
+        else {
+            panic!("expected a compound statement");
+        };
+        assert_eq!(
+            statements[1].range.source_text(),
+            Some("while (x) { x = x - 1; }")
+        );
+        let StatementKind::While { body, .. } = &statements[2].kind else {
+            panic!("expected a while statement");
+        };
+        assert_eq!(body.kind, StatementKind::Empty);
+
+        // The body can't be a declaration
+        let source_file = SourceFile::new("test.c", "int main(void) { while (0) int x = 2; }");
+        assert!(parse_source(&source_file).had_errors);
+    }
+
+    #[test]
+    fn test_parse_call() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int f(void) { return f(); }\nint main(void) { return f() + 1; }",
+        );
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        assert!(
+            parse_result
+                .translation_unit
+                .dump()
+                .contains("        Call \"f\" 1:22-1:24\n")
+        );
+
+        for source in [
+            "int main(void) { return f(); }\nint f(void) { return 0; }",
+            "int f(void) { return 0; }\nint main(void) { return f(1, 2); }",
+            "int f(void) { return 0; }\nint main(void) { return f(1; }",
+        ] {
+            let source_file = SourceFile::new("test.c", source);
+
+            assert!(parse_source(&source_file).had_errors, "{source}");
+        }
+    }
+
+    #[test]
+    fn test_parse_adjacent_string_literals() {
+        let source_file = SourceFile::new("test.c", "int main(void) { return \"a\" \"b\"; }");
+        let parse_result = parse_source(&source_file);
+
+        let StatementKind::Compound(statements) =
+            &parse_result.translation_unit.function[0].body.kind
+        else {
+            panic!("expected a compound statement");
+        };
+        let StatementKind::Return(expression) = &statements[0].kind else {
+            panic!("expected a return statement");
+        };
+        assert_eq!(
+            expression.kind,
+            ExpressionKind::StringLiteral(b"ab".to_vec())
+        );
+        assert_eq!(expression.range.source_text(), Some("\"a\" \"b\""));
+    }
+
+    #[test]
+    fn test_parse_function_prototype() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int helper(void);\nint main(void) { return helper(); }\nint helper(void) { return 2; }",
+        );
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        let declarations = &parse_result.translation_unit.declarations;
+        assert_eq!(declarations.len(), 1);
+        assert_eq!(declarations[0].name, "helper");
+        assert!(!declarations[0].is_extern);
+        assert_eq!(
+            declarations[0].range.source_text(),
+            Some("int helper(void);")
+        );
+        assert_eq!(parse_result.translation_unit.function.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_extern_function_definition() {
+        let source_file = SourceFile::new("test.c", "extern int f(void) { return 0; }");
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        assert!(parse_result.translation_unit.declarations.is_empty());
+        let functions = &parse_result.translation_unit.function;
+        assert_eq!(functions.len(), 1);
+        assert!(functions[0].is_extern);
+        assert_eq!(
+            functions[0].range.source_text(),
+            Some("extern int f(void) { return 0; }")
+        );
+    }
+
+    #[test]
+    fn test_parse_call_argument_errors() {
+        let declaration = "int f(void) { return 0; }\n";
+
+        // An empty argument list is fine
+        assert_eq!(
+            parse_diagnostic_ids(&format!("{declaration}int main(void) {{ return f(); }}")),
+            []
+        );
+
+        // Each mistake is reported once and parsing continues after the call
+        for (arguments, ids) in [
+            (
+                "1,",
+                [DiagnosticId::TrailingComma, DiagnosticId::TooManyArguments].as_slice(),
+            ),
+            (
+                "1 2",
+                &[
+                    DiagnosticId::ExpectedRightParenthesis,
+                    DiagnosticId::TooManyArguments,
+                ],
+            ),
+            ("1 + , 2", &[DiagnosticId::ExpectedExpression]),
+        ] {
+            let source = format!("{declaration}int main(void) {{ return f({arguments}) + 1; }}");
+
+            assert_eq!(parse_diagnostic_ids(&source), ids, "{source}");
+        }
+
+        // Without a prototype any arguments are valid C, but not supported yet
+        assert_eq!(
+            parse_diagnostic_ids("int f();\nint main(void) { return f(1); }"),
+            [DiagnosticId::CallWithoutPrototypeNotSupported]
+        );
+
+        // A later declaration without a prototype keeps the earlier prototype
+        assert_eq!(
+            parse_diagnostic_ids("int f(void);\nint f();\nint main(void) { return f(1); }"),
+            [DiagnosticId::TooManyArguments]
+        );
+
+        // A missing ')' ends the call at the end of the statement
+        assert_eq!(
+            parse_diagnostic_ids(&format!("{declaration}int main(void) {{ return f(1; }}")),
+            [DiagnosticId::ExpectedRightParenthesis]
+        );
+    }
+
+    #[test]
+    fn test_parse_storage_classes() {
+        let source_file = SourceFile::new(
+            "test.c",
+            "int main(void) { register int x = 1; auto int y = 2; int z = 3; return x; }",
+        );
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        let StatementKind::Compound(statements) =
+            &parse_result.translation_unit.function[0].body.kind
+        else {
+            panic!("expected a compound statement");
+        };
+        let storage_classes = statements
+            .iter()
+            .filter_map(|statement| match statement.kind {
+                StatementKind::Declaration { storage_class, .. } => Some(storage_class),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            storage_classes,
+            [
+                StorageClass::Register,
+                StorageClass::Auto,
+                StorageClass::Auto
+            ]
+        );
+        assert_eq!(
+            statements[0].range.source_text(),
+            Some("register int x = 1;")
+        );
+    }
+
+    #[test]
+    fn test_parse_variable_errors() {
+        for source in [
+            "int main(void) { return x; }",
+            "int main(void) { { int x; } return x; }",
+            "int main(void) { int x; int x; return 0; }",
+            "int main(void) { int x; x + 1 = 2; return 0; }",
+            "int main(void) { int; return 0; }",
+        ] {
+            let source_file = SourceFile::new("test.c", source);
+
+            assert!(parse_source(&source_file).had_errors, "{source}");
+        }
+
+        // A nested block may shadow a variable
+        let source_file =
+            SourceFile::new("test.c", "int main(void) { int x; { int x; } return x; }");
+        assert!(!parse_source(&source_file).had_errors);
+    }
+
+    #[test]
+    fn test_parse_empty_function_body() {
+        let source_file = SourceFile::new("test.c", "int main(void) {}");
+        let parse_result = parse_source(&source_file);
+
+        assert!(!parse_result.had_errors);
+        assert_eq!(
+            parse_result.translation_unit.function[0].body.kind,
+            StatementKind::Compound(Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_parse_statement_from_source_return() {
+        let source_file = SourceFile::new("test.c", "return 2;");
+        let statement = parse_statement_from_source(diagnostic_engine(), &source_file).unwrap();
+
+        let StatementKind::Return(expression) = &statement.kind else {
+            panic!("Expected return statement");
+        };
+        assert_eq!(expression.kind, ExpressionKind::IntegerLiteral(2));
+        assert_eq!(statement.range.source_text(), Some("return 2;"));
+    }
+
+    #[test]
+    fn test_parse_statement_from_source_return_unary() {
+        let source_file = SourceFile::new("test.c", "return -(1);");
+        let statement = parse_statement_from_source(diagnostic_engine(), &source_file).unwrap();
+
+        let StatementKind::Return(expression) = &statement.kind else {
+            panic!("Expected return statement");
+        };
+        let ExpressionKind::UnaryOperation {
+            operator,
+            expression,
+        } = &expression.kind
+        else {
+            panic!("Expected unary operation");
+        };
+
+        assert_eq!(*operator, UnaryOperator::Negate);
+        assert!(matches!(expression.kind, ExpressionKind::Parenthesis(_)));
+    }
+
+    fn dump_return_expression(source: &str) -> String {
+        let source_file = SourceFile::new("test.c", source);
+        let statement = parse_statement_from_source(diagnostic_engine(), &source_file).unwrap();
+
+        let StatementKind::Return(expression) = &statement.kind else {
+            panic!("Expected return statement");
+        };
+        expression.dump(0)
+    }
+
+    #[test]
+    fn test_parse_binary_precedence() {
+        assert_eq!(
+            dump_return_expression("return 1 + 2 * 3;"),
+            "BinaryOperation Add 1:8-1:16\n  \
+             IntegerLiteral (1) 1:8\n  \
+             BinaryOperation Multiply 1:12-1:16\n    \
+             IntegerLiteral (2) 1:12\n    \
+             IntegerLiteral (3) 1:16"
+        );
+    }
+
+    #[test]
+    fn test_parse_binary_left_associative() {
+        assert_eq!(
+            dump_return_expression("return 8 - 4 - 2;"),
+            "BinaryOperation Subtract 1:8-1:16\n  \
+             BinaryOperation Subtract 1:8-1:12\n    \
+             IntegerLiteral (8) 1:8\n    \
+             IntegerLiteral (4) 1:12\n  \
+             IntegerLiteral (2) 1:16"
+        );
+    }
+
+    #[test]
+    fn test_parse_binary_unary_binds_tighter() {
+        assert_eq!(
+            dump_return_expression("return -1 % (2 / 3);"),
+            "BinaryOperation Remainder 1:8-1:19\n  \
+             UnaryOperation Negate 1:8-1:9\n    \
+             IntegerLiteral (1) 1:9\n  \
+             Parenthesis 1:13-1:19\n    \
+             BinaryOperation Divide 1:14-1:18\n      \
+             IntegerLiteral (2) 1:14\n      \
+             IntegerLiteral (3) 1:18"
+        );
+    }
+
+    #[test]
+    fn test_parse_comparison() {
+        assert_eq!(
+            dump_return_expression("return 1 < 2;"),
+            "BinaryOperation Less 1:8-1:12\n  \
+             IntegerLiteral (1) 1:8\n  \
+             IntegerLiteral (2) 1:12"
+        );
+    }
+
+    #[test]
+    fn test_parse_comparison_precedence() {
+        // Relational operators bind tighter than equality ones but looser than arithmetic
+        assert_eq!(
+            dump_return_expression("return 1 == 2 + 3 >= 4;"),
+            "BinaryOperation Equal 1:8-1:22\n  \
+             IntegerLiteral (1) 1:8\n  \
+             BinaryOperation GreaterEqual 1:13-1:22\n    \
+             BinaryOperation Add 1:13-1:17\n      \
+             IntegerLiteral (2) 1:13\n      \
+             IntegerLiteral (3) 1:17\n    \
+             IntegerLiteral (4) 1:22"
+        );
+    }
+
+    #[test]
+    fn test_parse_logical_precedence() {
+        // `&&` binds tighter than `||` and both bind looser than comparisons
+        assert_eq!(
+            dump_return_expression("return 1 || 2 && 3 == 4;"),
+            "BinaryOperation LogicalOr 1:8-1:23\n  \
+             IntegerLiteral (1) 1:8\n  \
+             BinaryOperation LogicalAnd 1:13-1:23\n    \
+             IntegerLiteral (2) 1:13\n    \
+             BinaryOperation Equal 1:18-1:23\n      \
+             IntegerLiteral (3) 1:18\n      \
+             IntegerLiteral (4) 1:23"
+        );
+    }
+
+    #[test]
+    fn test_parse_binary_missing_right_operand() {
+        let source_file = SourceFile::new("test.c", "return 1 + ;");
+
+        assert!(parse_statement_from_source(diagnostic_engine(), &source_file).is_none());
+    }
+
+    #[test]
+    fn test_parse_statement_from_source_missing_semicolon() {
+        let source_file = SourceFile::new("test.c", "return 2");
+
+        assert!(parse_statement_from_source(diagnostic_engine(), &source_file).is_none());
+    }
+
+    #[test]
+    fn test_parse_statement_from_source_trailing_tokens() {
+        let source_file = SourceFile::new("test.c", "return 1; return 2;");
+
+        assert!(parse_statement_from_source(diagnostic_engine(), &source_file).is_none());
+    }
+
+    #[test]
+    fn test_parse_statement_from_source_empty() {
+        let source_file = SourceFile::new("test.c", "");
+
+        assert!(parse_statement_from_source(diagnostic_engine(), &source_file).is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use std::{iter, mem};
+
 /// Represents a source file with a path and its content.
 ///
 /// # Examples
@@ -35,6 +37,86 @@ pub fn new<P: Into<String>, C: Into<String>>(path: P, content: C) -> Self {
             content: content.into(),
         }
     }
+
+    /// Whether the file has no content at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// Returns the number of lines of the file. A trailing line terminator doesn't start another
+    /// line, so an empty file has no lines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustcc::source_file::SourceFile;
+    /// assert_eq!(SourceFile::new("test.c", "").line_count(), 0);
+    /// assert_eq!(SourceFile::new("test.c", "int x;\n").line_count(), 1);
+    /// assert_eq!(SourceFile::new("test.c", "int x;\n\n").line_count(), 2);
+    /// ```
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+
+    /// Returns the lines of the file without their terminators. Like the lexer this accepts `\n`,
+    /// `\r\n` and a lone `\r` as line terminators, unlike [`str::lines`] which keeps a lone `\r`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustcc::source_file::SourceFile;
+    /// let source_file = SourceFile::new("test.c", "a\r\nb\rc\n");
+    ///
+    /// assert_eq!(source_file.lines().collect::<Vec<_>>(), ["a", "b", "c"]);
+    /// ```
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        let mut rest = self.content.as_str();
+
+        iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+
+            let Some(terminator_index) = rest.find(['\n', '\r']) else {
+                return Some(mem::take(&mut rest));
+            };
+
+            let line = &rest[..terminator_index];
+            let terminator_length = if rest[terminator_index..].starts_with("\r\n") {
+                2
+            } else {
+                1
+            };
+            rest = &rest[terminator_index + terminator_length..];
+
+            Some(line)
+        })
+    }
+
+    /// Returns the content of the file with every line prefixed by its right-aligned line number
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustcc::source_file::SourceFile;
+    /// let source_file = SourceFile::new("test.c", "int main(void) {\n  return 0;\n}\n");
+    ///
+    /// assert_eq!(
+    ///     source_file.dump_numbered(),
+    ///     "1 | int main(void) {\n2 |   return 0;\n3 | }\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn dump_numbered(&self) -> String {
+        let width = self.line_count().to_string().len();
+
+        self.lines()
+            .enumerate()
+            .map(|(index, line)| format!("{:>width$} | {line}\n", index + 1))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -56,6 +138,61 @@ fn test_source_file_creation() {
         assert_eq!(source_file.content, content);
     }
 
+    #[test]
+    fn test_dump_numbered_aligns_line_numbers() {
+        let source_file = SourceFile::new("test.c", "x\n".repeat(10));
+        let dump = source_file.dump_numbered();
+
+        assert!(dump.starts_with(" 1 | x\n 2 | x\n"));
+        assert!(dump.ends_with(" 9 | x\n10 | x\n"));
+        assert_eq!(SourceFile::new("test.c", "").dump_numbered(), "");
+    }
+
+    #[test]
+    fn test_source_file_stdin_path() {
+        let source_file = SourceFile::new("<stdin>", "int main() { return 0; }");
+
+        assert_eq!(source_file.path, "<stdin>");
+    }
+
+    #[test]
+    fn test_source_file_empty() {
+        let source_file = SourceFile::new("test_path.c", "");
+
+        assert!(source_file.is_empty());
+        assert_eq!(source_file.line_count(), 0);
+    }
+
+    #[test]
+    fn test_source_file_single_line() {
+        let source_file = SourceFile::new("test_path.c", "int main() { return 0; }");
+
+        assert!(!source_file.is_empty());
+        assert_eq!(source_file.line_count(), 1);
+
+        // A trailing newline doesn't start another line
+        let source_file = SourceFile::new("test_path.c", "int main() { return 0; }\n");
+        assert_eq!(source_file.line_count(), 1);
+    }
+
+    #[test]
+    fn test_source_file_multiple_lines() {
+        let source_file = SourceFile::new("test_path.c", "int main() {\n    return 0;\n}");
+        assert_eq!(source_file.line_count(), 3);
+
+        let source_file = SourceFile::new("test_path.c", "int main() {\r\n    return 0;\r\n}\r\n");
+        assert_eq!(source_file.line_count(), 3);
+
+        // A lone carriage return ends a line as well
+        let source_file = SourceFile::new("test_path.c", "int main() {\r    return 0;\r}\r");
+        assert_eq!(source_file.line_count(), 3);
+
+        // Empty lines count, only the final newline doesn't
+        let source_file = SourceFile::new("test_path.c", "\n\nint x;\n\n");
+        assert!(!source_file.is_empty());
+        assert_eq!(source_file.line_count(), 4);
+    }
+
     #[test]
     fn test_source_file_equality() {
         let path = "test_path.c";
@@ -1,10 +1,17 @@
 use crate::{source_file::SourceFile, source_range::SourceRange};
-use std::fmt;
+use std::{cmp::Ordering, fmt};
 
-// TODO: Maybe custom implementations for PartialOrd and Ord since it makes no sense to compare SourceLocations with different source files
 /// A location in a source file, represented by a line and column number.
+///
+/// Locations are only ordered within the same source file, locations in different files are
+/// incomparable. Scratch locations all share index 0 and are ordered by line and column instead.
+///
+/// With the `serde` feature only the index, line and column are serialized. A deserialized location
+/// has no source file.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SourceLocation<'a> {
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub source_file: Option<&'a SourceFile>,
     pub index: usize,
     pub line: u32,
@@ -55,9 +62,8 @@ pub fn new(source_file: &'a SourceFile, index: usize, line: u32, column: u32) ->
             source_file.path
         );
 
-        let file_lines = source_file.content.lines().count();
+        let file_lines = source_file.line_count();
         let line_length = source_file
-            .content
             .lines()
             .nth((line - 1) as usize)
             .map(|line| line.chars().count());
@@ -168,6 +174,74 @@ pub const fn is_valid(&self) -> bool {
         self.line != 0
     }
 
+    /// Returns the location `chars` characters after this location, or `None` if that would be at or
+    /// past the end of the source file.
+    ///
+    /// Line breaks are respected, so advancing over a `'\n'`, `"\r\n"` or lone `'\r'` moves to the
+    /// first column of the next line. Characters are counted as Unicode codepoints, not bytes.
+    ///
+    /// # Parameters
+    ///
+    /// - `chars`: The number of characters to advance by.
+    ///
+    /// # Returns
+    ///
+    /// The advanced location, or `None` if this location has no source file or the advanced
+    /// location would be past the end of the source file.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rustcc::source_file::SourceFile;
+    /// # use rustcc::source_location::SourceLocation;
+    /// let source_file = SourceFile::new("path/to/file", "ab\ncd");
+    /// let location = SourceLocation::new(&source_file, 0, 1, 1);
+    /// let advanced = location.advanced_by(3).unwrap();
+    ///
+    /// assert_eq!(advanced.index, 3);
+    /// assert_eq!(advanced.line, 2);
+    /// assert_eq!(advanced.column, 1);
+    ///
+    /// assert!(location.advanced_by(5).is_none());
+    /// ```
+    #[must_use]
+    pub fn advanced_by(&self, chars: usize) -> Option<SourceLocation<'a>> {
+        let source_file = self.source_file?;
+
+        let mut index = self.index;
+        let mut line = self.line;
+        let mut column = self.column;
+
+        let mut characters = source_file.content.get(self.index..)?.chars().peekable();
+        for _ in 0..chars {
+            let Some(character) = characters.next() else {
+                break;
+            };
+            index += character.len_utf8();
+
+            // A '\r' directly followed by '\n' is part of a single line break
+            let is_line_break =
+                character == '\n' || (character == '\r' && characters.peek() != Some(&'\n'));
+            if is_line_break {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+
+        // We either ran out of characters or landed exactly on the end of the file
+        if index >= source_file.content.len() {
+            return None;
+        }
+
+        Some(Self {
+            source_file: Some(source_file),
+            index,
+            line,
+            column,
+        })
+    }
+
     /// Returns a `SourceRange` with the same begin and end locations as this `SourceLocation`.
     ///
     /// # Examples
@@ -196,6 +270,21 @@ fn default() -> Self {
     }
 }
 
+impl PartialOrd for SourceLocation<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.source_file != other.source_file {
+            return None;
+        }
+
+        Some(
+            self.index
+                .cmp(&other.index)
+                .then(self.line.cmp(&other.line))
+                .then(self.column.cmp(&other.column)),
+        )
+    }
+}
+
 impl fmt::Display for SourceLocation<'_> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         if !self.is_valid() {
@@ -334,6 +423,75 @@ fn test_fmt_invalid_source_location() {
         assert_eq!(expected_output, format!("{location}"));
     }
 
+    #[test]
+    fn test_advanced_by_same_line() {
+        let source_file = SourceFile::new("path/to/file", "content");
+        let location = SourceLocation::new(&source_file, 0, 1, 1);
+        let advanced = location.advanced_by(3).unwrap();
+
+        assert_eq!(advanced.source_file, Some(&source_file));
+        assert_eq!(advanced.index, 3);
+        assert_eq!(advanced.line, 1);
+        assert_eq!(advanced.column, 4);
+    }
+
+    #[test]
+    fn test_advanced_by_zero() {
+        let source_file = SourceFile::new("path/to/file", "content");
+        let location = SourceLocation::new(&source_file, 2, 1, 3);
+
+        assert_eq!(location.advanced_by(0), Some(location));
+    }
+
+    #[test]
+    fn test_advanced_by_across_newline() {
+        let source_file = SourceFile::new("path/to/file", "ab\ncd\nef");
+        let location = SourceLocation::new(&source_file, 1, 1, 2);
+
+        let advanced = location.advanced_by(2).unwrap();
+        assert_eq!(advanced.index, 3);
+        assert_eq!(advanced.line, 2);
+        assert_eq!(advanced.column, 1);
+
+        let advanced = location.advanced_by(6).unwrap();
+        assert_eq!(advanced.index, 7);
+        assert_eq!(advanced.line, 3);
+        assert_eq!(advanced.column, 2);
+    }
+
+    #[test]
+    fn test_advanced_by_multi_byte_characters() {
+        let source_file = SourceFile::new("path/to/file", "aこbѤc");
+        let location = SourceLocation::new(&source_file, 0, 1, 1);
+
+        let advanced = location.advanced_by(2).unwrap();
+        assert_eq!(advanced.index, 4);
+        assert_eq!(advanced.line, 1);
+        assert_eq!(advanced.column, 3);
+
+        let advanced = advanced.advanced_by(2).unwrap();
+        assert_eq!(advanced.index, 7);
+        assert_eq!(advanced.line, 1);
+        assert_eq!(advanced.column, 5);
+        assert_eq!(advanced.to_range().source_text(), Some("c"));
+    }
+
+    #[test]
+    fn test_advanced_by_past_end_of_file() {
+        let source_file = SourceFile::new("path/to/file", "ab\ncd");
+        let location = SourceLocation::new(&source_file, 3, 2, 1);
+
+        assert!(location.advanced_by(1).is_some());
+        assert!(location.advanced_by(2).is_none());
+        assert!(location.advanced_by(100).is_none());
+    }
+
+    #[test]
+    fn test_advanced_by_without_source_file() {
+        assert!(SourceLocation::invalid().advanced_by(1).is_none());
+        assert!(SourceLocation::new_scratch(1, 1).advanced_by(0).is_none());
+    }
+
     #[test]
     fn test_to_range_valid_location() {
         let source_file = SourceFile::new("path/to/file", "content");
@@ -364,4 +522,27 @@ fn test_to_range_scratch_location() {
         assert_eq!(range.begin, location);
         assert_eq!(range.end, location);
     }
+
+    #[test]
+    fn test_ordering_same_file() {
+        let source_file = SourceFile::new("test.c", "int x;\nint y;");
+        let first = SourceLocation::new(&source_file, 4, 1, 5);
+        let second = SourceLocation::new(&source_file, 9, 2, 3);
+
+        assert!(first < second);
+        assert!(second > first);
+        assert_eq!(first.partial_cmp(&first), Some(Ordering::Equal));
+        assert!(SourceLocation::new_scratch(1, 2) < SourceLocation::new_scratch(2, 1));
+    }
+
+    #[test]
+    fn test_ordering_different_files() {
+        let first_file = SourceFile::new("first.c", "int x;");
+        let second_file = SourceFile::new("second.c", "int x;");
+        let first = SourceLocation::new(&first_file, 0, 1, 1);
+        let second = SourceLocation::new(&second_file, 4, 1, 5);
+
+        assert_eq!(first.partial_cmp(&second), None);
+        assert_eq!(first.partial_cmp(&SourceLocation::new_scratch(1, 1)), None);
+    }
 }
@@ -1,19 +1,78 @@
-use crate::source_file::SourceFile;
+use crate::{
+    error::{Error, Result},
+    source_file::SourceFile,
+};
 use elsa::FrozenMap;
-use std::{collections::HashMap, fmt::Debug, fs};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Debug},
+    fs, io,
+};
+
+/// The input path meaning the source is read from the standard input
+pub const STDIN_PATH: &str = "-";
+
+/// The path of the source file read from the standard input, as shown in diagnostics
+pub const STDIN_FILE_NAME: &str = "<stdin>";
+
+/// Why a source file could not be loaded
+#[derive(Debug)]
+pub enum SourceManagerError {
+    NotFound,
+    PermissionDenied,
+    InvalidUtf8,
+    /// Any other error reported by the filesystem
+    Io(io::Error),
+}
+
+impl From<io::Error> for SourceManagerError {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => SourceManagerError::NotFound,
+            io::ErrorKind::PermissionDenied => SourceManagerError::PermissionDenied,
+            // Reading a file into a string fails with invalid data if it isn't valid UTF-8
+            io::ErrorKind::InvalidData => SourceManagerError::InvalidUtf8,
+            _ => SourceManagerError::Io(error),
+        }
+    }
+}
+
+impl fmt::Display for SourceManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceManagerError::NotFound => write!(f, "no such file or directory"),
+            SourceManagerError::PermissionDenied => write!(f, "permission denied"),
+            SourceManagerError::InvalidUtf8 => write!(f, "file is not valid UTF-8"),
+            SourceManagerError::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for SourceManagerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SourceManagerError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
 
 /// This trait defines the interface for a source manager
 /// which is responsible for loading source files
 /// and caching them
 pub trait SourceManager<'a> {
-    // TODO: Instead of optional return a Result
-    fn load_file<S: Into<&'a str>>(&self, path: S) -> Option<&SourceFile>;
+    fn load_file<S: Into<&'a str>>(&self, path: S) -> Result<&SourceFile>;
+
+    /// Returns the paths of all files loaded so far
+    fn loaded_paths(&self) -> Vec<String>;
 }
 
 /// This class manages all the source files with access to the real filesystem
 #[derive(Default)]
 pub struct RealFSSourceManager {
     source_files: FrozenMap<String, Box<SourceFile>>,
+    loaded_paths: RefCell<Vec<String>>,
 }
 
 impl RealFSSourceManager {
@@ -21,23 +80,31 @@ impl RealFSSourceManager {
     pub fn new() -> Self {
         Self {
             source_files: FrozenMap::new(),
+            loaded_paths: RefCell::new(Vec::new()),
         }
     }
 
-    fn load_file_from_disk(&self, path: &str) -> bool {
+    fn load_file_from_disk(&self, path: &str) -> Result<()> {
         debug_assert!(!self.is_file_loaded(path), "File already loaded");
 
-        if let Ok(content) = fs::read_to_string(path) {
-            // Cache the file
-            self.source_files.insert(
-                path.to_owned(),
-                Box::from(SourceFile::new(path.to_owned(), content)),
-            );
-
-            return true;
-        }
+        let (file_name, content) = if path == STDIN_PATH {
+            (STDIN_FILE_NAME, io::read_to_string(io::stdin()))
+        } else {
+            (path, fs::read_to_string(path))
+        };
+        let content = content.map_err(|error| Error::Io {
+            path: path.to_owned(),
+            error: error.into(),
+        })?;
+
+        // Cache the file
+        self.source_files.insert(
+            path.to_owned(),
+            Box::from(SourceFile::new(file_name, content)),
+        );
+        self.loaded_paths.borrow_mut().push(path.to_owned());
 
-        false
+        Ok(())
     }
 
     fn is_file_loaded(&self, path: &str) -> bool {
@@ -51,14 +118,18 @@ fn get_source_file(&self, path: &str) -> &SourceFile {
 }
 
 impl<'a> SourceManager<'a> for RealFSSourceManager {
-    fn load_file<S: Into<&'a str>>(&self, path: S) -> Option<&SourceFile> {
+    fn load_file<S: Into<&'a str>>(&self, path: S) -> Result<&SourceFile> {
         let path = path.into();
 
-        if self.is_file_loaded(path) || self.load_file_from_disk(path) {
-            return Some(self.get_source_file(path));
+        if !self.is_file_loaded(path) {
+            self.load_file_from_disk(path)?;
         }
 
-        None
+        Ok(self.get_source_file(path))
+    }
+
+    fn loaded_paths(&self) -> Vec<String> {
+        self.loaded_paths.borrow().clone()
     }
 }
 
@@ -83,8 +154,16 @@ pub fn add_file<S1: Into<String> + Clone, S2: Into<String>>(&mut self, path: S1,
 }
 
 impl<'a> SourceManager<'a> for VirtualSourceManager {
-    fn load_file<S: Into<&'a str>>(&self, path: S) -> Option<&SourceFile> {
-        self.source_files.get(path.into())
+    fn load_file<S: Into<&'a str>>(&self, path: S) -> Result<&SourceFile> {
+        let path = path.into();
+
+        self.source_files.get(path).ok_or_else(|| not_found(path))
+    }
+
+    fn loaded_paths(&self) -> Vec<String> {
+        let mut paths = self.source_files.keys().cloned().collect::<Vec<_>>();
+        paths.sort();
+        paths
     }
 }
 
@@ -100,8 +179,19 @@ pub const fn new() -> Self {
 }
 
 impl<'a> SourceManager<'a> for EmptySourceManager {
-    fn load_file<S: Into<&'a str>>(&self, _path: S) -> Option<&SourceFile> {
-        None
+    fn load_file<S: Into<&'a str>>(&self, path: S) -> Result<&SourceFile> {
+        Err(not_found(path.into()))
+    }
+
+    fn loaded_paths(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+fn not_found(path: &str) -> Error {
+    Error::Io {
+        path: path.to_owned(),
+        error: SourceManagerError::NotFound,
     }
 }
 
@@ -128,11 +218,10 @@ fn test_real_fs_source_manager() {
         assert_eq!(source_file.content, "content");
 
         // Load the same file again (which should now be cached)
-        assert!(
-            source_manager
-                .load_file(file_path_string.as_str())
-                .is_some()
-        );
+        assert!(source_manager.load_file(file_path_string.as_str()).is_ok());
+
+        // The file is only reported once
+        assert_eq!(source_manager.loaded_paths(), vec![file_path_string]);
     }
 
     #[test]
@@ -147,10 +236,79 @@ fn test_virtual_source_manager() {
         assert_eq!(source_file.content, "content");
     }
 
+    #[test]
+    fn test_virtual_source_manager_loaded_paths() {
+        let mut source_manager = VirtualSourceManager::new();
+
+        source_manager.add_file("b", "content");
+        source_manager.add_file("a", "content");
+
+        assert_eq!(source_manager.loaded_paths(), vec!["a", "b"]);
+    }
+
     #[test]
     fn test_empty_source_manager() {
         let source_manager = EmptySourceManager;
 
-        assert!(source_manager.load_file("any_path").is_none());
+        assert!(matches!(
+            source_manager.load_file("any_path"),
+            Err(Error::Io { .. })
+        ));
+        assert!(source_manager.loaded_paths().is_empty());
+    }
+
+    #[test]
+    fn test_real_fs_source_manager_missing_file() {
+        let source_manager = RealFSSourceManager::new();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("missing.c");
+        let file_path_string = file_path.into_os_string().into_string().unwrap();
+
+        let Err(Error::Io { path, error }) = source_manager.load_file(file_path_string.as_str())
+        else {
+            panic!("expected an IO error");
+        };
+
+        assert_eq!(path, file_path_string);
+        assert!(matches!(error, SourceManagerError::NotFound));
+        assert!(source_manager.loaded_paths().is_empty());
+    }
+
+    #[test]
+    fn test_real_fs_source_manager_invalid_utf8() {
+        let source_manager = RealFSSourceManager::new();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("invalid.c");
+        fs::write(&file_path, b"int \xff;").unwrap();
+        let file_path_string = file_path.into_os_string().into_string().unwrap();
+
+        let Err(Error::Io { error, .. }) = source_manager.load_file(file_path_string.as_str())
+        else {
+            panic!("expected an IO error");
+        };
+
+        assert!(matches!(error, SourceManagerError::InvalidUtf8));
+        assert_eq!(error.to_string(), "file is not valid UTF-8");
+    }
+
+    #[test]
+    fn test_source_manager_error_from_io_error() {
+        let error = SourceManagerError::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(matches!(error, SourceManagerError::PermissionDenied));
+
+        let error = SourceManagerError::from(io::Error::from(io::ErrorKind::Interrupted));
+        assert!(matches!(error, SourceManagerError::Io(_)));
+    }
+
+    #[test]
+    fn test_virtual_source_manager_missing_file() {
+        let source_manager = VirtualSourceManager::new();
+
+        assert!(matches!(
+            source_manager.load_file("missing"),
+            Err(Error::Io { .. })
+        ));
     }
 }
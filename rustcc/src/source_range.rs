@@ -1,9 +1,11 @@
 use crate::source_location::SourceLocation;
-
-// TODO: Same problem for PartialOrd and Ord as with SourceLocation
+use std::cmp::Ordering;
 
 /// A range of source code, represented by a beginning and ending location.
 ///
+/// Like their locations, ranges are only ordered within the same source file. They are ordered by
+/// their beginning and then by their end.
+///
 /// # Examples
 ///
 /// ```
@@ -21,6 +23,7 @@
 /// assert_eq!(range.end, end);
 /// ```
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SourceRange<'a> {
     pub begin: SourceLocation<'a>,
     pub end: SourceLocation<'a>,
@@ -230,6 +233,70 @@ pub fn source_text(&self) -> Option<&'a str> {
 
         source_file.content.get(self.begin.index..=self.end.index)
     }
+
+    /// Returns the smallest range covering both ranges, like a binary operation spanning from its
+    /// left to its right operand. The ranges may overlap or have a gap between them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustcc::source_file::SourceFile;
+    /// # use rustcc::source_location::SourceLocation;
+    /// # use rustcc::source_range::SourceRange;
+    ///
+    /// let source_file = SourceFile::new("path/to/file", "1 + 2");
+    /// let left = SourceRange::from_location(SourceLocation::new(&source_file, 0, 1, 1));
+    /// let right = SourceRange::from_location(SourceLocation::new(&source_file, 4, 1, 5));
+    ///
+    /// assert_eq!(left.merge(right).source_text(), Some("1 + 2"));
+    /// assert_eq!(right.merge(left), left.merge(right));
+    /// ```
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        debug_assert!(
+            self.begin.source_file == other.begin.source_file,
+            "Can only merge ranges in the same file.\nFirst:  {}\nSecond: {}",
+            self.begin,
+            other.begin,
+        );
+
+        Self {
+            begin: if other.begin < self.begin {
+                other.begin
+            } else {
+                self.begin
+            },
+            end: if other.end > self.end {
+                other.end
+            } else {
+                self.end
+            },
+        }
+    }
+
+    /// Whether the location lies within the range, both the begin and end location are part of the
+    /// range. A location in another file is never contained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustcc::source_file::SourceFile;
+    /// # use rustcc::source_location::SourceLocation;
+    /// # use rustcc::source_range::SourceRange;
+    ///
+    /// let source_file = SourceFile::new("path/to/file", "content");
+    /// let range = SourceRange::new(
+    ///     SourceLocation::new(&source_file, 1, 1, 2),
+    ///     SourceLocation::new(&source_file, 3, 1, 4),
+    /// );
+    ///
+    /// assert!(range.contains(SourceLocation::new(&source_file, 2, 1, 3)));
+    /// assert!(!range.contains(SourceLocation::new(&source_file, 4, 1, 5)));
+    /// ```
+    #[must_use]
+    pub fn contains(&self, location: SourceLocation<'a>) -> bool {
+        self.begin <= location && location <= self.end
+    }
 }
 
 impl Default for SourceRange<'_> {
@@ -244,6 +311,15 @@ fn from(location: SourceLocation<'a>) -> Self {
     }
 }
 
+impl PartialOrd for SourceRange<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.begin.partial_cmp(&other.begin)? {
+            Ordering::Equal => self.end.partial_cmp(&other.end),
+            ordering => Some(ordering),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -458,4 +534,74 @@ fn test_source_text_none_source_file() {
 
         assert_eq!(range.source_text(), None);
     }
+
+    #[test]
+    fn test_ordering() {
+        let source_file = SourceFile::new("test.c", "int x;");
+        let location = |index| SourceLocation::new(&source_file, index, 1, index as u32 + 1);
+        let int = SourceRange::new(location(0), location(2));
+        let i = SourceRange::new(location(0), location(0));
+        let x = SourceRange::new(location(4), location(4));
+
+        assert!(i < int);
+        assert!(int < x);
+        assert_eq!(int.partial_cmp(&int), Some(Ordering::Equal));
+
+        let other_file = SourceFile::new("other.c", "int x;");
+        let other = SourceRange::new(
+            SourceLocation::new(&other_file, 0, 1, 1),
+            SourceLocation::new(&other_file, 2, 1, 3),
+        );
+        assert_eq!(int.partial_cmp(&other), None);
+    }
+
+    #[test]
+    fn test_merge_adjacent() {
+        let source_file = SourceFile::new("test.c", "return 0;");
+        let location = |index| SourceLocation::new(&source_file, index, 1, index as u32 + 1);
+        let keyword = SourceRange::new(location(0), location(5));
+        let rest = SourceRange::new(location(6), location(8));
+
+        let merged = keyword.merge(rest);
+        assert_eq!(merged, SourceRange::new(location(0), location(8)));
+        assert_eq!(merged.source_text(), Some("return 0;"));
+        assert_eq!(rest.merge(keyword), merged);
+    }
+
+    #[test]
+    fn test_merge_overlapping() {
+        let source_file = SourceFile::new("test.c", "return 0;");
+        let location = |index| SourceLocation::new(&source_file, index, 1, index as u32 + 1);
+        let outer = SourceRange::new(location(0), location(8));
+        let inner = SourceRange::new(location(2), location(4));
+        let overlapping = SourceRange::new(location(4), location(6));
+
+        assert_eq!(outer.merge(inner), outer);
+        assert_eq!(inner.merge(outer), outer);
+        assert_eq!(
+            inner.merge(overlapping),
+            SourceRange::new(location(2), location(6))
+        );
+        assert_eq!(inner.merge(inner), inner);
+    }
+
+    #[test]
+    fn test_contains_boundaries() {
+        let source_file = SourceFile::new("test.c", "return 0;");
+        let location = |index| SourceLocation::new(&source_file, index, 1, index as u32 + 1);
+        let range = SourceRange::new(location(2), location(4));
+
+        assert!(!range.contains(location(1)));
+        assert!(range.contains(location(2)));
+        assert!(range.contains(location(3)));
+        assert!(range.contains(location(4)));
+        assert!(!range.contains(location(5)));
+
+        let single = SourceRange::from_location(location(7));
+        assert!(single.contains(location(7)));
+        assert!(!single.contains(location(6)));
+
+        let other_file = SourceFile::new("other.c", "return 0;");
+        assert!(!range.contains(SourceLocation::new(&other_file, 3, 1, 4)));
+    }
 }
@@ -4,41 +4,140 @@
 
 pub type TokenList<'a> = VecDeque<Token<'a>>;
 
+/// Returns an iterator over all keyword tokens of the token list
+pub fn keywords_only<'t, 'a>(tokens: &'t TokenList<'a>) -> impl Iterator<Item = &'t Token<'a>> {
+    tokens.iter().filter(|token| token.is_keyword())
+}
+
+/// Returns an iterator over all identifier tokens of the token list
+pub fn identifiers<'t, 'a>(tokens: &'t TokenList<'a>) -> impl Iterator<Item = &'t Token<'a>> {
+    tokens.iter().filter(|token| token.is_identifier())
+}
+
+/// Returns an iterator over the tokens of the token list which are neither keywords nor identifiers
+pub fn punctuation_and_literals<'t, 'a>(
+    tokens: &'t TokenList<'a>,
+) -> impl Iterator<Item = &'t Token<'a>> {
+    tokens
+        .iter()
+        .filter(|token| !token.is_keyword() && !token.is_identifier())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TokenKind {
     // Keywords
-    KeywordInt,    // int
-    KeywordReturn, // return
-    KeywordVoid,   // void
+    KeywordAuto,     // auto
+    KeywordBool,     // _Bool
+    KeywordExtern,   // extern
+    KeywordInt,      // int
+    KeywordRegister, // register
+    KeywordRestrict, // restrict
+    KeywordReturn,   // return
+    KeywordVoid,     // void
+    KeywordVolatile, // volatile
+    KeywordWhile,    // while
 
     Identifier(String),
 
     // Literals
-    IntegerLiteral(u32),
+    IntegerLiteral(u32, IntegerSuffix),
+    /// A character constant like `'a'` or `'\n'` with the value of its character
+    CharacterLiteral(u32),
+    /// A string literal like `"hi\n"` with the bytes of its escape sequences decoded
+    StringLiteral(Vec<u8>),
 
     // Symbols
-    LeftParenthesis,  // (
-    RightParenthesis, // )
-    LeftBrace,        // {
-    RightBrace,       // }
-    Semicolon,        // ;
-    Slash,            // /
-    Tilde,            // ~
-    Minus,            // -
-    MinusMinus,       // --
-    Plus,             // +
-    PlusPlus,         // ++
-    Star,             // *
-    Percent,          // %
+    LeftParenthesis,    // (
+    RightParenthesis,   // )
+    LeftBrace,          // {
+    RightBrace,         // }
+    Semicolon,          // ;
+    Comma,              // ,
+    Slash,              // /
+    Tilde,              // ~
+    Minus,              // -
+    MinusMinus,         // --
+    Arrow,              // ->
+    Plus,               // +
+    PlusPlus,           // ++
+    Star,               // *
+    Percent,            // %
+    Less,               // <
+    LessEqual,          // <=
+    Greater,            // >
+    GreaterEqual,       // >=
+    Equal,              // =
+    EqualEqual,         // ==
+    ExclamationEqual,   // !=
+    AmpersandAmpersand, // &&
+    PipePipe,           // ||
+}
+
+/// The suffix of an integer literal, which determines the type of the literal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntegerSuffix {
+    #[default]
+    None, // int
+    Unsigned,         // u
+    Long,             // l
+    UnsignedLong,     // ul
+    LongLong,         // ll
+    UnsignedLongLong, // ull
+}
+
+impl IntegerSuffix {
+    /// Parses the suffix of an integer literal, ignoring case and the order of the unsigned and long
+    /// parts. The two letters of `ll` must have the same case though. Returns `None` if the suffix
+    /// is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustcc::token::IntegerSuffix;
+    /// assert_eq!(IntegerSuffix::from_suffix("u"), Some(IntegerSuffix::Unsigned));
+    /// assert_eq!(IntegerSuffix::from_suffix("LLU"), Some(IntegerSuffix::UnsignedLongLong));
+    /// assert_eq!(IntegerSuffix::from_suffix("lul"), None);
+    /// assert_eq!(IntegerSuffix::from_suffix("lL"), None);
+    /// ```
+    #[must_use]
+    pub fn from_suffix(suffix: &str) -> Option<IntegerSuffix> {
+        // The unsigned part may be placed before or after the long part
+        let (is_unsigned, long_part) = match suffix.strip_prefix(['u', 'U']) {
+            Some(long_part) => (true, long_part),
+            None => match suffix.strip_suffix(['u', 'U']) {
+                Some(long_part) => (true, long_part),
+                None => (false, suffix),
+            },
+        };
+
+        match (is_unsigned, long_part) {
+            (false, "") => Some(IntegerSuffix::None),
+            (true, "") => Some(IntegerSuffix::Unsigned),
+            (false, "l" | "L") => Some(IntegerSuffix::Long),
+            (true, "l" | "L") => Some(IntegerSuffix::UnsignedLong),
+            (false, "ll" | "LL") => Some(IntegerSuffix::LongLong),
+            (true, "ll" | "LL") => Some(IntegerSuffix::UnsignedLongLong),
+            _ => None,
+        }
+    }
 }
 
 impl TokenKind {
     #[must_use]
     pub fn from_identifier(identifier: &str) -> TokenKind {
         match identifier {
+            "auto" => TokenKind::KeywordAuto,
+            "_Bool" => TokenKind::KeywordBool,
+            "extern" => TokenKind::KeywordExtern,
             "int" => TokenKind::KeywordInt,
+            "register" => TokenKind::KeywordRegister,
+            "restrict" => TokenKind::KeywordRestrict,
             "return" => TokenKind::KeywordReturn,
             "void" => TokenKind::KeywordVoid,
+            "volatile" => TokenKind::KeywordVolatile,
+            "while" => TokenKind::KeywordWhile,
             _ => TokenKind::Identifier(identifier.to_string()),
         }
     }
@@ -47,7 +146,16 @@ pub fn from_identifier(identifier: &str) -> TokenKind {
     pub fn is_keyword(&self) -> bool {
         matches!(
             self,
-            TokenKind::KeywordInt | TokenKind::KeywordReturn | TokenKind::KeywordVoid
+            TokenKind::KeywordAuto
+                | TokenKind::KeywordBool
+                | TokenKind::KeywordExtern
+                | TokenKind::KeywordInt
+                | TokenKind::KeywordRegister
+                | TokenKind::KeywordRestrict
+                | TokenKind::KeywordReturn
+                | TokenKind::KeywordVoid
+                | TokenKind::KeywordVolatile
+                | TokenKind::KeywordWhile
         )
     }
 
@@ -55,9 +163,17 @@ pub fn is_keyword(&self) -> bool {
     pub fn is_identifier(&self) -> bool {
         matches!(self, TokenKind::Identifier(_))
     }
+
+    /// Whether the token is an assignment operator like `=`. Compound assignments like `+=` belong
+    /// here as well once they are lexed.
+    #[must_use]
+    pub fn is_assignment_operator(&self) -> bool {
+        matches!(self, TokenKind::Equal)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token<'a> {
     pub kind: TokenKind,
     pub range: SourceRange<'a>,
@@ -80,9 +196,32 @@ pub fn new_identifier<R: Into<SourceRange<'a>>>(range: R) -> Self {
     }
 
     #[must_use]
-    pub fn new_integer_literal<R: Into<SourceRange<'a>>>(value: u32, range: R) -> Self {
+    pub fn new_integer_literal<R: Into<SourceRange<'a>>>(
+        value: u32,
+        suffix: IntegerSuffix,
+        range: R,
+    ) -> Self {
         Self {
-            kind: TokenKind::IntegerLiteral(value),
+            kind: TokenKind::IntegerLiteral(value, suffix),
+            range: range.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn new_character_literal<R: Into<SourceRange<'a>>>(value: u32, range: R) -> Self {
+        Self {
+            kind: TokenKind::CharacterLiteral(value),
+            range: range.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn new_string_literal<V: Into<Vec<u8>>, R: Into<SourceRange<'a>>>(
+        value: V,
+        range: R,
+    ) -> Self {
+        Self {
+            kind: TokenKind::StringLiteral(value.into()),
             range: range.into(),
         }
     }
@@ -147,6 +286,18 @@ pub fn new_semicolon<R: Into<SourceRange<'a>>>(range: R) -> Self {
         }
     }
 
+    #[must_use]
+    pub fn new_comma<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), ",");
+
+        Self {
+            kind: TokenKind::Comma,
+            range,
+        }
+    }
+
     #[must_use]
     pub fn new_slash<R: Into<SourceRange<'a>>>(range: R) -> Self {
         let range = range.into();
@@ -195,6 +346,18 @@ pub fn new_minus_minus<R: Into<SourceRange<'a>>>(range: R) -> Self {
         }
     }
 
+    #[must_use]
+    pub fn new_arrow<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), "->");
+
+        Self {
+            kind: TokenKind::Arrow,
+            range,
+        }
+    }
+
     #[must_use]
     pub fn new_plus<R: Into<SourceRange<'a>>>(range: R) -> Self {
         let range = range.into();
@@ -243,6 +406,114 @@ pub fn new_percent<R: Into<SourceRange<'a>>>(range: R) -> Self {
         }
     }
 
+    #[must_use]
+    pub fn new_less<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), "<");
+
+        Self {
+            kind: TokenKind::Less,
+            range,
+        }
+    }
+
+    #[must_use]
+    pub fn new_less_equal<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), "<=");
+
+        Self {
+            kind: TokenKind::LessEqual,
+            range,
+        }
+    }
+
+    #[must_use]
+    pub fn new_greater<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), ">");
+
+        Self {
+            kind: TokenKind::Greater,
+            range,
+        }
+    }
+
+    #[must_use]
+    pub fn new_greater_equal<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), ">=");
+
+        Self {
+            kind: TokenKind::GreaterEqual,
+            range,
+        }
+    }
+
+    #[must_use]
+    pub fn new_equal<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), "=");
+
+        Self {
+            kind: TokenKind::Equal,
+            range,
+        }
+    }
+
+    #[must_use]
+    pub fn new_equal_equal<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), "==");
+
+        Self {
+            kind: TokenKind::EqualEqual,
+            range,
+        }
+    }
+
+    #[must_use]
+    pub fn new_exclamation_equal<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), "!=");
+
+        Self {
+            kind: TokenKind::ExclamationEqual,
+            range,
+        }
+    }
+
+    #[must_use]
+    pub fn new_ampersand_ampersand<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), "&&");
+
+        Self {
+            kind: TokenKind::AmpersandAmpersand,
+            range,
+        }
+    }
+
+    #[must_use]
+    pub fn new_pipe_pipe<R: Into<SourceRange<'a>>>(range: R) -> Self {
+        let range = range.into();
+
+        debug_assert_eq!(range.source_text().unwrap(), "||");
+
+        Self {
+            kind: TokenKind::PipePipe,
+            range,
+        }
+    }
+
     #[must_use]
     pub fn is_keyword(&self) -> bool {
         self.kind.is_keyword()
@@ -282,3 +553,133 @@ pub fn dump(&self) -> String {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        diagnostic_consumer::IgnoreDiagnosticConsumer, diagnostic_engine::DiagnosticEngine,
+        lexer::Lexer, source_file::SourceFile,
+    };
+
+    use std::{cell::RefCell, rc::Rc};
+
+    const SAMPLE_PROGRAM: &str = "int main(void) {\n    return value;\n}\n";
+
+    fn tokenize(source_file: &SourceFile) -> TokenList<'_> {
+        let diagnostic_engine = Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+            IgnoreDiagnosticConsumer,
+        ))));
+
+        Lexer::new(diagnostic_engine, source_file).tokenize()
+    }
+
+    #[test]
+    fn test_keywords_only() {
+        let source_file = SourceFile::new("test.c", SAMPLE_PROGRAM);
+        let tokens = tokenize(&source_file);
+
+        let keywords: Vec<_> = keywords_only(&tokens).map(|token| &token.kind).collect();
+        assert_eq!(
+            keywords,
+            [
+                &TokenKind::KeywordInt,
+                &TokenKind::KeywordVoid,
+                &TokenKind::KeywordReturn
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identifiers() {
+        let source_file = SourceFile::new("test.c", SAMPLE_PROGRAM);
+        let tokens = tokenize(&source_file);
+
+        let names: Vec<_> = identifiers(&tokens)
+            .filter_map(Token::source_text)
+            .collect();
+        assert_eq!(names, ["main", "value"]);
+    }
+
+    #[test]
+    fn test_punctuation_and_literals() {
+        let source_file = SourceFile::new("test.c", SAMPLE_PROGRAM);
+        let tokens = tokenize(&source_file);
+
+        assert_eq!(punctuation_and_literals(&tokens).count(), 5);
+        assert_eq!(
+            keywords_only(&tokens).count()
+                + identifiers(&tokens).count()
+                + punctuation_and_literals(&tokens).count(),
+            tokens.len()
+        );
+    }
+
+    #[test]
+    fn test_storage_class_keywords() {
+        assert_eq!(TokenKind::from_identifier("auto"), TokenKind::KeywordAuto);
+        assert_eq!(
+            TokenKind::from_identifier("register"),
+            TokenKind::KeywordRegister
+        );
+        assert!(TokenKind::KeywordAuto.is_keyword());
+        assert!(TokenKind::KeywordRegister.is_keyword());
+    }
+
+    #[test]
+    fn test_type_qualifier_keywords() {
+        assert_eq!(
+            TokenKind::from_identifier("restrict"),
+            TokenKind::KeywordRestrict
+        );
+        assert_eq!(
+            TokenKind::from_identifier("volatile"),
+            TokenKind::KeywordVolatile
+        );
+        assert!(TokenKind::KeywordRestrict.is_keyword());
+        assert!(TokenKind::KeywordVolatile.is_keyword());
+    }
+
+    #[test]
+    fn test_while_keyword() {
+        assert_eq!(TokenKind::from_identifier("while"), TokenKind::KeywordWhile);
+        assert!(TokenKind::KeywordWhile.is_keyword());
+    }
+
+    #[test]
+    fn test_bool_keyword() {
+        assert_eq!(TokenKind::from_identifier("_Bool"), TokenKind::KeywordBool);
+        assert!(TokenKind::KeywordBool.is_keyword());
+
+        // Only the C23 spelling 'bool' is a macro from <stdbool.h> in C99
+        assert_eq!(
+            TokenKind::from_identifier("bool"),
+            TokenKind::Identifier("bool".to_string())
+        );
+    }
+
+    #[test]
+    fn test_assignment_operators() {
+        assert!(TokenKind::Equal.is_assignment_operator());
+
+        // Comparisons contain an '=' but don't assign
+        for kind in [
+            TokenKind::EqualEqual,
+            TokenKind::ExclamationEqual,
+            TokenKind::LessEqual,
+            TokenKind::GreaterEqual,
+            TokenKind::Plus,
+        ] {
+            assert!(!kind.is_assignment_operator(), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn test_filters_on_empty_token_list() {
+        let tokens = TokenList::new();
+
+        assert_eq!(keywords_only(&tokens).count(), 0);
+        assert_eq!(identifiers(&tokens).count(), 0);
+        assert_eq!(punctuation_and_literals(&tokens).count(), 0);
+    }
+}
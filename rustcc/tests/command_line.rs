@@ -1,6 +1,587 @@
 use assert_cmd::Command;
+use std::fs;
 
 #[test]
 fn command_line_no_arguments() {
     Command::cargo_bin("rustcc").unwrap().assert().failure();
 }
+
+#[test]
+fn command_line_werror_exit_code() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("warning.c");
+    fs::write(&file_path, "\0\nint main(void) {\n  return 0;\n}\n").unwrap();
+
+    // Warnings alone don't fail the compilation
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .assert()
+        .success();
+
+    // Warnings turned into errors use the default failure exit code
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-Werror")
+        .assert()
+        .code(1);
+
+    // Unless a custom exit code was requested
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-Werror")
+        .arg("--werror-exit-code")
+        .arg("42")
+        .assert()
+        .code(42);
+}
+
+#[test]
+fn command_line_ignore_all_warnings() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("warning.c");
+    fs::write(&file_path, "\0\nint main(void) {\n  return 0;\n}\n").unwrap();
+
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-w")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+
+    // Suppressed warnings can't be turned into errors
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-w")
+        .arg("-Werror")
+        .assert()
+        .success();
+}
+
+#[test]
+fn command_line_stdin() {
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg("-")
+        .arg("--print-ir")
+        .write_stdin("int main(void){return 0;}")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("source_filename = \"<stdin>\""));
+
+    // Diagnostics refer to the synthetic file name
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg("-")
+        .write_stdin("int main(void){return;}")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("<stdin>:1:22: error:"));
+}
+
+#[test]
+fn command_line_werror_exit_code_with_real_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("error.c");
+    fs::write(&file_path, "\0\nint main(void) {\n  return;\n}\n").unwrap();
+
+    // Genuine errors always use the default failure exit code
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-Werror")
+        .arg("--werror-exit-code")
+        .arg("42")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn command_line_dependency_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    let dependency_file_path = temp_dir.path().join("deps.d");
+    fs::write(&file_path, "int main(void) {\n  return 0;\n}\n").unwrap();
+
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--MD")
+        .arg("--MF")
+        .arg(&dependency_file_path)
+        .assert()
+        .success();
+
+    let dependency_file = fs::read_to_string(dependency_file_path).unwrap();
+
+    assert_eq!(
+        dependency_file,
+        format!("main.o: {}\n", file_path.to_str().unwrap())
+    );
+}
+
+#[test]
+fn command_line_dependency_file_default_path() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    fs::write(&file_path, "int main(void) {\n  return 0;\n}\n").unwrap();
+
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--MD")
+        .assert()
+        .success();
+
+    let dependency_file = fs::read_to_string(temp_dir.path().join("main.d")).unwrap();
+
+    assert!(dependency_file.contains(file_path.to_str().unwrap()));
+}
+
+#[test]
+fn command_line_dependency_file_requires_md() {
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg("main.c")
+        .arg("--MF")
+        .arg("deps.d")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn command_line_diagnostics_to_stderr() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("warning.c");
+    fs::write(&file_path, "\0\nint main(void) {\n  return 0;\n}\n").unwrap();
+
+    // By default warnings are printed to stdout
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("null character ignored"));
+    assert!(output.stderr.is_empty());
+
+    // With the flag they are printed to stderr
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--fdiagnostics-to-stderr")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("null character ignored"));
+}
+
+#[test]
+fn command_line_print_diagnostic_flags() {
+    // No input file is needed to list the flags
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg("--print-diagnostic-flags")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.lines().any(|line| line == "-Wnull-character"));
+    assert!(stdout.lines().any(|line| line == "-Wstrict-prototypes"));
+}
+
+#[test]
+fn command_line_unreadable_input_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let missing_path = temp_dir.path().join("missing.c");
+    let invalid_path = temp_dir.path().join("invalid.c");
+    fs::write(&invalid_path, b"int main(void) { return \xff; }\n").unwrap();
+
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&missing_path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no such file or directory"));
+
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&invalid_path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("file is not valid UTF-8"));
+}
+
+#[test]
+fn command_line_output_ir_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    let output_path = temp_dir.path().join("out.ll");
+    fs::write(&file_path, "int main(void) {\n  return 0;\n}\n").unwrap();
+
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-o")
+        .arg(&output_path)
+        .assert()
+        .success();
+
+    let ir = fs::read_to_string(output_path).unwrap();
+
+    assert!(ir.contains("define i32 @main"));
+}
+
+#[test]
+fn command_line_output_object_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    fs::write(&file_path, "int main(void) {\n  return 0;\n}\n").unwrap();
+
+    // Without '-o' the object file is named after the source file
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-c")
+        .assert()
+        .success();
+
+    let object_file = fs::read(temp_dir.path().join("main.o")).unwrap();
+
+    assert!(!object_file.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn command_line_short_circuit_does_not_trap() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    let object_path = temp_dir.path().join("main.o");
+    let executable_path = temp_dir.path().join("main");
+    fs::write(
+        &file_path,
+        "int main(void) {\n  return (0 && 1 / 0) + (1 || 1 % 0);\n}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-c")
+        .arg("-o")
+        .arg(&object_path)
+        .assert()
+        .success();
+
+    // Link using the system C compiler and run the program, the divisions must never execute
+    let linker = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    Command::new(linker)
+        .arg(&object_path)
+        .arg("-o")
+        .arg(&executable_path)
+        .assert()
+        .success();
+
+    Command::new(&executable_path).assert().code(1);
+}
+
+#[test]
+fn command_line_module_hash_is_reproducible() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    fs::write(
+        &file_path,
+        "int main(void) {\n  int x = 2;\n  return x * 3;\n}\n",
+    )
+    .unwrap();
+
+    let module_hash = || {
+        let output = Command::cargo_bin("rustcc")
+            .unwrap()
+            .arg(&file_path)
+            .arg("--module-hash")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let first_hash = module_hash();
+    assert_eq!(first_hash.trim_end().len(), 16);
+    assert!(first_hash.trim_end().chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(first_hash, module_hash());
+}
+
+#[cfg(unix)]
+#[test]
+fn command_line_local_variable() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    let object_path = temp_dir.path().join("main.o");
+    let executable_path = temp_dir.path().join("main");
+    fs::write(&file_path, "int main(void){int x=3; return x+1;}\n").unwrap();
+
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-c")
+        .arg("-o")
+        .arg(&object_path)
+        .assert()
+        .success();
+
+    let linker = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    Command::new(linker)
+        .arg(&object_path)
+        .arg("-o")
+        .arg(&executable_path)
+        .assert()
+        .success();
+
+    Command::new(&executable_path).assert().code(4);
+}
+
+#[cfg(unix)]
+#[test]
+fn command_line_function_call() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    let object_path = temp_dir.path().join("main.o");
+    let executable_path = temp_dir.path().join("main");
+    fs::write(
+        &file_path,
+        "int helper(void) {\n  return 7;\n}\nint main(void) {\n  return helper();\n}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-c")
+        .arg("-o")
+        .arg(&object_path)
+        .assert()
+        .success();
+
+    let linker = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    Command::new(linker)
+        .arg(&object_path)
+        .arg("-o")
+        .arg(&executable_path)
+        .assert()
+        .success();
+
+    Command::new(&executable_path).assert().code(7);
+}
+
+#[cfg(unix)]
+#[test]
+fn command_line_bool_return_reads_back_one() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("is_set.c");
+    let object_path = temp_dir.path().join("is_set.o");
+    let main_path = temp_dir.path().join("main.c");
+    let executable_path = temp_dir.path().join("main");
+    fs::write(&file_path, "_Bool is_set(void) {\n  return 42;\n}\n").unwrap();
+    fs::write(
+        &main_path,
+        "extern _Bool is_set(void);\nint main(void) { return is_set(); }\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-c")
+        .arg("-o")
+        .arg(&object_path)
+        .assert()
+        .success();
+
+    // The caller is compiled by the system C compiler, it has to see the normalized value 1
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    Command::new(compiler)
+        .arg(&main_path)
+        .arg(&object_path)
+        .arg("-o")
+        .arg(&executable_path)
+        .assert()
+        .success();
+
+    Command::new(&executable_path).assert().code(1);
+}
+
+#[test]
+fn command_line_emit_assembly() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    fs::write(&file_path, "int main(void) {\n  return 2;\n}\n").unwrap();
+
+    // Without '-o' the assembly file is named after the source file
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--emit=asm")
+        .assert()
+        .success();
+
+    let assembly = fs::read_to_string(temp_dir.path().join("main.s")).unwrap();
+
+    assert!(assembly.contains("main"));
+    assert!(assembly.contains("ret") || assembly.contains("mov"));
+
+    // The kind of output doesn't depend on the extension of '-o'
+    let output_path = temp_dir.path().join("main.txt");
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--emit", "ir", "-o"])
+        .arg(&output_path)
+        .assert()
+        .success();
+
+    assert!(
+        fs::read_to_string(output_path)
+            .unwrap()
+            .contains("define i32 @main()")
+    );
+}
+
+#[test]
+fn command_line_target() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    fs::write(&file_path, "int main(void) {\n  return 2;\n}\n").unwrap();
+
+    let output_path = temp_dir.path().join("main.ll");
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--target=x86_64-unknown-linux-gnu")
+        .arg("-o")
+        .arg(&output_path)
+        .assert()
+        .success();
+
+    assert!(
+        fs::read_to_string(output_path)
+            .unwrap()
+            .contains("target triple = \"x86_64-unknown-linux-gnu\"")
+    );
+
+    // An unknown triple is reported without generating any code
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--target=not-a-target", "--print-ir"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .starts_with("error: unknown target triple 'not-a-target'")
+    );
+
+    // Like any other diagnostic it follows the diagnostics format
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--target=not-a-target", "--fdiagnostics-format=json"])
+        .output()
+        .unwrap();
+    let diagnostic: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(diagnostic["level"], "error");
+    assert!(
+        diagnostic["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("unknown target triple 'not-a-target'")
+    );
+}
+
+#[test]
+fn command_line_diagnostic_summary() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    fs::write(&file_path, "int main() {\n  return 0x;\n}\n").unwrap();
+
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("-Wstrict-prototypes")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).lines().last(),
+        Some("1 warning and 1 error generated.")
+    );
+
+    // Nothing is printed for a file without diagnostics
+    fs::write(&file_path, "int main(void) {\n  return 0;\n}\n").unwrap();
+
+    Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+}
+
+#[test]
+fn command_line_diagnostics_format_json() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("main.c");
+    fs::write(&file_path, "int main(void) {\n  return ;\n}\n").unwrap();
+
+    let output = Command::cargo_bin("rustcc")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--fdiagnostics-format=json")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+
+    // Every line is a diagnostic and no summary is printed
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let diagnostics = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert!(
+        diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic["level"] == "error")
+    );
+
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic["message"], "expected expression");
+    assert_eq!(diagnostic["file"], file_path.display().to_string());
+    assert_eq!(diagnostic["line"], 2);
+    assert_eq!(diagnostic["column"], 10);
+    assert_eq!(diagnostic["flag"], serde_json::Value::Null);
+    assert_eq!(diagnostic["notes"], serde_json::json!([]));
+}
@@ -0,0 +1,113 @@
+use rustcc::{
+    diagnostic::{DiagnosticId, DiagnosticLevel},
+    diagnostic_consumer::CollectingDiagnosticConsumer,
+    run_with_diagnostic_consumer,
+    source_manager::STDIN_PATH,
+};
+use std::{
+    collections::BTreeSet,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+use test_driver::run_directive::{self, RunDirective};
+
+/// Errors which can't be caused by any source file, so no test input can exist for them
+const UNTESTABLE_DIAGNOSTICS: &[DiagnosticId] = &[
+    // Integer literals are only parsed when the next token is one
+    DiagnosticId::ExpectedIntegerLiteral,
+];
+
+/// Errors which are only reported for bugs in the compiler, so no test input may cause them
+const INTERNAL_ERRORS: &[DiagnosticId] = &[
+    // Reported for an invalid LLVM module
+    DiagnosticId::CodegenFailed,
+];
+
+/// Collects all `.c` files below `directory` sorted by path
+fn collect_inputs(directory: &Path, inputs: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.is_dir() {
+            collect_inputs(&path, inputs);
+        } else if path.extension().is_some_and(|extension| extension == "c") {
+            inputs.push(path);
+        }
+    }
+
+    inputs.sort();
+}
+
+#[test]
+fn every_error_has_a_test_input() {
+    let input_directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/input");
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    let mut inputs = Vec::new();
+    collect_inputs(&input_directory, &mut inputs);
+
+    let mut reported = BTreeSet::new();
+    for (index, input_path) in inputs.iter().enumerate() {
+        let content = fs::read_to_string(input_path).unwrap();
+        let temp_path = temp_dir.path().join(format!("test-{index}"));
+
+        // The compiler runs in this process, so the standard input is passed as a file instead
+        let stdin_path = temp_dir.path().join(format!("test-{index}.stdin"));
+        if let Some(stdin) = run_directive::parse_stdin(&content) {
+            fs::write(&stdin_path, stdin).unwrap();
+        }
+
+        // Other executables like the builtin `cat` don't report diagnostics
+        let directives = RunDirective::parse_all(&content).unwrap();
+        for directive in directives
+            .iter()
+            .filter(|directive| directive.executable == "rustcc")
+        {
+            let arguments = directive
+                .command_line_arguments(input_path, &temp_path)
+                .into_iter()
+                .map(|argument| {
+                    if argument == STDIN_PATH {
+                        stdin_path.clone().into_os_string()
+                    } else {
+                        argument
+                    }
+                });
+
+            let consumer = CollectingDiagnosticConsumer::default();
+            run_with_diagnostic_consumer(
+                [OsString::from(&directive.executable)]
+                    .into_iter()
+                    .chain(arguments),
+                Box::new(consumer.clone()),
+            );
+
+            reported.extend(consumer.ids().into_iter().map(|id| format!("{id:?}")));
+        }
+    }
+
+    let untested = DiagnosticId::ALL
+        .iter()
+        .filter(|id| id.level() >= DiagnosticLevel::Error)
+        .filter(|id| !UNTESTABLE_DIAGNOSTICS.contains(id) && !INTERNAL_ERRORS.contains(id))
+        .map(|id| format!("{id:?}"))
+        .filter(|id| !reported.contains(id))
+        .collect::<Vec<_>>();
+
+    assert!(
+        untested.is_empty(),
+        "No input under tests/input reports these errors, add a test for them: {untested:?}"
+    );
+
+    let internal = INTERNAL_ERRORS
+        .iter()
+        .map(|id| format!("{id:?}"))
+        .filter(|id| reported.contains(id))
+        .collect::<Vec<_>>();
+
+    assert!(
+        internal.is_empty(),
+        "Inputs under tests/input report errors caused by compiler bugs: {internal:?}"
+    );
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} %{ENV:RUSTCC_TEST_FLAGS}
+
+int main(void) {
+    return 0;
+}
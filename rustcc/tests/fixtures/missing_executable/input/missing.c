@@ -0,0 +1,5 @@
+// RUN: ${{rustc-c}}
+
+int main(void) {
+    return 0;
+}
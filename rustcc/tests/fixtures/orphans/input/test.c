@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}}
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,6 @@
+// ALLOW-RETRIES: 1
+// RUN: ${{rustcc}}
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}}
+// XFAIL
+
+int main(void) {
+    return 2;
+}
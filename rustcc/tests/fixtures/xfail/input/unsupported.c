@@ -0,0 +1,8 @@
+// RUN: ${{rustcc}}
+// XFAIL
+
+int main(void) {
+    int a = 2;
+    int *b = &a;
+    return *b;
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --print-ast --print-ir
+
+int main(void) {
+    return 1 + 2;
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --print-ast --print-ir
+
+int main(void) {
+    return 5 * 4 / 2 - 3 % (2 + 1);
+}
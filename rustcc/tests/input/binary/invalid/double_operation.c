@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int main(void) {
+    return 1 * / 2;
+}
@@ -0,0 +1,12 @@
+// RUN: ${{rustcc}} --print-ast
+// RUN: ${{rustcc}} --print-ir
+
+extern _Bool is_ready(void);
+
+int helper(void) {
+    return 40;
+}
+
+int main(void) {
+    return helper() + is_ready() + 1;
+}
@@ -0,0 +1,11 @@
+// RUN: ${{rustcc}} --print-ast --print-ir
+
+int helper(void);
+
+int main(void) {
+    return helper();
+}
+
+int helper(void) {
+    return 2;
+}
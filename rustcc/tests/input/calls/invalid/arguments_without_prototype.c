@@ -0,0 +1,8 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int helper();
+
+int main(void) {
+    return helper(1, 2 + 3);
+}
@@ -0,0 +1,8 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+extern int f(void);
+
+int main(void) {
+    return f(1 2);
+}
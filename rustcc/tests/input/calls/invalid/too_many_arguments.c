@@ -0,0 +1,10 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int helper(void) {
+    return 1;
+}
+
+int main(void) {
+    return helper(1, 2 + 3);
+}
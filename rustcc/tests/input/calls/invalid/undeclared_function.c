@@ -0,0 +1,10 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int main(void) {
+    return helper();
+}
+
+int helper(void) {
+    return 1;
+}
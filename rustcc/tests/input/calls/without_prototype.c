@@ -0,0 +1,11 @@
+// RUN: ${{rustcc}} --print-ir
+
+int helper();
+
+int main(void) {
+    return helper();
+}
+
+int helper() {
+    return 3;
+}
@@ -0,0 +1,13 @@
+// RUN: ${{rustcc}} --print-ast --print-ir
+
+_Bool is_set(void) {
+    return 42;
+}
+
+_Bool is_clear(void) {
+    return 0;
+}
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,4 @@
+// RUN: ${{rustcc}} --print-ir
+
+int main(void) {
+}
@@ -0,0 +1,11 @@
+// RUN: ${{rustcc}} --print-ast --print-ir
+
+extern int answer(void);
+
+extern int helper(void) {
+    return 42;
+}
+
+int main(void) {
+    return 0;
+}
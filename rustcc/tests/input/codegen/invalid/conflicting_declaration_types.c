@@ -0,0 +1,9 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+extern int f(void);
+extern _Bool f(void);
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,12 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+extern _Bool f(void);
+
+int main(void) {
+    return 0;
+}
+
+int f(void) {
+    return 3;
+}
@@ -0,0 +1,10 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int main(void) {
+    return 0;
+}
+
+int main(void) {
+    return 1;
+}
@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --print-ir
+
+int main(void) {
+    return 1;
+    return 2;
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --print-source-map
+
+int main(void) {
+    return -(~1);
+}
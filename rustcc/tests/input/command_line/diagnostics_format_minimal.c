@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --fdiagnostics-format=minimal
+// EXPECT-FAILURE
+
+int main(void) {
+    return @;
+}
@@ -0,0 +1,18 @@
+// RUN: ${{rustcc}} --dump-scopes
+
+int helper(void) {
+    return 1;
+}
+
+int main(void) {
+    int x = helper();
+    {
+        volatile int y = x;
+        while (y) {
+            int z = y;
+            y = z - 1;
+        }
+    }
+    int w = 2;
+    return x + w;
+}
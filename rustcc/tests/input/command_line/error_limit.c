@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --ferror-limit=3
+// EXPECT-FAILURE
+
+int main(void) {
+    return @ @ @ @ @ @ @ @;
+}
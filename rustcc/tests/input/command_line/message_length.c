@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --fmessage-length=40 --fdiagnostics-format=minimal -Wstrict-prototypes
+
+int main() {
+    return 0;
+}
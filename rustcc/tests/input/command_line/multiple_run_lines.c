@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --print-tokens
+// RUN: ${{rustcc}} --print-ast
+
+int main(void) {
+    return 1;
+}
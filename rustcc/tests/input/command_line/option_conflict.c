@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} -Wstrict-prototypes -Wno-strict-prototypes -Wno-null-character -Wno-null-character
+
+int main() {
+    return 0;
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} -Werror -Wnull-character -Wno-null-character
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1 @@
+// RUN: ${{rustcc}} --print-diagnostic-flags
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --show-input
+
+int main(void) {
+    return 0;
+}
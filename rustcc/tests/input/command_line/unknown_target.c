@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --target=not-a-target
+// EXPECT-FAILURE
+
+int main(void) {
+    return 0;
+}
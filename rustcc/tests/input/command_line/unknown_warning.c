@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} -Wunknown-warning
+// EXPECT-FAILURE
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --print-tokens --print-ir
+
+int main(void) {
+    return 'A' + '\n' - '\0' + '\'' - '\\';
+}
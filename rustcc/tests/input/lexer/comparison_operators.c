@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --print-tokens
+
+int main(void) {
+    return 1<2 <= 3 > 4>=5 == 6 != 7;
+}
@@ -1,3 +1,4 @@
 // RUN: ${{rustcc}} --print-tokens
+// EXPECT-FAILURE
 
 /
\ No newline at end of file
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --fdigit-separators --print-tokens
+
+int main(void) {
+    return 1'000_000;
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --fdollars-in-identifiers --print-tokens
+
+int $main$(void) {
+    return 0;
+}
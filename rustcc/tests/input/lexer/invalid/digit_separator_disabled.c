@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --print-tokens
+// EXPECT-FAILURE
+
+int main(void) {
+    return 1'000;
+}
@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --print-tokens
+// EXPECT-FAILURE
+
+int ma$in(void) {
+    return 0;
+}
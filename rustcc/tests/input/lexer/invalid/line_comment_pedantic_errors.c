@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --std=c89 --pedantic-errors
+// EXPECT-FAILURE
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --fdigit-separators --print-tokens
+// EXPECT-FAILURE
+
+int main(void) {
+    return 1__000_;
+}
@@ -0,0 +1,4 @@
+// RUN: ${{rustcc}} --print-tokens
+// EXPECT-FAILURE
+
+register auto
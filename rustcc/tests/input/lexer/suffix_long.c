@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --print-tokens
+
+int main(void) {
+    return 10L;
+}
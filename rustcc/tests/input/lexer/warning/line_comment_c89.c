@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --std=c89
+// RUN: ${{rustcc}} --std=c99
+
+int main(void) {
+    return 0; /* A block comment is fine */
+}
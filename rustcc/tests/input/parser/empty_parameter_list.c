@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} --print-ast
+
+int main() {
+    return 0;
+}
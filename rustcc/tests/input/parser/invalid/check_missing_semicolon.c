@@ -0,0 +1,7 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+// CHECK: check_missing_semicolon.c:{{[0-9]+}}:{{[0-9]+}}: error: expected ';'
+
+int main(void) {
+    return 0
+}
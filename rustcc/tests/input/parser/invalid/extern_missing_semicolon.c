@@ -0,0 +1,8 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+extern int answer(void)
+
+int main(void) {
+    return 0;
+}
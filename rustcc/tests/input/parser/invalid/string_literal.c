@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --print-ast
+// EXPECT-FAILURE
+
+int main(void) {
+    return "con" "cat" "enated";
+}
@@ -0,0 +1,8 @@
+// RUN: ${{rustcc}} --print-ast
+
+int main(void) {
+    return 1;
+    {
+        return 2;
+    }
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} -Wstrict-prototypes -Wno-strict-prototypes -Wno-option-conflict
+
+int main() {
+    return 0;
+}
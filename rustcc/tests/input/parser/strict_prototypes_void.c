@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} -Wstrict-prototypes
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,7 @@
+// RUN: ${{rustcc}} --print-ast
+
+extern volatile int get(void);
+
+int volatile volatile main(void) {
+    return 0;
+}
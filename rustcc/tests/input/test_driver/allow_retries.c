@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}}
+// ALLOW-RETRIES: 2
+
+int main(void) {
+    return 0;
+}
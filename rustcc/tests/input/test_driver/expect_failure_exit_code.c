@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} -Wstrict-prototypes -Werror --werror-exit-code 3
+// EXPECT-FAILURE: 3
+
+int main() {
+    return 0;
+}
@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} -Wstrict-prototypes --print-ir
+
+int main() {
+    return 0;
+}
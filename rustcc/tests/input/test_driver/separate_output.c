@@ -0,0 +1,5 @@
+// RUN: ${{rustcc}} -Wstrict-prototypes --fdiagnostics-to-stderr --print-ast
+
+int main() {
+    return 0;
+}
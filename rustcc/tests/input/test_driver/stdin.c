@@ -0,0 +1,8 @@
+// RUN: ${{rustcc}} --print-ast
+// STDIN: int main(void) {
+// STDIN:     return 1;
+// STDIN: }
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} %s -o %t.ll
+// RUN: ${{cat}} %t.ll
+
+int main(void) {
+    return 0;
+}
@@ -0,0 +1,11 @@
+// RUN: ${{rustcc}} --print-ast
+
+int main(void) {
+    int x = 3;
+    _Bool is_set;
+    is_set = x = x + 1;
+    {
+        int x;
+    }
+    return x;
+}
@@ -0,0 +1,7 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int main(void) {
+    int = 1;
+    return 0;
+}
@@ -0,0 +1,8 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int main(void) {
+    int x = 1;
+    x + 1 = 2;
+    return x;
+}
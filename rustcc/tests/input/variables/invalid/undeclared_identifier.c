@@ -0,0 +1,9 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int main(void) {
+    {
+        int x = 1;
+    }
+    return x;
+}
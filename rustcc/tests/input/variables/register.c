@@ -0,0 +1,8 @@
+// RUN: ${{rustcc}} --print-ast --print-ir
+// RUN: ${{rustcc}} -Wdeprecated
+
+int main(void) {
+    register int x = 1;
+    auto int y = 2;
+    return x + y;
+}
@@ -0,0 +1,7 @@
+// RUN: ${{rustcc}} --print-ir
+
+int main(void) {
+    volatile int x = 1;
+    x = x + 1;
+    return x;
+}
@@ -0,0 +1,7 @@
+// RUN: ${{rustcc}}
+// EXPECT-FAILURE
+
+int main(void) {
+    while (0) int x = 2;
+    return x;
+}
@@ -0,0 +1,6 @@
+// RUN: ${{rustcc}} --print-ir
+
+int main(void) {
+    while (1)
+        return 2;
+}
@@ -0,0 +1,13 @@
+// RUN: ${{rustcc}} --print-ast
+// RUN: ${{rustcc}} --print-ir
+
+int main(void) {
+    int x = 3;
+    int sum = 0;
+    while (x) {
+        sum = sum + x;
+        x = x - 1;
+    }
+    while (0);
+    return sum;
+}
@@ -1,16 +1,649 @@
 use assert_cmd::{assert::OutputAssertExt, cargo::CommandCargoExt};
-use std::{path::PathBuf, process::Command};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+fn workspace_dir() -> PathBuf {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    manifest_dir.parent().unwrap().to_path_buf()
+}
+
+fn test_driver_command(workspace_dir: &Path) -> Command {
+    let mut command = Command::cargo_bin("test-driver").unwrap();
+    command
+        .current_dir(workspace_dir)
+        .arg("--directory")
+        .arg("rustcc/tests");
+
+    command
+}
 
 #[test]
 fn test_driver() {
-    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    let workspace_dir = manifest_dir.parent().unwrap();
+    test_driver_command(&workspace_dir()).assert().success();
+}
+
+#[test]
+fn test_driver_parallel_matches_sequential() {
+    let workspace_dir = workspace_dir();
+
+    let sequential = test_driver_command(&workspace_dir)
+        .args(["--jobs", "1"])
+        .output()
+        .unwrap();
+    let parallel = test_driver_command(&workspace_dir)
+        .args(["--jobs", "4"])
+        .output()
+        .unwrap();
+
+    assert!(sequential.status.success());
+    assert_eq!(sequential.status.code(), parallel.status.code());
+    assert_eq!(
+        String::from_utf8_lossy(&sequential.stdout),
+        String::from_utf8_lossy(&parallel.stdout)
+    );
+}
+
+#[test]
+fn test_driver_env_var_substitution() {
+    let workspace_dir = workspace_dir();
 
     Command::cargo_bin("test-driver")
         .unwrap()
-        .current_dir(workspace_dir)
+        .current_dir(&workspace_dir)
         .arg("--directory")
-        .arg("rustcc/tests")
+        .arg("rustcc/tests/fixtures/env_var")
+        .env("RUSTCC_TEST_FLAGS", "--print-ast")
         .assert()
         .success();
+
+    // Without the variable the test can't be run
+    Command::cargo_bin("test-driver")
+        .unwrap()
+        .current_dir(&workspace_dir)
+        .arg("--directory")
+        .arg("rustcc/tests/fixtures/env_var")
+        .env_remove("RUSTCC_TEST_FLAGS")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_driver_filter() {
+    let output = test_driver_command(&workspace_dir())
+        .args(["--filter", "lexer/suffix_"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Skipped "));
+
+    let tests = stdout
+        .lines()
+        .filter(|line| line.starts_with("Running test "))
+        .collect::<Vec<_>>();
+    assert_eq!(tests.len(), 3);
+    assert!(tests.iter().all(|test| test.contains("lexer/suffix_")));
+}
+
+#[test]
+fn test_driver_timings() {
+    let output = test_driver_command(&workspace_dir())
+        .args(["--filter", "lexer/suffix_", "--timings"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Slowest tests:"));
+    assert!(stdout.contains("Total time: "));
+    assert!(
+        stdout
+            .lines()
+            .skip_while(|line| *line != "Slowest tests:")
+            .any(|line| line.ends_with("suffix_long.c"))
+    );
+}
+
+const PASSING_SOURCE: &str = "// RUN: ${{rustcc}}\n\nint main(void) {\n    return 0;\n}\n";
+
+/// Creates a tests directory containing `tests` as pairs of file names and expected outputs
+fn create_tests_dir(tests: &[(&str, Option<&str>)]) -> tempfile::TempDir {
+    let tests_dir = tempfile::tempdir().unwrap();
+    let input_dir = tests_dir.path().join("input");
+    let output_dir = tests_dir.path().join("output");
+    fs::create_dir(&input_dir).unwrap();
+    fs::create_dir(&output_dir).unwrap();
+
+    for (name, expected_output) in tests {
+        fs::write(input_dir.join(name).with_extension("c"), PASSING_SOURCE).unwrap();
+
+        if let Some(expected_output) = expected_output {
+            fs::write(output_dir.join(name).with_extension("out"), expected_output).unwrap();
+        }
+    }
+
+    tests_dir
+}
+
+/// Copies the fixture `name` into a temporary directory, for tests which modify their baselines
+fn copy_fixture(name: &str) -> tempfile::TempDir {
+    let fixture_dir = workspace_dir().join("rustcc/tests/fixtures").join(name);
+    let tests_dir = tempfile::tempdir().unwrap();
+
+    for directory in ["input", "output"] {
+        fs::create_dir(tests_dir.path().join(directory)).unwrap();
+
+        for entry in fs::read_dir(fixture_dir.join(directory)).unwrap() {
+            let path = entry.unwrap().path();
+            fs::copy(
+                &path,
+                tests_dir
+                    .path()
+                    .join(directory)
+                    .join(path.file_name().unwrap()),
+            )
+            .unwrap();
+        }
+    }
+
+    tests_dir
+}
+
+fn test_driver_in(tests_dir: &Path) -> Command {
+    let mut command = Command::cargo_bin("test-driver").unwrap();
+    command.arg("--directory").arg(tests_dir);
+
+    command
+}
+
+#[test]
+fn test_driver_junit_report() {
+    // One passing test and one test without an expected output file
+    let tests_dir = create_tests_dir(&[("pass", Some("")), ("fail", None)]);
+    let report_path = tests_dir.path().join("report.xml");
+
+    let output = test_driver_in(tests_dir.path())
+        .arg("--junit")
+        .arg(&report_path)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+
+    let report = fs::read_to_string(report_path).unwrap();
+    assert_eq!(report.matches("<testcase ").count(), 2);
+    assert_eq!(report.matches("<failure ").count(), 1);
+    assert!(report.contains(r#"tests="2" failures="1""#));
+    assert!(report.contains("Expected output file"));
+}
+
+#[test]
+fn test_driver_update_baseline_dry_run_writes_nothing() {
+    let tests_dir = create_tests_dir(&[("stale", Some("stale output\n")), ("missing", None)]);
+    let output_dir = tests_dir.path().join("output");
+
+    let output = test_driver_in(tests_dir.path())
+        .args(["--update-baseline", "--dry-run"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert_eq!(stdout.matches("WOULD UPDATE").count(), 2);
+    assert!(stdout.contains("- stale output"));
+    assert_eq!(
+        fs::read_to_string(output_dir.join("stale.out")).unwrap(),
+        "stale output\n"
+    );
+    assert!(!output_dir.join("missing.out").exists());
+}
+
+#[test]
+fn test_driver_update_baseline_only_updates_failing_tests() {
+    // The extra whitespace is ignored when comparing, so this test passes
+    let tests_dir = create_tests_dir(&[("passing", Some("\n\n")), ("stale", Some("stale\n"))]);
+    let output_dir = tests_dir.path().join("output");
+
+    test_driver_in(tests_dir.path())
+        .arg("--update-baseline")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(output_dir.join("passing.out")).unwrap(),
+        "\n\n"
+    );
+    assert_eq!(
+        fs::read_to_string(output_dir.join("stale.out")).unwrap(),
+        ""
+    );
+
+    // With --force even passing tests are rewritten
+    test_driver_in(tests_dir.path())
+        .args(["--update-baseline", "--force"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(output_dir.join("passing.out")).unwrap(),
+        ""
+    );
+}
+
+#[test]
+fn test_driver_check_orphans() {
+    let output = test_driver_in(&workspace_dir().join("rustcc/tests/fixtures/orphans"))
+        .arg("--check-orphans")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("Orphaned expected output files:"));
+    assert!(stdout.contains("removed_test.out"));
+    assert!(!stdout.contains("output/test.out"));
+}
+
+#[test]
+fn test_driver_prune_orphans() {
+    let tests_dir = create_tests_dir(&[("test", Some(""))]);
+    let orphan_path = tests_dir.path().join("output").join("orphan.out");
+    fs::write(&orphan_path, "").unwrap();
+
+    test_driver_in(tests_dir.path())
+        .args(["--check-orphans", "--prune"])
+        .assert()
+        .success();
+
+    assert!(!orphan_path.exists());
+    assert!(tests_dir.path().join("output").join("test.out").exists());
+}
+
+#[test]
+fn test_driver_shuffle_same_seed_same_order() {
+    let run_order = |seed: &str| {
+        let output = test_driver_command(&workspace_dir())
+            .args(["--filter", "lexer/", "--shuffle", seed])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+
+        assert!(output.status.success());
+        assert!(stdout.contains(&format!("Shuffling tests with seed {seed}")));
+
+        stdout
+            .lines()
+            .filter(|line| line.starts_with("Running test "))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(run_order("1234"), run_order("1234"));
+}
+
+#[test]
+fn test_driver_fail_fast() {
+    // Both tests fail since they have no expected output file
+    let tests_dir = create_tests_dir(&[("a_first", None), ("b_second", None)]);
+
+    let output = test_driver_in(tests_dir.path())
+        .args(["--fail-fast", "--jobs", "1"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("a_first.c"));
+    assert!(!stdout.contains("b_second.c"));
+    assert!(stdout.contains("Stopped after the first failure, skipped 1 tests"));
+    assert!(stdout.contains("Reproduce with:"));
+    assert!(stdout.contains("Ran 1 tests"));
+
+    // Without --fail-fast both tests run
+    let output = test_driver_in(tests_dir.path()).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("a_first.c"));
+    assert!(stdout.contains("b_second.c"));
+}
+
+#[test]
+fn test_driver_compares_stderr_separately() {
+    let tests_dir = copy_fixture("stderr_split");
+    let error_path = tests_dir.path().join("output").join("test.err");
+
+    let output = test_driver_in(tests_dir.path()).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("Diff of 'test.err'"));
+    assert!(stdout.contains("- error: unexpected"));
+
+    // Updating the baseline rewrites the `.err` file with the actual stderr
+    test_driver_in(tests_dir.path())
+        .arg("--update-baseline")
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(error_path).unwrap(), "");
+}
+
+#[test]
+fn test_driver_verbose_prints_command_line() {
+    let tests_dir = create_tests_dir(&[("test", Some(""))]);
+    let input_path = tests_dir.path().join("input").join("test.c");
+
+    let output = test_driver_in(tests_dir.path())
+        .arg("--verbose")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Command: "));
+    assert!(stdout.contains(&format!("rustcc {}", input_path.display())));
+    assert!(stdout.contains("Working directory: "));
+
+    // Without --verbose the command line isn't printed
+    let output = test_driver_in(tests_dir.path()).output().unwrap();
+
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Command: "));
+}
+
+#[test]
+fn test_driver_normalizes_input_paths() {
+    // The expected output uses a placeholder for the input path, so the test passes no matter
+    // whether the tests directory is given as a relative or an absolute path
+    test_driver_command(&workspace_dir())
+        .args(["--filter", "test_driver/normalize_paths"])
+        .assert()
+        .success();
+
+    test_driver_in(&workspace_dir().join("rustcc/tests"))
+        .args(["--filter", "test_driver/normalize_paths"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_driver_retries_failing_tests() {
+    // Both tests always fail since they have no expected output file
+    let tests_dir = workspace_dir().join("rustcc/tests/fixtures/retries");
+
+    let output = test_driver_in(&tests_dir)
+        .args(["--retries", "2", "--filter", "always_fails"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("Failed after 3 attempts"));
+
+    // The directive of the test takes precedence over the command line
+    let output = test_driver_in(&tests_dir)
+        .args(["--retries", "2", "--filter", "allow_retries"])
+        .output()
+        .unwrap();
+
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Failed after 2 attempts"));
+}
+
+#[test]
+fn test_driver_flag_coverage() {
+    let tests_dir = create_tests_dir(&[("plain", Some("")), ("strict", Some(""))]);
+    fs::write(
+        tests_dir.path().join("input").join("strict.c"),
+        PASSING_SOURCE.replace("${{rustcc}}", "${{rustcc}} -Wno-strict-prototypes"),
+    )
+    .unwrap();
+
+    let output = test_driver_in(tests_dir.path())
+        .arg("--flag-coverage")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("Warning flags not covered by any test:"));
+    assert!(stdout.lines().any(|line| line == "-Wnull-character"));
+    assert!(!stdout.lines().any(|line| line == "-Wstrict-prototypes"));
+}
+
+#[test]
+fn test_driver_runner() {
+    let tests_dir = create_tests_dir(&[("test", Some(""))]);
+
+    let output = test_driver_in(tests_dir.path())
+        .args(["--runner", "env RUSTCC_RUNNER=1", "--verbose"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Command: env RUSTCC_RUNNER=1 "));
+    assert!(stdout.contains("rustcc "));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_driver_timeout() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tests_dir = create_tests_dir(&[("hang", Some(""))]);
+
+    // A runner which never runs the compiler, standing in for a compiler stuck in a loop
+    let runner_path = tests_dir.path().join("hang.sh");
+    fs::write(&runner_path, "#!/bin/sh\nexec sleep 30\n").unwrap();
+    fs::set_permissions(&runner_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = test_driver_in(tests_dir.path())
+        .arg("--runner")
+        .arg(&runner_path)
+        .args(["--timeout", "1"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("TIMEOUT"));
+    assert!(stdout.contains("Test timed out after 1 seconds"));
+    assert!(stdout.contains("1 of the failed tests timed out"));
+    assert!(stdout.contains("hang.c (timed out)"));
+}
+
+#[test]
+fn test_driver_json_summary() {
+    let tests_dir = create_tests_dir(&[("pass", Some("")), ("fail", None), ("other", Some(""))]);
+
+    let output = test_driver_in(tests_dir.path())
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(summary["total"], 3);
+    assert_eq!(summary["passed"], 2);
+    assert_eq!(summary["failed"], 1);
+    assert_eq!(summary["timed_out"], 0);
+
+    let failed_tests = summary["failed_tests"].as_array().unwrap();
+    assert_eq!(failed_tests.len(), 1);
+    assert!(failed_tests[0].as_str().unwrap().ends_with("fail.c"));
+}
+
+#[test]
+fn test_driver_json_summary_without_tests() {
+    let tests_dir = create_tests_dir(&[("test", Some(""))]);
+
+    let output = test_driver_in(tests_dir.path())
+        .args(["--format", "json", "--filter", "does_not_exist"])
+        .output()
+        .unwrap();
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    // The message goes to stderr so stdout only holds the empty summary
+    assert!(!output.status.success());
+    assert_eq!(summary["total"], 0);
+    assert_eq!(summary["failed_tests"], serde_json::json!([]));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("No test files found in "));
+}
+
+#[test]
+fn test_driver_json_summary_with_reports() {
+    let tests_dir = create_tests_dir(&[("pass", Some(""))]);
+    fs::write(tests_dir.path().join("output/orphan.out"), "").unwrap();
+
+    let output = test_driver_in(tests_dir.path())
+        .args(["--format", "json", "--timings", "--check-orphans"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    // The reports go to stderr, so stdout is only the JSON summary
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["passed"], 1);
+    assert!(stderr.contains("Slowest tests:"));
+    assert!(stderr.contains("Orphaned expected output files:"));
+    assert!(stderr.contains("orphan.out"));
+}
+
+#[test]
+fn test_driver_multiple_directories() {
+    let first_tests_dir = create_tests_dir(&[("first", Some(""))]);
+    let second_tests_dir = create_tests_dir(&[("second", Some(""))]);
+
+    // Passing the directory option multiple times
+    let output = test_driver_in(first_tests_dir.path())
+        .arg("--directory")
+        .arg(second_tests_dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Found 2 test files"));
+    assert!(stdout.contains("first.c... PASS"));
+    assert!(stdout.contains("second.c... PASS"));
+
+    // Passing multiple values at once, like a shell glob expands to
+    let output = Command::cargo_bin("test-driver")
+        .unwrap()
+        .arg("--directory")
+        .arg(first_tests_dir.path())
+        .arg(second_tests_dir.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Found 2 test files"));
+}
+
+#[test]
+fn test_driver_missing_executable() {
+    let output = Command::cargo_bin("test-driver")
+        .unwrap()
+        .current_dir(workspace_dir())
+        .arg("--directory")
+        .arg("rustcc/tests/fixtures/missing_executable")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("Executables: rustc-c, rustcc"));
+    assert!(stdout.contains("executable 'rustc-c' referenced by 1 tests is not a cargo binary"));
+    assert!(!stdout.contains("executable 'rustcc' referenced"));
+}
+
+#[test]
+fn test_driver_xfail() {
+    // A failing XFAIL test is a known failure and doesn't fail the run
+    let output = Command::cargo_bin("test-driver")
+        .unwrap()
+        .current_dir(workspace_dir())
+        .args(["--directory", "rustcc/tests/fixtures/xfail"])
+        .args(["--filter", "unsupported"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("unsupported.c... XFAIL"));
+    assert!(stdout.contains("1 tests failed as expected (XFAIL)"));
+
+    // A passing XFAIL test is reported loudly so the XFAIL line gets removed
+    let output = Command::cargo_bin("test-driver")
+        .unwrap()
+        .current_dir(workspace_dir())
+        .args(["--directory", "rustcc/tests/fixtures/xfail"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("supported.c... XPASS"));
+    assert!(stdout.contains("The test is marked XFAIL but passed"));
+    assert!(stdout.contains("unsupported.c... XFAIL"));
+}
+
+#[test]
+fn test_driver_cache() {
+    let tests_dir = create_tests_dir(&[("first", Some("")), ("second", Some(""))]);
+    let cache_dir = tests_dir.path().join("cache");
+
+    // Run a copy of rustcc so its modification time can be changed
+    let rustcc_path = tests_dir
+        .path()
+        .join(format!("rustcc{}", std::env::consts::EXE_SUFFIX));
+    fs::copy(env!("CARGO_BIN_EXE_rustcc"), &rustcc_path).unwrap();
+
+    let run = || {
+        let output = test_driver_in(tests_dir.path())
+            .arg("--cache")
+            .arg(&cache_dir)
+            .env("CARGO_BIN_EXE_rustcc", &rustcc_path)
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let stdout = run();
+    assert!(stdout.contains("first.c... PASS"));
+    assert!(stdout.contains("second.c... PASS"));
+
+    // Nothing changed, so both tests are skipped
+    let stdout = run();
+    assert!(stdout.contains("first.c... CACHED"));
+    assert!(stdout.contains("second.c... CACHED"));
+    assert!(stdout.contains("Skipped 2 unchanged tests which passed before"));
+
+    // Only the changed test runs again
+    fs::write(
+        tests_dir.path().join("input").join("first.c"),
+        format!("{PASSING_SOURCE}\n"),
+    )
+    .unwrap();
+
+    let stdout = run();
+    assert!(stdout.contains("first.c... PASS"));
+    assert!(stdout.contains("second.c... CACHED"));
+
+    // A rebuilt compiler invalidates all tests
+    let rustcc_file = fs::File::options().write(true).open(&rustcc_path).unwrap();
+    let modified = rustcc_file.metadata().unwrap().modified().unwrap() + Duration::from_secs(60);
+    rustcc_file.set_modified(modified).unwrap();
+    drop(rustcc_file);
+
+    let stdout = run();
+    assert!(stdout.contains("first.c... PASS"));
+    assert!(stdout.contains("second.c... PASS"));
 }
@@ -0,0 +1,81 @@
+#![cfg(feature = "serde")]
+
+use rustcc::{
+    ast::TranslationUnit,
+    diagnostic_consumer::IgnoreDiagnosticConsumer,
+    diagnostic_engine::DiagnosticEngine,
+    lexer::Lexer,
+    parser::Parser,
+    source_file::SourceFile,
+    source_location::SourceLocation,
+    token::{Token, TokenKind},
+};
+use std::{cell::RefCell, rc::Rc};
+
+const SOURCE: &str = "int main(void) {\n    return -(~2);\n}\n";
+
+fn diagnostic_engine() -> Rc<RefCell<DiagnosticEngine>> {
+    Rc::new(RefCell::new(DiagnosticEngine::new(Box::new(
+        IgnoreDiagnosticConsumer,
+    ))))
+}
+
+#[test]
+fn serde_round_trip_translation_unit() {
+    let source_file = SourceFile::new("test.c", SOURCE);
+    let diagnostic_engine = diagnostic_engine();
+    let tokens = Lexer::new(diagnostic_engine.clone(), &source_file).tokenize();
+    let mut parser = Parser::new(diagnostic_engine, tokens);
+    let translation_unit = parser.parse().translation_unit;
+
+    let json = serde_json::to_string(&translation_unit).unwrap();
+    let deserialized: TranslationUnit = serde_json::from_str(&json).unwrap();
+
+    // The source file is not serialized, so compare the dumps which only contain lines and columns
+    assert_eq!(deserialized.dump(), translation_unit.dump());
+}
+
+#[test]
+fn serde_round_trip_tokens() {
+    let source_file = SourceFile::new("test.c", SOURCE);
+    let tokens = Lexer::new(diagnostic_engine(), &source_file).tokenize();
+
+    let json = serde_json::to_string(&tokens).unwrap();
+    let deserialized: Vec<Token> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(deserialized.len(), tokens.len());
+    for (deserialized, token) in deserialized.iter().zip(&tokens) {
+        assert_eq!(deserialized.kind, token.kind);
+        assert_eq!(deserialized.range.begin.index, token.range.begin.index);
+        assert_eq!(deserialized.range.begin.line, token.range.begin.line);
+        assert_eq!(deserialized.range.begin.column, token.range.begin.column);
+        assert_eq!(deserialized.range.end.index, token.range.end.index);
+        assert_eq!(deserialized.range.end.line, token.range.end.line);
+        assert_eq!(deserialized.range.end.column, token.range.end.column);
+        assert_eq!(deserialized.range.begin.source_file, None);
+    }
+}
+
+#[test]
+fn serde_source_location_skips_source_file() {
+    let source_file = SourceFile::new("test.c", SOURCE);
+    let location = SourceLocation::new(&source_file, 4, 1, 5);
+
+    let json = serde_json::to_string(&location).unwrap();
+    assert_eq!(json, r#"{"index":4,"line":1,"column":5}"#);
+
+    let deserialized: SourceLocation = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        deserialized,
+        SourceLocation {
+            source_file: None,
+            ..location
+        }
+    );
+}
+
+#[test]
+fn serde_token_kind() {
+    let json = serde_json::to_string(&TokenKind::Identifier("main".to_string())).unwrap();
+    assert_eq!(json, r#"{"Identifier":"main"}"#);
+}
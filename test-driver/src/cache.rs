@@ -0,0 +1,128 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    hash::Hasher,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The file in the cache directory storing the keys of the passed tests
+const CACHE_FILE_NAME: &str = "test-driver-cache";
+
+/// A 64-bit FNV-1a hasher. Unlike the hasher of the standard library its output is specified, so
+/// the keys stay valid across builds of the test-driver.
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xCBF2_9CE4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01B3);
+        }
+    }
+}
+
+/// Remembers the key of every test which passed in a previous run, a test whose key didn't change
+/// since doesn't have to run again
+pub struct TestCache {
+    path: PathBuf,
+    keys: BTreeMap<PathBuf, u64>,
+}
+
+impl TestCache {
+    /// Loads the cache from the directory, a missing or unreadable cache file is an empty cache
+    pub fn load(directory: &Path) -> Self {
+        let path = directory.join(CACHE_FILE_NAME);
+        let keys = fs::read_to_string(&path)
+            .map(|content| parse_keys(&content))
+            .unwrap_or_default();
+
+        Self { path, keys }
+    }
+
+    /// Whether the test passed in a previous run with the same key
+    pub fn contains(&self, input_path: &Path, key: u64) -> bool {
+        self.keys.get(input_path) == Some(&key)
+    }
+
+    /// Records the key of a passed test or forgets the test if it failed, so it runs again
+    pub fn update(&mut self, input_path: &Path, key: Option<u64>) {
+        match key {
+            Some(key) => self.keys.insert(input_path.to_path_buf(), key),
+            None => self.keys.remove(input_path),
+        };
+    }
+
+    /// Writes the cache back into its directory, creating the directory if needed
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        let content = self
+            .keys
+            .iter()
+            .map(|(input_path, key)| format!("{key:016x} {}\n", input_path.display()))
+            .collect::<String>();
+
+        fs::write(&self.path, content)
+    }
+}
+
+/// Parses the `<key> <input path>` lines of a cache file, skipping malformed lines
+fn parse_keys(content: &str) -> BTreeMap<PathBuf, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, input_path) = line.split_once(' ')?;
+            let key = u64::from_str_radix(key, 16).ok()?;
+
+            Some((PathBuf::from(input_path), key))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::Hash;
+
+    #[test]
+    fn test_stable_hasher() {
+        let mut hasher = StableHasher::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xAF63_DC4C_8601_EC8C);
+
+        let hash = |value: &str| {
+            let mut hasher = StableHasher::default();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash("int main(void)"), hash("int main(void)"));
+        assert_ne!(hash("return 1;"), hash("return 2;"));
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let keys =
+            parse_keys("00000000000000ff input/a b.c\nnot a key\n0000000000000001 input/c.c\n");
+
+        assert_eq!(
+            keys.into_iter().collect::<Vec<_>>(),
+            [
+                (PathBuf::from("input/a b.c"), 0xFF),
+                (PathBuf::from("input/c.c"), 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use colored::Colorize;
+use std::fmt::Write;
+
+/// A single line of a line-by-line diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Unchanged(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Computes a line-by-line diff turning `expected` into `actual`, based on their longest common
+/// subsequence of lines
+pub fn line_diff<'a>(expected: &'a str, actual: &'a str) -> Vec<DiffLine<'a>> {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+
+    // lengths[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut lengths = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() && j < actual.len() {
+        if expected[i] == actual[j] {
+            diff.push(DiffLine::Unchanged(expected[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            diff.push(DiffLine::Removed(expected[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(actual[j]));
+            j += 1;
+        }
+    }
+    diff.extend(expected[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(actual[j..].iter().map(|line| DiffLine::Added(line)));
+
+    diff
+}
+
+/// Formats a colored diff, removed lines are prefixed with `-` and added lines with `+`
+pub fn format_diff(expected: &str, actual: &str) -> String {
+    let mut output = String::new();
+
+    for line in line_diff(expected, actual) {
+        let _ = match line {
+            DiffLine::Unchanged(line) => writeln!(output, "  {line}"),
+            DiffLine::Removed(line) => writeln!(output, "{}", format!("- {line}").red()),
+            DiffLine::Added(line) => writeln!(output, "{}", format!("+ {line}").green()),
+        };
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        let diff = line_diff("a\nb\nc\n", "a\nx\nc\nd\n");
+
+        assert_eq!(
+            diff,
+            [
+                DiffLine::Unchanged("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("x"),
+                DiffLine::Unchanged("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_diff_identical() {
+        let diff = line_diff("a\nb\n", "a\nb\n");
+
+        assert!(
+            diff.iter()
+                .all(|line| matches!(line, DiffLine::Unchanged(_)))
+        );
+    }
+
+    #[test]
+    fn test_format_diff_marks_differing_line() {
+        let diff = format_diff(
+            "error: expected ';'\nerror: expected '}'\n",
+            "error: expected ';'\nerror: expected ')'\n",
+        );
+
+        assert!(diff.contains("  error: expected ';'"));
+        assert!(diff.contains("- error: expected '}'"));
+        assert!(diff.contains("+ error: expected ')'"));
+    }
+}
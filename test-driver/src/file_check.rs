@@ -0,0 +1,123 @@
+use regex::{Regex, RegexBuilder};
+use std::sync::LazyLock;
+
+static CHECK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    RegexBuilder::new(r"^//\s*CHECK:\s*(.*?)\s*$")
+        .multi_line(true)
+        .build()
+        .expect("Failed to build regex")
+});
+
+static PATTERN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    RegexBuilder::new(r"\{\{(.*?)\}\}")
+        .build()
+        .expect("Failed to build regex")
+});
+
+/// A single `// CHECK: <pattern>` line of a test input.
+///
+/// The pattern is matched literally, except for `{{regex}}` blocks which are matched as regular
+/// expressions, like in LLVM's FileCheck.
+#[derive(Debug, Clone)]
+pub struct CheckDirective {
+    pub pattern: String,
+    regex: Regex,
+}
+
+impl CheckDirective {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let mut regex = String::new();
+        let mut last_end = 0;
+
+        for captures in PATTERN_REGEX.captures_iter(pattern) {
+            let whole_match = captures.get(0).unwrap();
+
+            regex.push_str(&regex::escape(&pattern[last_end..whole_match.start()]));
+            regex.push_str(&format!("(?:{})", &captures[1]));
+            last_end = whole_match.end();
+        }
+        regex.push_str(&regex::escape(&pattern[last_end..]));
+
+        let regex = Regex::new(&regex)
+            .map_err(|error| format!("Invalid check pattern '{pattern}': {error}"))?;
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    /// Parses all check directives of a test input in the order they appear
+    pub fn parse_all(input: &str) -> Result<Vec<Self>, String> {
+        CHECK_REGEX
+            .captures_iter(input)
+            .filter_map(|captures| captures.get(1))
+            .map(|m| Self::parse(m.as_str()))
+            .collect()
+    }
+}
+
+/// Matches all `checks` against `output` in order, every check has to match after the previous one
+pub fn check_output(checks: &[CheckDirective], output: &str) -> Result<(), String> {
+    let mut position = 0;
+
+    for check in checks {
+        let Some(found) = check.regex.find_at(output, position) else {
+            return Err(format!(
+                "Expected string not found in output\nCHECK: {}",
+                check.pattern
+            ));
+        };
+
+        position = found.end();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks(input: &str) -> Vec<CheckDirective> {
+        CheckDirective::parse_all(input).unwrap()
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let checks = checks("// CHECK: first\n//CHECK:   second  \nint x;\n");
+
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].pattern, "first");
+        assert_eq!(checks[1].pattern, "second");
+    }
+
+    #[test]
+    fn test_matches_in_order() {
+        let checks = checks("// CHECK: error: a\n// CHECK: error: b\n");
+
+        assert!(check_output(&checks, "error: a\nerror: b\n").is_ok());
+        assert!(check_output(&checks, "error: b\nerror: a\n").is_err());
+    }
+
+    #[test]
+    fn test_pattern_is_literal() {
+        let checks = checks("// CHECK: expected ';' (.)\n");
+
+        assert!(check_output(&checks, "expected ';' (.)").is_ok());
+        assert!(check_output(&checks, "expected ';' (x)").is_err());
+    }
+
+    #[test]
+    fn test_regex_block() {
+        let checks = checks("// CHECK: test.c:{{[0-9]+}}:{{[0-9]+}}: error\n");
+
+        assert!(check_output(&checks, "test.c:4:27: error").is_ok());
+        assert!(check_output(&checks, "test.c:x:27: error").is_err());
+    }
+
+    #[test]
+    fn test_invalid_regex_block() {
+        assert!(CheckDirective::parse("{{(}}").is_err());
+    }
+}
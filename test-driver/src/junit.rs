@@ -0,0 +1,124 @@
+use regex::Regex;
+use std::{fmt::Write, sync::LazyLock, time::Duration};
+
+static ANSI_ESCAPE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").expect("Failed to build regex"));
+
+/// The result of a single test as reported in a JUnit XML report
+#[derive(Debug, Clone)]
+pub struct JunitTestCase {
+    pub name: String,
+    pub duration: Duration,
+    /// The reason the test failed, `None` if it passed
+    pub failure: Option<String>,
+}
+
+/// Formats the test cases as a JUnit XML report with a single test suite
+pub fn junit_report(suite_name: &str, test_cases: &[JunitTestCase], duration: Duration) -> String {
+    let number_of_failures = test_cases
+        .iter()
+        .filter(|test_case| test_case.failure.is_some())
+        .count();
+
+    let mut report = String::new();
+    let _ = writeln!(report, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(report, "<testsuites>");
+    let _ = writeln!(
+        report,
+        r#"  <testsuite name="{}" tests="{}" failures="{}" time="{:.3}">"#,
+        escape_xml(suite_name),
+        test_cases.len(),
+        number_of_failures,
+        duration.as_secs_f64()
+    );
+
+    for test_case in test_cases {
+        let _ = write!(
+            report,
+            r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+            escape_xml(&test_case.name),
+            escape_xml(suite_name),
+            test_case.duration.as_secs_f64()
+        );
+
+        match &test_case.failure {
+            Some(failure) => {
+                let _ = writeln!(report, ">");
+                let _ = writeln!(
+                    report,
+                    r#"      <failure message="Test failed">{}</failure>"#,
+                    escape_xml(failure.trim())
+                );
+                let _ = writeln!(report, "    </testcase>");
+            }
+            None => {
+                let _ = writeln!(report, "/>");
+            }
+        }
+    }
+
+    let _ = writeln!(report, "  </testsuite>");
+    let _ = writeln!(report, "</testsuites>");
+
+    report
+}
+
+/// Escapes a string so it can be used as XML text or attribute value. Colors are removed and other
+/// control characters are dropped since they are not allowed in XML.
+fn escape_xml(string: &str) -> String {
+    let string = ANSI_ESCAPE_REGEX.replace_all(string, "");
+    let mut escaped = String::with_capacity(string.len());
+
+    for character in string.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\t' | '\r' => escaped.push(character),
+            character if character.is_control() => {}
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_junit_report() {
+        let test_cases = [
+            JunitTestCase {
+                name: "pass.c".to_string(),
+                duration: Duration::from_millis(12),
+                failure: None,
+            },
+            JunitTestCase {
+                name: "fail.c".to_string(),
+                duration: Duration::from_millis(3),
+                failure: Some("Expected output file 'fail.out' not found\n".to_string()),
+            },
+        ];
+
+        let report = junit_report("rustcc", &test_cases, Duration::from_millis(15));
+
+        assert!(
+            report.contains(r#"<testsuite name="rustcc" tests="2" failures="1" time="0.015">"#)
+        );
+        assert!(report.contains(r#"<testcase name="pass.c" classname="rustcc" time="0.012"/>"#));
+        assert!(report.contains(
+            r#"<failure message="Test failed">Expected output file &apos;fail.out&apos; not found</failure>"#
+        ));
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml(r#"<a & "b">"#), "&lt;a &amp; &quot;b&quot;&gt;");
+        assert_eq!(escape_xml("\x1b[31mFAIL\x1b[0m\n"), "FAIL\n");
+        assert_eq!(escape_xml("a\u{1}b"), "ab");
+    }
+}
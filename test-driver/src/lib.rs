@@ -0,0 +1,3 @@
+//! The parts of the test-driver which are shared with the tests of the other crates
+
+pub mod run_directive;
@@ -1,15 +1,196 @@
 use assert_cmd::cargo::CommandCargoExt;
+use cache::{StableHasher, TestCache};
 use clap::ArgAction;
 use colored::Colorize;
-use regex::RegexBuilder;
+use file_check::CheckDirective;
+use junit::JunitTestCase;
+use regex::{Regex, RegexBuilder};
 use std::{
-    env, fs,
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+    env,
+    fmt::Write,
+    fs,
+    hash::{Hash, Hasher},
+    io, iter,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
-    process,
+    process::{self, Stdio},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
 };
+use test_driver::run_directive::{self, RunDirective};
+
+mod cache;
+mod diff;
+mod file_check;
+mod junit;
+mod shuffle;
+mod summary;
 
 const ARG_DIRECTORY: &str = "DIRECTORY";
 const ARG_UPDATE_BASELINE: &str = "UPDATE_BASELINE";
+const ARG_DRY_RUN: &str = "DRY_RUN";
+const ARG_FORCE: &str = "FORCE";
+const ARG_JOBS: &str = "JOBS";
+const ARG_FILTER: &str = "FILTER";
+const ARG_TIMINGS: &str = "TIMINGS";
+const ARG_JUNIT: &str = "JUNIT";
+const ARG_CHECK_ORPHANS: &str = "CHECK_ORPHANS";
+const ARG_PRUNE: &str = "PRUNE";
+const ARG_SHUFFLE: &str = "SHUFFLE";
+const ARG_FAIL_FAST: &str = "FAIL_FAST";
+const ARG_VERBOSE: &str = "VERBOSE";
+const ARG_RETRIES: &str = "RETRIES";
+const ARG_FLAG_COVERAGE: &str = "FLAG_COVERAGE";
+const ARG_RUNNER: &str = "RUNNER";
+const ARG_TIMEOUT: &str = "TIMEOUT";
+const ARG_FORMAT: &str = "FORMAT";
+const ARG_CACHE: &str = "CACHE";
+
+const FORMAT_HUMAN: &str = "human";
+const FORMAT_JSON: &str = "json";
+
+/// The executable of run directives which is implemented by the test-driver itself, it prints the
+/// content of the files passed as arguments like the `cat` of lit's internal shell
+const BUILTIN_CAT: &str = "cat";
+
+/// How many of the slowest tests are reported with `--timings`
+const NUMBER_OF_SLOWEST_TESTS: usize = 10;
+
+/// A tests directory with the test inputs in `input` and their expected output in `output`
+struct TestDirectory {
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+}
+
+impl TestDirectory {
+    fn new(directory: &Path) -> Self {
+        Self {
+            input_dir: directory.join("input"),
+            output_dir: directory.join("output"),
+        }
+    }
+}
+
+/// Everything needed to run a single test
+struct TestContext {
+    directories: Vec<TestDirectory>,
+    update_baseline: bool,
+    /// Only report which baselines would be updated
+    dry_run: bool,
+    /// Also rewrite baselines which already match the output
+    force: bool,
+    /// Stop running tests after the first failure
+    fail_fast: bool,
+    /// Print the command line of every run
+    verbose: bool,
+    /// How often a failing test is run again, unless the test sets its own `// ALLOW-RETRIES:`
+    retries: u32,
+    /// A wrapper command like `valgrind --error-exitcode=1` every executable is run under
+    runner: Vec<String>,
+    /// Every run taking longer than this is killed and the test reported as timed out
+    timeout: Option<Duration>,
+    /// The directory containing the `%t` paths of all tests, removed once all tests have run
+    temp_dir: PathBuf,
+    /// The tests which passed in a previous run, unchanged ones are skipped
+    cache: Option<TestCache>,
+    expect_failure_regex: Regex,
+}
+
+impl TestContext {
+    /// Returns the tests directory containing the input file and the path of the input relative to
+    /// its input directory
+    fn relative_input_path<'a>(&self, input_path: &'a Path) -> (usize, &'a Path) {
+        self.directories
+            .iter()
+            .enumerate()
+            .find_map(|(index, directory)| {
+                input_path
+                    .strip_prefix(&directory.input_dir)
+                    .ok()
+                    .map(|relative_path| (index, relative_path))
+            })
+            .expect("Failed to strip prefix")
+    }
+
+    /// Returns the path of the expected output file of the test, in the output directory of the
+    /// tests directory containing the input and preserving the directory structure
+    fn output_path(&self, input_path: &Path) -> PathBuf {
+        let (directory_index, relative_path) = self.relative_input_path(input_path);
+
+        self.directories[directory_index]
+            .output_dir
+            .join(relative_path)
+            .with_extension("out")
+    }
+
+    /// Returns the path `%t` expands to for the test, unique to the test and this test-driver run
+    fn temp_path(&self, input_path: &Path) -> PathBuf {
+        let (directory_index, relative_path) = self.relative_input_path(input_path);
+
+        self.temp_dir
+            .join(directory_index.to_string())
+            .join(relative_path)
+            .with_extension("tmp")
+    }
+}
+
+/// How a single test or run directive ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TestOutcome {
+    Passed,
+    Failed,
+    /// A run was killed after exceeding the `--timeout`
+    TimedOut,
+}
+
+/// The exit status a test expects from every run directive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExpectedStatus {
+    Success,
+    /// `// EXPECT-FAILURE`, any nonzero exit code
+    AnyFailure,
+    /// `// EXPECT-FAILURE: <code>`
+    ExitCode(i32),
+}
+
+impl ExpectedStatus {
+    fn from_input(expect_failure_regex: &Regex, input: &str) -> Result<Self, String> {
+        let Some(captures) = expect_failure_regex.captures(input) else {
+            return Ok(ExpectedStatus::Success);
+        };
+
+        match captures.get(1) {
+            Some(code) => code
+                .as_str()
+                .parse()
+                .map(ExpectedStatus::ExitCode)
+                .map_err(|_| format!("Invalid exit code '{}' in EXPECT-FAILURE", code.as_str())),
+            None => Ok(ExpectedStatus::AnyFailure),
+        }
+    }
+}
+
+/// The buffered output of a single test, which is printed once all tests have finished so the
+/// output stays deterministic when running tests in parallel
+struct TestResult {
+    /// Everything printed after the "Running test" line
+    log: String,
+    passed: bool,
+    timed_out: bool,
+    /// The test is marked `// XFAIL` and failed as expected, it counts as passed
+    expected_failure: bool,
+    /// The test passed in a previous run and was skipped since nothing changed
+    cached: bool,
+    /// The key the `--cache` records for the test if it passed, `None` if it can't be cached
+    cache_key: Option<u64>,
+    duration: Duration,
+}
 
 fn main() {
     let command_line = clap::Command::new(env!("CARGO_PKG_NAME"))
@@ -20,15 +201,143 @@ fn main() {
             clap::Arg::new(ARG_DIRECTORY)
                 .short('d')
                 .long("directory")
-                .help("The tests directory")
-                .action(ArgAction::Set)
+                .help("The tests directories, e.g. from a shell glob, the tests of all of them are run")
+                .action(ArgAction::Append)
+                .num_args(1..)
                 .required(true),
         )
         .arg(
             clap::Arg::new(ARG_UPDATE_BASELINE)
                 .short('u')
                 .long("update-baseline")
-                .help("update the expected output files instead of running tests")
+                .help("update the expected output files of failing tests instead of running tests")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new(ARG_DRY_RUN)
+                .long("dry-run")
+                .help("Print which expected output files would be updated without writing them")
+                .requires(ARG_UPDATE_BASELINE)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new(ARG_FORCE)
+                .long("force")
+                .help("Update all expected output files, even the ones which already match")
+                .requires(ARG_UPDATE_BASELINE)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new(ARG_JOBS)
+                .short('j')
+                .long("jobs")
+                .help("The number of tests to run in parallel, defaults to the number of CPUs")
+                .value_parser(clap::value_parser!(NonZeroUsize))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            clap::Arg::new(ARG_RETRIES)
+                .long("retries")
+                .value_name("count")
+                .help("Run failing tests again up to count times before reporting them as failed")
+                .value_parser(clap::value_parser!(u32))
+                .default_value("0")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            clap::Arg::new(ARG_FILTER)
+                .short('f')
+                .long("filter")
+                .value_name("regex")
+                .help("Only run the tests whose path matches the regex")
+                .value_parser(|filter: &str| Regex::new(filter).map_err(|error| error.to_string()))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            clap::Arg::new(ARG_TIMINGS)
+                .long("timings")
+                .help("Print the total time and the slowest tests")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new(ARG_JUNIT)
+                .long("junit")
+                .value_name("file")
+                .help("Write the test results as a JUnit XML report to the file")
+                .value_parser(clap::value_parser!(PathBuf))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            clap::Arg::new(ARG_CHECK_ORPHANS)
+                .long("check-orphans")
+                .help("Report expected output files which have no corresponding test")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new(ARG_PRUNE)
+                .long("prune")
+                .help("Delete the orphaned expected output files")
+                .requires(ARG_CHECK_ORPHANS)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new(ARG_SHUFFLE)
+                .long("shuffle")
+                .value_name("seed")
+                .help("Run the tests in a random order, a seed reproduces a previous order")
+                .value_parser(clap::value_parser!(u64))
+                .num_args(0..=1),
+        )
+        .arg(
+            clap::Arg::new(ARG_FAIL_FAST)
+                .long("fail-fast")
+                .help("Stop at the first failing test and print the command to reproduce it")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new(ARG_RUNNER)
+                .long("runner")
+                .value_name("command")
+                .help("Run every executable under a wrapper, e.g. 'valgrind --error-exitcode=1'")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            clap::Arg::new(ARG_TIMEOUT)
+                .long("timeout")
+                .value_name("secs")
+                .help("Kill every run taking longer than secs seconds and report the test as timed out")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            clap::Arg::new(ARG_CACHE)
+                .long("cache")
+                .value_name("dir")
+                .help("Skip tests which passed before if neither they nor the executables they run changed, the results are stored in dir")
+                .value_parser(clap::value_parser!(PathBuf))
+                .conflicts_with(ARG_UPDATE_BASELINE)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            clap::Arg::new(ARG_FLAG_COVERAGE)
+                .long("flag-coverage")
+                .help("Report warning flags of rustcc which no test passes to '-W'")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            clap::Arg::new(ARG_FORMAT)
+                .long("format")
+                .value_name("format")
+                .help("The format of the results, 'json' only prints a summary object for scripts")
+                .value_parser([FORMAT_HUMAN, FORMAT_JSON])
+                .default_value(FORMAT_HUMAN)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            clap::Arg::new(ARG_VERBOSE)
+                .short('v')
+                .long("verbose")
+                .help("Print the command line and working directory of every run")
                 .action(ArgAction::SetTrue),
         )
         .arg_required_else_help(true);
@@ -37,184 +346,1098 @@ fn main() {
     let matches = command_line.get_matches();
 
     // Extract arguments
-    let directory: &String = matches.get_one(ARG_DIRECTORY).unwrap();
+    let directories = matches
+        .get_many::<String>(ARG_DIRECTORY)
+        .unwrap()
+        .collect::<Vec<_>>();
+    let directories_list = directories
+        .iter()
+        .map(|directory| format!("'{directory}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
     let update_baseline = matches.get_flag(ARG_UPDATE_BASELINE);
+    let jobs = matches
+        .get_one::<NonZeroUsize>(ARG_JOBS)
+        .copied()
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+    let human_format = matches.get_one::<String>(ARG_FORMAT).unwrap() == FORMAT_HUMAN;
 
-    let input_dir = Path::new(&directory).join("input");
-    let output_dir = Path::new(&directory).join("output");
+    let mut context = TestContext {
+        directories: directories
+            .iter()
+            .map(|directory| TestDirectory::new(Path::new(directory)))
+            .collect(),
+        update_baseline,
+        dry_run: matches.get_flag(ARG_DRY_RUN),
+        force: matches.get_flag(ARG_FORCE),
+        fail_fast: matches.get_flag(ARG_FAIL_FAST),
+        verbose: matches.get_flag(ARG_VERBOSE),
+        retries: *matches.get_one(ARG_RETRIES).unwrap(),
+        runner: matches
+            .get_one::<String>(ARG_RUNNER)
+            .map(|runner| runner.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default(),
+        timeout: matches
+            .get_one::<u64>(ARG_TIMEOUT)
+            .map(|secs| Duration::from_secs(*secs)),
+        temp_dir: env::temp_dir().join(format!("rustcc-test-driver-{}", process::id())),
+        cache: matches
+            .get_one::<PathBuf>(ARG_CACHE)
+            .map(|directory| TestCache::load(directory)),
+        expect_failure_regex: RegexBuilder::new(r"^//\s*EXPECT-FAILURE(?::\s*(\S+))?\s*$")
+            .multi_line(true)
+            .build()
+            .expect("Failed to build regex"),
+    };
 
-    // Recursively find all `.c` files in the input directory
-    let input_files = find_c_files(&input_dir);
+    // Recursively find all `.c` files in the input directories, a file is only run once even if
+    // the same directory was given twice
+    let mut input_files = context
+        .directories
+        .iter()
+        .flat_map(|directory| find_files(&directory.input_dir, "c"))
+        .collect::<Vec<_>>();
+    input_files.sort();
+    input_files.dedup();
+
+    // Only keep the tests matching the filter
+    let number_of_found_files = input_files.len();
+    if let Some(filter) = matches.get_one::<Regex>(ARG_FILTER) {
+        input_files.retain(|input_path| filter.is_match(&input_path.to_string_lossy()));
+    }
+    let number_of_skipped_files = number_of_found_files - input_files.len();
 
     // Ensure that there are even any test files
     if input_files.is_empty() {
-        println!("No test files found in '{}'", directory);
+        print_report(
+            human_format,
+            &format!("No test files found in {directories_list}\n"),
+        );
+        if !human_format {
+            println!("{}", summary::json_summary(0, 0, &[]));
+        }
         process::exit(1);
     }
 
-    let mut failed_tests = Vec::new();
+    if human_format {
+        println!(
+            "Found {} test files in {directories_list}",
+            input_files.len()
+        );
+    }
+    if human_format && number_of_skipped_files > 0 {
+        println!("Skipped {number_of_skipped_files} tests not matching the filter");
+    }
 
-    println!("Found {} test files in '{}'", input_files.len(), directory);
-
-    let run_regex = RegexBuilder::new(r"^//\s*RUN:\s*(.*)$")
-        .multi_line(true)
-        .build()
-        .expect("Failed to build regex");
-    let binary_file_regex = RegexBuilder::new(r"\$\{\{(.+?)\}\}")
-        .build()
-        .expect("Failed to build regex");
-    let expect_failure_regex = RegexBuilder::new(r"^//\s*EXPECT-FAILURE\s*$")
-        .multi_line(true)
-        .build()
-        .expect("Failed to build regex");
-
-    for input_path in &input_files {
-        print!("Running test {}... ", input_path.display());
-
-        // Construct the output path, preserving the directory structure
-        let relative_path = input_path
-            .strip_prefix(&input_dir)
-            .expect("Failed to strip prefix");
-        let output_path = output_dir.join(relative_path).with_extension("out");
-
-        // Read the input file
-        let input = fs::read_to_string(input_path).expect("Failed to read input file");
-
-        // Extract run command from the input file
-        let Some(run_command) = run_regex
-            .captures(&input)
-            .and_then(|captures| captures.get(1))
-            .map(|m| m.as_str())
-        else {
-            println!("{}", "TEST ERROR".red());
-            println!("Missing run directive");
+    // List the executables the tests run, a wrong one is easier to spot here than in each failure
+    if human_format {
+        print_executables(&input_files);
+    }
 
-            failed_tests.push(input_path);
-            continue;
-        };
+    // Randomize the order to catch tests depending on each other
+    if matches.contains_id(ARG_SHUFFLE) {
+        let seed = matches
+            .get_one::<u64>(ARG_SHUFFLE)
+            .copied()
+            .unwrap_or_else(shuffle::random_seed);
 
-        // Extract executable from the run command
-        let Some(executable) = binary_file_regex
-            .captures(run_command)
-            .and_then(|capture| capture.get(1))
-            .map(|m| m.as_str())
-        else {
-            println!("{}", "TEST ERROR".red());
-            println!("Missing executable name in run directive");
-            println!("Run directive: '{}'", run_command);
+        if human_format {
+            println!("Shuffling tests with seed {seed}");
+        }
+        shuffle::shuffle(&mut input_files, seed);
+    }
 
-            failed_tests.push(input_path);
-            continue;
-        };
+    let start_time = Instant::now();
+    let results = run_tests(&context, &input_files, jobs);
+    let total_duration = start_time.elapsed();
+    let _ = fs::remove_dir_all(&context.temp_dir);
 
-        // Remove executable from the run command
-        let run_command = binary_file_regex.replace(run_command, "");
+    let mut failed_tests = Vec::new();
+    let mut number_of_expected_failures = 0;
+    let mut number_of_cached_tests = 0;
+    for (input_path, result) in input_files.iter().zip(&results) {
+        if human_format {
+            print!("Running test {}... {}", input_path.display(), result.log);
+        }
 
-        // Collect the command line arguments
-        let args = run_command.split_whitespace().collect::<Vec<_>>();
+        if !result.passed {
+            failed_tests.push((input_path, result.timed_out));
+        }
+        if result.expected_failure {
+            number_of_expected_failures += 1;
+        }
+        if result.cached {
+            number_of_cached_tests += 1;
+        }
+    }
 
-        // Check if the test is expected to fail
-        let expect_failure = expect_failure_regex.is_match(&input);
+    if human_format && number_of_cached_tests > 0 {
+        println!("\nSkipped {number_of_cached_tests} unchanged tests which passed before");
+    }
 
-        // Run executable on the input file
-        let Ok(mut command) = process::Command::cargo_bin(executable) else {
-            println!("{}", "TEST ERROR".red());
-            println!("Executable '{}' not found", executable);
+    // Remember the passed tests, failed ones are forgotten so they run again
+    if let Some(cache) = &mut context.cache {
+        for (input_path, result) in input_files.iter().zip(&results) {
+            cache.update(input_path, result.cache_key.filter(|_| result.passed));
+        }
 
-            failed_tests.push(input_path);
-            continue;
-        };
+        if let Err(error) = cache.save() {
+            print_report(
+                human_format,
+                &format!("Failed to write the test cache: {error}\n"),
+            );
+        }
+    }
 
-        let output = command
-            .arg(input_path.to_str().unwrap())
-            .args(args)
-            .output()
-            .expect("Failed to execute binary");
+    // Only the tests up to the first failure have results with --fail-fast
+    let number_of_run_tests = results.len();
+    if human_format && number_of_run_tests < input_files.len() {
+        let failed_test = &input_files[number_of_run_tests - 1];
 
-        // Extract status code
-        let Some(status_code) = output.status.code() else {
-            println!("{}", "TEST ERROR".red());
-            println!("Failed to extract status code");
-            continue;
-        };
+        println!(
+            "\nStopped after the first failure, skipped {} tests",
+            input_files.len() - number_of_run_tests
+        );
+        print_reproduction(&context, failed_test);
+    }
 
-        // Check the status code
-        if !expect_failure && status_code != 0 {
-            println!("{}", "FAIL".red());
-            println!("Test unexpectedly failed with status code: {status_code}");
+    if matches.get_flag(ARG_TIMINGS) {
+        print_report(
+            human_format,
+            &timings_report(&input_files, &results, total_duration),
+        );
+    }
 
-            failed_tests.push(input_path);
-            continue;
-        } else if expect_failure && status_code == 0 {
-            println!("{}", "FAIL".red());
-            println!("Test unexpectedly passed");
+    if let Some(junit_path) = matches.get_one::<PathBuf>(ARG_JUNIT) {
+        let test_cases = input_files
+            .iter()
+            .zip(&results)
+            .map(|(input_path, result)| JunitTestCase {
+                name: input_path.display().to_string(),
+                duration: result.duration,
+                failure: (!result.passed).then(|| result.log.clone()),
+            })
+            .collect::<Vec<_>>();
+        let report = junit::junit_report("rustcc", &test_cases, total_duration);
 
-            failed_tests.push(input_path);
-            continue;
+        if let Err(error) = fs::write(junit_path, report) {
+            print_report(
+                human_format,
+                &format!(
+                    "Failed to write JUnit report '{}': {error}\n",
+                    junit_path.display()
+                ),
+            );
+            process::exit(1);
         }
+    }
 
-        // Convert output to string
-        let stdout_str = String::from_utf8_lossy(&output.stdout);
-        let stderr_str = String::from_utf8_lossy(&output.stderr);
+    // Report expected output files whose test has been removed
+    let mut report = String::new();
+    let orphans_remaining = matches.get_flag(ARG_CHECK_ORPHANS)
+        && check_orphans(&context, matches.get_flag(ARG_PRUNE), &mut report);
 
-        let output_str = format!("{}{}", stderr_str, stdout_str);
+    // Report warning flags which aren't exercised by any test
+    let uncovered_flags =
+        matches.get_flag(ARG_FLAG_COVERAGE) && check_flag_coverage(&input_files, &mut report);
+    print_report(human_format, &report);
 
-        if update_baseline {
-            fs::create_dir_all(output_path.parent().unwrap())
-                .expect("Failed to create output directory");
-            fs::write(output_path, output_str).expect("Failed to write output file");
-            println!("{}", "UPDATED".yellow());
-        } else {
-            // Read the expected output
-            let Ok(expected_output) = fs::read_to_string(&output_path) else {
-                println!("{}", "TEST ERROR".red());
-                println!("Expected output file '{}' not found", output_path.display());
+    if update_baseline {
+        if orphans_remaining || uncovered_flags {
+            process::exit(1);
+        }
 
-                failed_tests.push(input_path);
-                continue;
-            };
+        return;
+    }
 
-            // Compare the output
-            if output_str.trim() == expected_output.trim() {
-                println!("{}", "PASS".green());
-            } else {
-                println!("{}\n", "FAIL".red());
-                println!("Expected:\n{}", expected_output);
-                println!("Got:\n{}", output_str);
+    // Print the summary
+    if human_format {
+        print_summary(
+            number_of_run_tests,
+            number_of_expected_failures,
+            &failed_tests,
+        );
+    } else {
+        let failed_tests = failed_tests
+            .iter()
+            .map(|(path, timed_out)| (path.as_path(), *timed_out))
+            .collect::<Vec<_>>();
 
-                failed_tests.push(input_path);
-                continue;
-            }
-        }
+        println!(
+            "{}",
+            summary::json_summary(
+                number_of_run_tests,
+                number_of_expected_failures,
+                &failed_tests
+            )
+        );
     }
 
-    if update_baseline {
-        return;
+    if !failed_tests.is_empty() || orphans_remaining || uncovered_flags {
+        process::exit(1);
     }
+}
+
+/// Prints the colored summary of the test run and the list of failed tests
+fn print_summary(
+    number_of_run_tests: usize,
+    number_of_expected_failures: usize,
+    failed_tests: &[(&PathBuf, bool)],
+) {
+    let number_of_timeouts = failed_tests
+        .iter()
+        .filter(|(_, timed_out)| *timed_out)
+        .count();
 
-    // Print the summary
     println!("\nSummary:");
     println!(
         "Ran {} tests {} passed {} failed",
-        input_files.len(),
-        (input_files.len() - failed_tests.len()).to_string().green(),
+        number_of_run_tests,
+        (number_of_run_tests - number_of_expected_failures - failed_tests.len())
+            .to_string()
+            .green(),
         failed_tests.len().to_string().red()
     );
+    if number_of_expected_failures > 0 {
+        println!(
+            "{} tests failed as expected (XFAIL)",
+            number_of_expected_failures.to_string().yellow()
+        );
+    }
+    if number_of_timeouts > 0 {
+        println!(
+            "{} of the failed tests timed out",
+            number_of_timeouts.to_string().red()
+        );
+    }
 
     // Print the failed tests
     if !failed_tests.is_empty() {
         println!("\nFailed tests:");
-        for test in failed_tests {
-            println!("{}", test.display());
+        for (test, timed_out) in failed_tests {
+            if *timed_out {
+                println!("{} (timed out)", test.display());
+            } else {
+                println!("{}", test.display());
+            }
+        }
+    }
+}
+
+/// Prints the distinct executables referenced by the run directives of the tests and warns about the
+/// ones which aren't a cargo binary, e.g. because of a typo copied into many tests
+fn print_executables(input_files: &[PathBuf]) {
+    // Count the tests referencing each executable, sorted by name so the output is deterministic
+    let mut executables = BTreeMap::<String, usize>::new();
+    for input_path in input_files {
+        let Ok(run_directives) = fs::read_to_string(input_path)
+            .map_err(|error| error.to_string())
+            .and_then(|input| RunDirective::parse_all(&input))
+        else {
+            continue;
+        };
+
+        let names = run_directives
+            .into_iter()
+            .map(|directive| directive.executable)
+            .collect::<HashSet<_>>();
+        for name in names.into_iter().filter(|name| name != BUILTIN_CAT) {
+            *executables.entry(name).or_default() += 1;
         }
+    }
 
-        // Exit with an error code
-        process::exit(1);
+    if executables.is_empty() {
+        return;
+    }
+
+    println!(
+        "Executables: {}",
+        executables.keys().cloned().collect::<Vec<_>>().join(", ")
+    );
+
+    for (name, number_of_tests) in &executables {
+        if cargo_bin(name).is_none() {
+            println!(
+                "{} executable '{name}' referenced by {number_of_tests} tests is not a cargo binary",
+                "Warning:".yellow()
+            );
+        }
     }
 }
 
-// Function to recursively find all `.c` files in a directory
-fn find_c_files(dir: &Path) -> Vec<PathBuf> {
+/// Prints a report besides the summary. In the JSON format it goes to stderr, so stdout only
+/// contains the JSON summary.
+fn print_report(human_format: bool, report: &str) {
+    if human_format {
+        print!("{report}");
+    } else {
+        eprint!("{report}");
+    }
+}
+
+/// Writes all warning flags of rustcc which aren't passed to `-W` by any of the tests, either
+/// enabling or disabling them, into `report`. Returns whether any flags aren't covered.
+fn check_flag_coverage(input_files: &[PathBuf], report: &mut String) -> bool {
+    let output = process::Command::cargo_bin("rustcc")
+        .expect("Executable 'rustcc' not found")
+        .arg("--print-diagnostic-flags")
+        .output()
+        .expect("Failed to execute binary");
+    let flags = String::from_utf8_lossy(&output.stdout).into_owned();
+
+    // Collect the flags passed by any run directive
+    let covered_flags = input_files
+        .iter()
+        .filter_map(|input_path| fs::read_to_string(input_path).ok())
+        .filter_map(|input| RunDirective::parse_all(&input).ok())
+        .flatten()
+        .flat_map(|directive| warning_flags(&directive.arguments))
+        .collect::<HashSet<_>>();
+
+    let uncovered_flags = flags
+        .lines()
+        .filter(|flag| !covered_flags.contains(*flag))
+        .collect::<Vec<_>>();
+
+    if uncovered_flags.is_empty() {
+        return false;
+    }
+
+    let _ = writeln!(report, "\nWarning flags not covered by any test:");
+    for flag in &uncovered_flags {
+        let _ = writeln!(report, "{flag}");
+    }
+
+    true
+}
+
+/// Returns the warning flags configured by the arguments as `-W<flag>`, for both `-W<flag>` and
+/// `-W <flag>` as well as their `-Wno-<flag>` variants
+fn warning_flags(arguments: &[String]) -> Vec<String> {
+    let mut flags = Vec::new();
+
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let option = match argument.as_str() {
+            "-W" => arguments.next().map(String::as_str),
+            argument => argument.strip_prefix("-W"),
+        };
+
+        if let Some(option) = option {
+            let flag = option.strip_prefix("no-").unwrap_or(option);
+            flags.push(format!("-W{flag}"));
+        }
+    }
+
+    flags
+}
+
+/// Writes all expected output and error files without a corresponding input file into `report` and
+/// deletes them if `prune` is set. Returns whether any orphaned files remain.
+fn check_orphans(context: &TestContext, prune: bool, report: &mut String) -> bool {
+    let orphans = context
+        .directories
+        .iter()
+        .flat_map(|directory| {
+            find_files(&directory.output_dir, "out")
+                .into_iter()
+                .chain(find_files(&directory.output_dir, "err"))
+                .filter(|output_path| {
+                    let relative_path = output_path
+                        .strip_prefix(&directory.output_dir)
+                        .expect("Failed to strip prefix");
+
+                    !directory
+                        .input_dir
+                        .join(relative_path)
+                        .with_extension("c")
+                        .exists()
+                })
+        })
+        .collect::<Vec<_>>();
+
+    if orphans.is_empty() {
+        return false;
+    }
+
+    let _ = writeln!(report, "\nOrphaned expected output files:");
+    for orphan in &orphans {
+        if prune {
+            fs::remove_file(orphan).expect("Failed to remove orphaned file");
+            let _ = writeln!(report, "{} {}", "REMOVED".yellow(), orphan.display());
+        } else {
+            let _ = writeln!(report, "{}", orphan.display());
+        }
+    }
+
+    !prune
+}
+
+/// Prints the commands run by the test, so a failure can be reproduced by hand
+fn print_reproduction(context: &TestContext, input_path: &Path) {
+    let Ok(run_directives) = fs::read_to_string(input_path)
+        .map_err(|error| error.to_string())
+        .and_then(|input| RunDirective::parse_all(&input))
+    else {
+        return;
+    };
+
+    println!("Reproduce with:");
+    for directive in &run_directives {
+        if directive.executable == BUILTIN_CAT {
+            let arguments =
+                directive.substituted_arguments(input_path, &context.temp_path(input_path));
+            println!("  {BUILTIN_CAT} {}", arguments.join(" "));
+        } else if let Some(command) = build_command(context, input_path, directive) {
+            println!("  {}", format_command(&command));
+        }
+    }
+}
+
+/// Runs all tests using up to `jobs` threads and returns their results in the same order as
+/// `input_files`. With `fail_fast` only the results up to and including the first failure are
+/// returned.
+fn run_tests(context: &TestContext, input_files: &[PathBuf], jobs: usize) -> Vec<TestResult> {
+    let next_test = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let results = Mutex::new(
+        input_files
+            .iter()
+            .map(|_| None)
+            .collect::<Vec<Option<TestResult>>>(),
+    );
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(input_files.len()) {
+            scope.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    let index = next_test.fetch_add(1, Ordering::Relaxed);
+                    let Some(input_path) = input_files.get(index) else {
+                        break;
+                    };
+
+                    let result = run_test(context, input_path);
+                    if context.fail_fast && !result.passed {
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    // Tests are started in order, so the ones which were skipped after a failure are at the end
+    let mut results = results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map_while(|result| result)
+        .collect::<Vec<_>>();
+
+    // Tests running in parallel might have finished after the first failure, drop them
+    if context.fail_fast {
+        if let Some(first_failure) = results.iter().position(|result| !result.passed) {
+            results.truncate(first_failure + 1);
+        }
+    }
+
+    results
+}
+
+fn run_test(context: &TestContext, input_path: &Path) -> TestResult {
+    let start_time = Instant::now();
+
+    // Skip the test if it passed before and nothing it depends on changed since
+    let cache_key = context
+        .cache
+        .as_ref()
+        .and_then(|_| cache_key(context, input_path));
+    if context
+        .cache
+        .as_ref()
+        .zip(cache_key)
+        .is_some_and(|(cache, key)| cache.contains(input_path, key))
+    {
+        return TestResult {
+            log: format!("{}\n", "CACHED".green()),
+            passed: true,
+            timed_out: false,
+            expected_failure: false,
+            cached: true,
+            cache_key,
+            duration: start_time.elapsed(),
+        };
+    }
+
+    // A test can allow a different number of retries than the default and can be expected to fail
+    let (retries, xfail) = match fs::read_to_string(input_path)
+        .map_err(|error| error.to_string())
+        .and_then(|input| {
+            let retries = run_directive::parse_allow_retries(&input)?;
+            Ok((retries, run_directive::parse_xfail(&input)))
+        }) {
+        Ok((retries, xfail)) => (retries.unwrap_or(context.retries), xfail),
+        Err(error) => {
+            return TestResult {
+                log: format!("{}\n{error}\n", "TEST ERROR".red()),
+                passed: false,
+                timed_out: false,
+                expected_failure: false,
+                cached: false,
+                cache_key: None,
+                duration: start_time.elapsed(),
+            };
+        }
+    };
+
+    // The output of an expected failure is wrong by definition, so it must not become the baseline
+    if xfail && context.update_baseline {
+        return TestResult {
+            log: format!("{}\n", "SKIPPED (XFAIL)".yellow()),
+            passed: true,
+            timed_out: false,
+            expected_failure: false,
+            cached: false,
+            cache_key: None,
+            duration: start_time.elapsed(),
+        };
+    }
+
+    // Run the test again until it passes or it ran out of retries, only the last log is kept
+    let mut attempts = 0;
+    let (mut log, outcome) = loop {
+        attempts += 1;
+
+        let mut log = String::new();
+        let outcome = execute_test(context, input_path, &mut log);
+        if outcome == TestOutcome::Passed || xfail || attempts > retries {
+            break (log, outcome);
+        }
+    };
+    let duration = start_time.elapsed();
+    let passed = outcome == TestOutcome::Passed;
+
+    // An expected failure only reports whether it still fails, passing means the XFAIL is stale
+    if xfail {
+        let log = if passed {
+            format!(
+                "{}\nThe test is marked XFAIL but passed, remove the XFAIL line\n",
+                "XPASS".red()
+            )
+        } else {
+            format!("{}\n", "XFAIL".yellow())
+        };
+
+        return TestResult {
+            log,
+            passed: !passed,
+            timed_out: false,
+            expected_failure: !passed,
+            cached: false,
+            cache_key: None,
+            duration,
+        };
+    }
+
+    if attempts > 1 {
+        let outcome = if passed { "Passed" } else { "Failed" };
+        let _ = writeln!(log, "{outcome} after {attempts} attempts");
+    }
+
+    TestResult {
+        log,
+        passed,
+        timed_out: outcome == TestOutcome::TimedOut,
+        expected_failure: false,
+        cached: false,
+        cache_key,
+        duration,
+    }
+}
+
+/// Computes the key of the test for the `--cache`, it changes whenever the input, the expected
+/// output, the run directives or one of the executables they run changes. Executables are compared
+/// by their modification time. Returns `None` if the test can't be cached.
+fn cache_key(context: &TestContext, input_path: &Path) -> Option<u64> {
+    let input = fs::read_to_string(input_path).ok()?;
+    let run_directives = RunDirective::parse_all(&input).ok()?;
+
+    let mut hasher = StableHasher::default();
+    input.hash(&mut hasher);
+    context.runner.hash(&mut hasher);
+
+    // A missing expected output file is part of the key as well
+    let output_path = context.output_path(input_path);
+    for path in [
+        output_path.with_extension("out"),
+        output_path.with_extension("err"),
+    ] {
+        fs::read(path).ok().hash(&mut hasher);
+    }
+
+    // The directives are hashed after the environment variables have been substituted
+    for directive in &run_directives {
+        directive.hash(&mut hasher);
+
+        if directive.executable != BUILTIN_CAT {
+            let executable = cargo_bin_path(&directive.executable)?;
+            fs::metadata(executable)
+                .and_then(|metadata| metadata.modified())
+                .ok()?
+                .hash(&mut hasher);
+        }
+    }
+
+    Some(hasher.finish())
+}
+
+/// Returns the report of the slowest tests and the total time
+fn timings_report(
+    input_files: &[PathBuf],
+    results: &[TestResult],
+    total_duration: Duration,
+) -> String {
+    let mut timings = input_files
+        .iter()
+        .zip(results)
+        .map(|(input_path, result)| (input_path, result.duration))
+        .collect::<Vec<_>>();
+    timings.sort_by_key(|(_, duration)| Reverse(*duration));
+
+    let mut report = String::from("\nSlowest tests:\n");
+    for (input_path, duration) in timings.iter().take(NUMBER_OF_SLOWEST_TESTS) {
+        let _ = writeln!(report, "{:>10.2?} {}", duration, input_path.display());
+    }
+
+    let _ = writeln!(report, "\nTotal time: {:.2?}", total_duration);
+
+    report
+}
+
+/// Runs a single test, writing everything which should be printed into `log`, and returns how it
+/// ended
+fn execute_test(context: &TestContext, input_path: &Path, log: &mut String) -> TestOutcome {
+    let output_path = context.output_path(input_path);
+
+    // Create the directory of the `%t` path, the test creates the files themselves
+    let temp_path = context.temp_path(input_path);
+    fs::create_dir_all(temp_path.parent().unwrap()).expect("Failed to create temporary directory");
+
+    // Read the input file
+    let input = fs::read_to_string(input_path).expect("Failed to read input file");
+
+    // Extract all run directives from the input file
+    let run_directives = match RunDirective::parse_all(&input) {
+        Ok(run_directives) => run_directives,
+        Err(error) => {
+            let _ = writeln!(log, "{}", "TEST ERROR".red());
+            let _ = writeln!(log, "{error}");
+
+            return TestOutcome::Failed;
+        }
+    };
+
+    // Extract all check directives, tests using them don't have an expected output file
+    let checks = match CheckDirective::parse_all(&input) {
+        Ok(checks) => checks,
+        Err(error) => {
+            let _ = writeln!(log, "{}", "TEST ERROR".red());
+            let _ = writeln!(log, "{error}");
+
+            return TestOutcome::Failed;
+        }
+    };
+
+    // Check if the test is expected to fail
+    let expected_status = match ExpectedStatus::from_input(&context.expect_failure_regex, &input) {
+        Ok(expected_status) => expected_status,
+        Err(error) => {
+            let _ = writeln!(log, "{}", "TEST ERROR".red());
+            let _ = writeln!(log, "{error}");
+
+            return TestOutcome::Failed;
+        }
+    };
+
+    // Extract the content piped to the standard input of every run
+    let stdin = run_directive::parse_stdin(&input);
+
+    // Run every directive in sequence, all of them have to pass
+    let mut output_str = String::new();
+    let mut stdout_str = String::new();
+    let mut stderr_str = String::new();
+    for directive in &run_directives {
+        let run_output = match run_directive(
+            context,
+            input_path,
+            directive,
+            stdin.as_deref(),
+            expected_status,
+            log,
+        ) {
+            Ok(run_output) => run_output,
+            Err(outcome) => return outcome,
+        };
+
+        output_str.push_str(&run_output.stderr);
+        output_str.push_str(&run_output.stdout);
+        stdout_str.push_str(&run_output.stdout);
+        stderr_str.push_str(&run_output.stderr);
+    }
+
+    if !checks.is_empty() {
+        // Match the check directives against the output
+        if let Err(error) = file_check::check_output(&checks, &output_str) {
+            let _ = writeln!(log, "{}\n", "FAIL".red());
+            let _ = writeln!(log, "{error}");
+            let _ = writeln!(log, "Got:\n{}", output_str);
+
+            return TestOutcome::Failed;
+        }
+
+        let _ = writeln!(log, "{}", "PASS".green());
+
+        return TestOutcome::Passed;
+    }
+
+    // Tests with an `.err` file compare stdout against the `.out` file and stderr against the
+    // `.err` file, all other tests compare the combined output against the `.out` file
+    let error_path = output_path.with_extension("err");
+    let baselines = if error_path.exists() {
+        vec![(output_path, stdout_str), (error_path, stderr_str)]
+    } else {
+        vec![(output_path, output_str)]
+    }
+    .into_iter()
+    .map(|(path, actual)| (path, normalize_paths(&actual, input_path)))
+    .collect::<Vec<_>>();
+
+    if context.update_baseline {
+        let stale_baselines = baselines
+            .iter()
+            .filter(|(path, actual)| {
+                context.force
+                    || read_baseline(path, input_path)
+                        .is_none_or(|expected| expected.trim() != actual.trim())
+            })
+            .collect::<Vec<_>>();
+
+        if stale_baselines.is_empty() {
+            let _ = writeln!(log, "{}", "PASS".green());
+        } else if context.dry_run {
+            let _ = writeln!(log, "{}", "WOULD UPDATE".yellow());
+            for (path, actual) in stale_baselines {
+                let expected = read_baseline(path, input_path).unwrap_or_default();
+                let _ = write!(log, "{}", diff::format_diff(expected.trim(), actual.trim()));
+            }
+        } else {
+            for (path, actual) in stale_baselines {
+                fs::create_dir_all(path.parent().unwrap())
+                    .expect("Failed to create output directory");
+                fs::write(path, actual).expect("Failed to write output file");
+            }
+            let _ = writeln!(log, "{}", "UPDATED".yellow());
+        }
+
+        return TestOutcome::Passed;
+    }
+
+    for (path, actual) in &baselines {
+        // Read the expected output
+        let Some(expected) = read_baseline(path, input_path) else {
+            let _ = writeln!(log, "{}", "TEST ERROR".red());
+            let _ = writeln!(log, "Expected output file '{}' not found", path.display());
+
+            return TestOutcome::Failed;
+        };
+
+        // Compare the output
+        if actual.trim() != expected.trim() {
+            let _ = writeln!(log, "{}\n", "FAIL".red());
+            let _ = writeln!(
+                log,
+                "Diff of '{}' (- expected, + actual):",
+                path.file_name().unwrap().to_string_lossy()
+            );
+            let _ = write!(log, "{}", diff::format_diff(expected.trim(), actual.trim()));
+
+            return TestOutcome::Failed;
+        }
+    }
+
+    let _ = writeln!(log, "{}", "PASS".green());
+
+    TestOutcome::Passed
+}
+
+/// The placeholder replacing the path of the input file in the output of a test
+const INPUT_PATH_PLACEHOLDER: &str = "%s";
+
+/// Replaces the path of the input file with [`INPUT_PATH_PLACEHOLDER`], so the expected output
+/// doesn't depend on where the repository is checked out or how the tests directory was passed
+fn normalize_paths(output: &str, input_path: &Path) -> String {
+    let mut paths = vec![input_path.display().to_string()];
+    if let Ok(absolute_path) = fs::canonicalize(input_path) {
+        paths.push(absolute_path.display().to_string());
+    }
+
+    // Replace the longest path first, since the relative path may be a suffix of the absolute one
+    paths.sort_by_key(|path| Reverse(path.len()));
+
+    paths.iter().fold(output.to_string(), |output, path| {
+        output.replace(path, INPUT_PATH_PLACEHOLDER)
+    })
+}
+
+/// Reads an expected output file with its paths normalized, `None` if it doesn't exist
+fn read_baseline(path: &Path, input_path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|expected| normalize_paths(&expected, input_path))
+}
+
+/// The output of a single run directive
+struct RunOutput {
+    stdout: String,
+    stderr: String,
+}
+
+/// Runs a single directive on the input file, feeding `stdin` to its standard input, and returns its
+/// output, or how the test failed
+fn run_directive(
+    context: &TestContext,
+    input_path: &Path,
+    directive: &RunDirective,
+    stdin: Option<&str>,
+    expected_status: ExpectedStatus,
+    log: &mut String,
+) -> Result<RunOutput, TestOutcome> {
+    if directive.executable == BUILTIN_CAT {
+        return run_builtin_cat(context, input_path, directive, log);
+    }
+
+    // Run executable on the input file
+    let Some(mut command) = build_command(context, input_path, directive) else {
+        let _ = writeln!(log, "{}", "TEST ERROR".red());
+        let _ = writeln!(log, "Executable '{}' not found", directive.executable);
+
+        return Err(TestOutcome::Failed);
+    };
+
+    if context.verbose {
+        let working_directory = env::current_dir().unwrap_or_default();
+
+        let _ = writeln!(log, "\n  Command: {}", format_command(&command));
+        let _ = writeln!(log, "  Working directory: {}", working_directory.display());
+    }
+
+    command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = command.spawn().expect("Failed to execute binary");
+
+    // Write the input from a separate thread so a child producing a lot of output can't deadlock.
+    // Errors are ignored since the child is free to exit without reading its input.
+    let stdin_writer = child
+        .stdin
+        .take()
+        .zip(stdin)
+        .map(|(mut child_stdin, stdin)| {
+            let stdin = stdin.to_string();
+            thread::spawn(move || {
+                let _ = io::Write::write_all(&mut child_stdin, stdin.as_bytes());
+            })
+        });
+
+    let Some(output) = wait_with_timeout(child, context.timeout) else {
+        let timeout = context.timeout.unwrap_or_default();
+        let _ = writeln!(log, "{}", "TIMEOUT".red());
+        let _ = writeln!(log, "Test timed out after {} seconds", timeout.as_secs());
+
+        return Err(TestOutcome::TimedOut);
+    };
+    if let Some(stdin_writer) = stdin_writer {
+        let _ = stdin_writer.join();
+    }
+
+    // Extract status code
+    let Some(status_code) = output.status.code() else {
+        let _ = writeln!(log, "{}", "TEST ERROR".red());
+        let _ = writeln!(log, "Failed to extract status code");
+
+        return Err(TestOutcome::Failed);
+    };
+
+    // Check the status code
+    if expected_status == ExpectedStatus::Success && status_code != 0 {
+        let _ = writeln!(log, "{}", "FAIL".red());
+        let _ = writeln!(
+            log,
+            "Test unexpectedly failed with status code: {status_code}"
+        );
+
+        return Err(TestOutcome::Failed);
+    } else if expected_status != ExpectedStatus::Success && status_code == 0 {
+        let _ = writeln!(log, "{}", "FAIL".red());
+        let _ = writeln!(log, "Test unexpectedly passed");
+
+        return Err(TestOutcome::Failed);
+    } else if let ExpectedStatus::ExitCode(expected_code) = expected_status {
+        if status_code != expected_code {
+            let _ = writeln!(log, "{}", "FAIL".red());
+            let _ = writeln!(
+                log,
+                "Test failed with status code {status_code} instead of {expected_code}"
+            );
+
+            return Err(TestOutcome::Failed);
+        }
+    }
+
+    // Convert output to string
+    Ok(RunOutput {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+/// Runs the builtin `cat`, which outputs the content of all files passed to it
+fn run_builtin_cat(
+    context: &TestContext,
+    input_path: &Path,
+    directive: &RunDirective,
+    log: &mut String,
+) -> Result<RunOutput, TestOutcome> {
+    let mut stdout = String::new();
+
+    for path in directive.substituted_arguments(input_path, &context.temp_path(input_path)) {
+        match fs::read_to_string(&path) {
+            Ok(content) => stdout.push_str(&content),
+            Err(error) => {
+                let _ = writeln!(log, "{}", "FAIL".red());
+                let _ = writeln!(log, "{BUILTIN_CAT}: Failed to read '{path}': {error}");
+
+                return Err(TestOutcome::Failed);
+            }
+        }
+    }
+
+    Ok(RunOutput {
+        stdout,
+        stderr: String::new(),
+    })
+}
+
+/// Waits for the child to exit and collects its output, killing it and returning `None` if it runs
+/// longer than `timeout`
+fn wait_with_timeout(
+    mut child: process::Child,
+    timeout: Option<Duration>,
+) -> Option<process::Output> {
+    let Some(timeout) = timeout else {
+        return Some(child.wait_with_output().expect("Failed to wait for binary"));
+    };
+
+    // Read the output from separate threads so a child filling a pipe can't block forever
+    let read_pipe = |pipe: Option<Box<dyn io::Read + Send>>| {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    };
+    let stdout_reader = read_pipe(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+    let stderr_reader = read_pipe(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().expect("Failed to wait for binary") {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+
+            // The readers are left behind, a grandchild of a runner may still hold the pipes open
+            return None;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    Some(process::Output {
+        status,
+        stdout: stdout_reader.join().unwrap_or_default(),
+        stderr: stderr_reader.join().unwrap_or_default(),
+    })
+}
+
+/// Builds the command running the directive on the input file, `None` if the executable doesn't exist
+fn build_command(
+    context: &TestContext,
+    input_path: &Path,
+    directive: &RunDirective,
+) -> Option<process::Command> {
+    let executable = cargo_bin(&directive.executable)?;
+
+    // The wrapper runs the resolved executable
+    let mut command = match context.runner.split_first() {
+        Some((runner, runner_arguments)) => {
+            let mut command = process::Command::new(runner);
+            command.args(runner_arguments).arg(executable.get_program());
+            command
+        }
+        None => executable,
+    };
+
+    command.args(directive.command_line_arguments(input_path, &context.temp_path(input_path)));
+
+    Some(command)
+}
+
+/// Returns a command running the cargo binary of this workspace with the given name, `None` if there
+/// is no such binary. Unlike `Command::cargo_bin` this never panics for unknown names.
+fn cargo_bin(name: &str) -> Option<process::Command> {
+    cargo_bin_path(name)?;
+
+    process::Command::cargo_bin(name).ok()
+}
+
+/// Returns the path of the cargo binary of this workspace with the given name, `None` if there is no
+/// such binary
+fn cargo_bin_path(name: &str) -> Option<PathBuf> {
+    let path = env::var_os(format!("CARGO_BIN_EXE_{name}"))
+        .map(PathBuf::from)
+        .or_else(|| {
+            // The test-driver is built into the same directory as the other binaries
+            let directory = env::current_exe().ok()?.parent()?.to_path_buf();
+            Some(directory.join(format!("{name}{}", env::consts::EXE_SUFFIX)))
+        })?;
+
+    path.is_file().then_some(path)
+}
+
+/// Formats a command as it would be typed into a shell
+fn format_command(command: &process::Command) -> String {
+    iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|argument| argument.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Function to recursively find all files with the given extension in a directory
+fn find_files(dir: &Path, extension: &str) -> Vec<PathBuf> {
     let mut files = Vec::new();
     let mut dirs = vec![dir.to_path_buf()];
 
@@ -225,12 +1448,15 @@ fn find_c_files(dir: &Path) -> Vec<PathBuf> {
 
                 if path.is_dir() {
                     dirs.push(path);
-                } else if path.extension().and_then(|s| s.to_str()) == Some("c") {
+                } else if path.extension().and_then(|s| s.to_str()) == Some(extension) {
                     files.push(path);
                 }
             }
         }
     }
 
+    // Sort the files so the tests always run in the same order
+    files.sort();
+
     files
 }
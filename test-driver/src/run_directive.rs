@@ -0,0 +1,322 @@
+use regex::{Captures, Regex, RegexBuilder};
+use std::{env, ffi::OsString, path::Path, sync::LazyLock};
+
+static RUN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    RegexBuilder::new(r"^//\s*RUN:\s*(.*)$")
+        .multi_line(true)
+        .build()
+        .expect("Failed to build regex")
+});
+
+static BINARY_FILE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    RegexBuilder::new(r"\$\{\{(.+?)\}\}")
+        .build()
+        .expect("Failed to build regex")
+});
+
+static ENV_VAR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    RegexBuilder::new(r"%\{ENV:([A-Za-z_][A-Za-z0-9_]*)\}")
+        .build()
+        .expect("Failed to build regex")
+});
+
+static STDIN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    RegexBuilder::new(r"^//\s*STDIN:[ \t]?(.*)$")
+        .multi_line(true)
+        .build()
+        .expect("Failed to build regex")
+});
+
+/// Collects the content of all `// STDIN: <line>` lines of a test input, which is piped to the
+/// standard input of every run. Returns `None` if there are no such lines.
+pub fn parse_stdin(input: &str) -> Option<String> {
+    let lines = STDIN_REGEX
+        .captures_iter(input)
+        .filter_map(|captures| captures.get(1))
+        .map(|m| format!("{}\n", m.as_str()))
+        .collect::<Vec<_>>();
+
+    (!lines.is_empty()).then(|| lines.concat())
+}
+
+static ALLOW_RETRIES_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    RegexBuilder::new(r"^//\s*ALLOW-RETRIES:\s*(\S+)\s*$")
+        .multi_line(true)
+        .build()
+        .expect("Failed to build regex")
+});
+
+/// Parses the `// ALLOW-RETRIES: <count>` line of a test input, `None` if there is no such line
+pub fn parse_allow_retries(input: &str) -> Result<Option<u32>, String> {
+    let Some(captures) = ALLOW_RETRIES_REGEX.captures(input) else {
+        return Ok(None);
+    };
+
+    captures[1]
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("Invalid retry count '{}' in ALLOW-RETRIES", &captures[1]))
+}
+
+static XFAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    RegexBuilder::new(r"^//\s*XFAIL\s*$")
+        .multi_line(true)
+        .build()
+        .expect("Failed to build regex")
+});
+
+/// Whether the test input contains an `// XFAIL` line, marking a test which is expected to fail
+/// because it exercises something not implemented yet
+pub fn parse_xfail(input: &str) -> bool {
+    XFAIL_REGEX.is_match(input)
+}
+
+/// The placeholder for the path of the input file in the arguments of a run directive
+const INPUT_PATH_SUBSTITUTION: &str = "%s";
+
+/// The placeholder for a temporary path unique to the test in the arguments of a run directive
+const TEMP_PATH_SUBSTITUTION: &str = "%t";
+
+/// A single `// RUN: ${{binary}} <arguments>` line of a test input.
+///
+/// The arguments may reference environment variables as `%{ENV:VAR}`. They are substituted after
+/// the `${{binary}}` has been extracted, so a variable can't change the executable, and before the
+/// arguments are split on whitespace, so a variable can expand to multiple arguments.
+///
+/// Like in LLVM's lit, `%s` stands for the input file and `%t` for a temporary path unique to the
+/// test, see [`RunDirective::substituted_arguments`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RunDirective {
+    /// The name of the cargo binary to execute
+    pub executable: String,
+    /// The command line arguments passed after the input file
+    pub arguments: Vec<String>,
+}
+
+impl RunDirective {
+    /// Parses the command of a run directive, i.e. everything after `RUN:`
+    pub fn parse(run_command: &str) -> Result<Self, String> {
+        Self::parse_with_env(run_command, |name| env::var(name).ok())
+    }
+
+    /// Like [`RunDirective::parse`] but looks up environment variables using `env_var`
+    fn parse_with_env<F: Fn(&str) -> Option<String>>(
+        run_command: &str,
+        env_var: F,
+    ) -> Result<Self, String> {
+        // Extract executable from the run command
+        let Some(executable) = BINARY_FILE_REGEX
+            .captures(run_command)
+            .and_then(|capture| capture.get(1))
+            .map(|m| m.as_str().to_string())
+        else {
+            return Err(format!(
+                "Missing executable name in run directive\nRun directive: '{run_command}'"
+            ));
+        };
+
+        // Remove executable from the run command
+        let run_command = BINARY_FILE_REGEX.replace(run_command, "");
+
+        // Substitute environment variables
+        let mut missing_env_var = None;
+        let run_command = ENV_VAR_REGEX.replace_all(&run_command, |captures: &Captures| {
+            env_var(&captures[1]).unwrap_or_else(|| {
+                missing_env_var.get_or_insert_with(|| captures[1].to_string());
+                String::new()
+            })
+        });
+        if let Some(name) = missing_env_var {
+            return Err(format!(
+                "Environment variable '{name}' used in run directive is not set"
+            ));
+        }
+
+        // Collect the command line arguments
+        let arguments = run_command.split_whitespace().map(str::to_string).collect();
+
+        Ok(Self {
+            executable,
+            arguments,
+        })
+    }
+
+    /// Whether the arguments place the input file themselves using `%s`. Otherwise the input file
+    /// is passed as the first argument.
+    pub fn uses_input_path(&self) -> bool {
+        self.arguments
+            .iter()
+            .any(|argument| argument.contains(INPUT_PATH_SUBSTITUTION))
+    }
+
+    /// Returns the arguments with `%s` replaced by `input_path` and `%t` by `temp_path`
+    pub fn substituted_arguments(&self, input_path: &Path, temp_path: &Path) -> Vec<String> {
+        self.arguments
+            .iter()
+            .map(|argument| {
+                argument
+                    .replace(INPUT_PATH_SUBSTITUTION, &input_path.display().to_string())
+                    .replace(TEMP_PATH_SUBSTITUTION, &temp_path.display().to_string())
+            })
+            .collect()
+    }
+
+    /// Returns the arguments the executable is run with. Unless the arguments place the input file
+    /// using `%s` it is passed as the first argument.
+    pub fn command_line_arguments(&self, input_path: &Path, temp_path: &Path) -> Vec<OsString> {
+        let input_argument = (!self.uses_input_path()).then(|| input_path.as_os_str().to_owned());
+
+        input_argument
+            .into_iter()
+            .chain(
+                self.substituted_arguments(input_path, temp_path)
+                    .into_iter()
+                    .map(OsString::from),
+            )
+            .collect()
+    }
+
+    /// Parses all run directives of a test input in the order they appear
+    pub fn parse_all(input: &str) -> Result<Vec<Self>, String> {
+        let directives = RUN_REGEX
+            .captures_iter(input)
+            .filter_map(|captures| captures.get(1))
+            .map(|m| Self::parse(m.as_str()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if directives.is_empty() {
+            return Err("Missing run directive".to_string());
+        }
+
+        Ok(directives)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let directive = RunDirective::parse("${{rustcc}} --print-tokens -W error").unwrap();
+
+        assert_eq!(directive.executable, "rustcc");
+        assert_eq!(directive.arguments, ["--print-tokens", "-W", "error"]);
+    }
+
+    #[test]
+    fn test_parse_missing_executable() {
+        assert!(RunDirective::parse("rustcc --print-tokens").is_err());
+    }
+
+    #[test]
+    fn test_parse_env_var() {
+        let env_var = |name: &str| (name == "FLAGS").then(|| "-W error --print-ast".to_string());
+
+        let directive =
+            RunDirective::parse_with_env("${{rustcc}} %{ENV:FLAGS} --print-ir", env_var).unwrap();
+
+        assert_eq!(directive.executable, "rustcc");
+        assert_eq!(
+            directive.arguments,
+            ["-W", "error", "--print-ast", "--print-ir"]
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_env_var() {
+        let result = RunDirective::parse_with_env("${{rustcc}} %{ENV:MISSING}", |_| None);
+
+        assert_eq!(
+            result,
+            Err("Environment variable 'MISSING' used in run directive is not set".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_env_var_cannot_change_executable() {
+        let env_var = |_: &str| Some("${{other}}".to_string());
+
+        let directive = RunDirective::parse_with_env("${{rustcc}} %{ENV:FLAGS}", env_var).unwrap();
+
+        assert_eq!(directive.executable, "rustcc");
+        assert_eq!(directive.arguments, ["${{other}}"]);
+    }
+
+    #[test]
+    fn test_parse_all_multiple_lines() {
+        let input = "// RUN: ${{rustcc}} --print-tokens\n//RUN: ${{rustcc}} --print-ast\nint x;\n";
+
+        let directives = RunDirective::parse_all(input).unwrap();
+
+        assert_eq!(directives.len(), 2);
+        assert_eq!(directives[0].arguments, ["--print-tokens"]);
+        assert_eq!(directives[1].arguments, ["--print-ast"]);
+    }
+
+    #[test]
+    fn test_substituted_arguments() {
+        let directive = RunDirective::parse("${{rustcc}} %s -o %t.ll --print-ir").unwrap();
+
+        assert!(directive.uses_input_path());
+        assert_eq!(
+            directive.substituted_arguments(Path::new("input/test.c"), Path::new("/tmp/test.tmp")),
+            ["input/test.c", "-o", "/tmp/test.tmp.ll", "--print-ir"]
+        );
+
+        let directive = RunDirective::parse("${{rustcc}} -o %t").unwrap();
+        assert!(!directive.uses_input_path());
+    }
+
+    #[test]
+    fn test_command_line_arguments() {
+        let input_path = Path::new("input/test.c");
+        let temp_path = Path::new("/tmp/test.tmp");
+
+        let directive = RunDirective::parse("${{rustcc}} -o %t").unwrap();
+        assert_eq!(
+            directive.command_line_arguments(input_path, temp_path),
+            ["input/test.c", "-o", "/tmp/test.tmp"]
+        );
+
+        let directive = RunDirective::parse("${{rustcc}} -o %t %s").unwrap();
+        assert_eq!(
+            directive.command_line_arguments(input_path, temp_path),
+            ["-o", "/tmp/test.tmp", "input/test.c"]
+        );
+    }
+
+    #[test]
+    fn test_parse_stdin() {
+        let input = "// RUN: ${{rustcc}}\n// STDIN: int main(void) {\n//STDIN:     return 0;\n// STDIN: }\n";
+
+        assert_eq!(
+            parse_stdin(input).as_deref(),
+            Some("int main(void) {\n    return 0;\n}\n")
+        );
+        assert_eq!(parse_stdin("// RUN: ${{rustcc}}\n"), None);
+    }
+
+    #[test]
+    fn test_parse_allow_retries() {
+        assert_eq!(parse_allow_retries("// ALLOW-RETRIES: 3\n"), Ok(Some(3)));
+        assert_eq!(parse_allow_retries("// RUN: ${{rustcc}}\n"), Ok(None));
+        assert!(parse_allow_retries("// ALLOW-RETRIES: many\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_xfail() {
+        assert!(parse_xfail("// RUN: ${{rustcc}}\n// XFAIL\n"));
+        assert!(parse_xfail("//XFAIL  \n"));
+        assert!(!parse_xfail("// RUN: ${{rustcc}}\n// XFAIL: later\n"));
+        assert!(!parse_xfail("// RUN: ${{rustcc}}\n"));
+    }
+
+    #[test]
+    fn test_parse_all_missing_directive() {
+        assert_eq!(
+            RunDirective::parse_all("int main(void) { return 0; }\n"),
+            Err("Missing run directive".to_string())
+        );
+    }
+}
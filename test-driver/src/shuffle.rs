@@ -0,0 +1,60 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small SplitMix64 pseudo random number generator, good enough for shuffling tests and
+/// reproducible across platforms for the same seed
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut value = self.0;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+}
+
+/// Returns a seed based on the current time, used when no explicit seed was given
+pub fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
+/// Shuffles `items` using the Fisher-Yates algorithm, the same seed always yields the same order
+pub fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut random = SplitMix64(seed);
+
+    for i in (1..items.len()).rev() {
+        let j = (random.next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_order() {
+        let mut first = (0..100).collect::<Vec<_>>();
+        let mut second = first.clone();
+
+        shuffle(&mut first, 42);
+        shuffle(&mut second, 42);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_shuffle_is_permutation() {
+        let mut items = (0..100).collect::<Vec<_>>();
+
+        shuffle(&mut items, 7);
+        assert_ne!(items, (0..100).collect::<Vec<_>>());
+
+        items.sort_unstable();
+        assert_eq!(items, (0..100).collect::<Vec<_>>());
+    }
+}
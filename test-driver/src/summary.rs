@@ -0,0 +1,60 @@
+use std::{fmt::Write, path::Path};
+
+/// Formats the summary of a test run as a single line JSON object, for scripts consuming the results
+pub fn json_summary(
+    number_of_run_tests: usize,
+    number_of_expected_failures: usize,
+    failed_tests: &[(&Path, bool)],
+) -> String {
+    let number_of_timeouts = failed_tests
+        .iter()
+        .filter(|(_, timed_out)| *timed_out)
+        .count();
+    let failed_paths = failed_tests
+        .iter()
+        .map(|(path, _)| serde_json::Value::from(path.display().to_string()).to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut summary = String::new();
+    let _ = write!(
+        summary,
+        "{{\"total\":{},\"passed\":{},\"expected_failures\":{},\"failed\":{},\"timed_out\":{},\"failed_tests\":[{}]}}",
+        number_of_run_tests,
+        number_of_run_tests - number_of_expected_failures - failed_tests.len(),
+        number_of_expected_failures,
+        failed_tests.len(),
+        number_of_timeouts,
+        failed_paths
+    );
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_summary() {
+        let failed_tests = [
+            (Path::new("input/fail.c"), false),
+            (Path::new("input/hang.c"), true),
+        ];
+
+        assert_eq!(
+            json_summary(6, 1, &failed_tests),
+            r#"{"total":6,"passed":3,"expected_failures":1,"failed":2,"timed_out":1,"failed_tests":["input/fail.c","input/hang.c"]}"#
+        );
+        assert_eq!(
+            json_summary(2, 0, &[]),
+            r#"{"total":2,"passed":2,"expected_failures":0,"failed":0,"timed_out":0,"failed_tests":[]}"#
+        );
+
+        // Paths are escaped like any other JSON string
+        assert!(
+            json_summary(1, 0, &[(Path::new("input/\"quoted\"\n.c"), false)])
+                .ends_with(r#""failed_tests":["input/\"quoted\"\n.c"]}"#)
+        );
+    }
+}